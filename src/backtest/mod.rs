@@ -0,0 +1,5 @@
+mod snapshot;
+mod engine;
+
+pub use snapshot::{PoolSnapshot, load_snapshots};
+pub use engine::{BacktestParams, BacktestReport, SprintResult, run_backtest};
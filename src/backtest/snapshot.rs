@@ -0,0 +1,95 @@
+use anyhow::{Result, Context, anyhow};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::meteora::{PoolInfo, PoolState};
+use crate::meteora::state::ActivationType;
+
+/// A pool's full state at one point in time, stored one JSON object per line
+///
+/// The raw pool account is kept so replays decode it exactly as `MeteoraClient` does live;
+/// the other fields are for reading the file by eye.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolSnapshot {
+    pub pool: String,
+    /// Unix timestamp (seconds) when the snapshot was taken
+    pub timestamp: i64,
+    pub slot: u64,
+    /// Token A vault balance
+    pub token_a_amount: u64,
+    /// Token B vault balance
+    pub token_b_amount: u64,
+    /// Raw token B per raw token A
+    pub price: f64,
+    /// Base fee in effect, in basis points
+    pub fee_bps: u16,
+    /// Swap transaction that triggered the snapshot, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Base64-encoded pool account data
+    pub account_data: String,
+}
+
+impl PoolSnapshot {
+    /// Capture a snapshot from live pool information
+    pub fn from_pool_info(pool_info: &PoolInfo, account_data: &[u8], timestamp: i64, slot: u64) -> Self {
+        Self {
+            pool: pool_info.address.to_string(),
+            timestamp,
+            slot,
+            token_a_amount: pool_info.token_a_amount,
+            token_b_amount: pool_info.token_b_amount,
+            price: crate::meteora::math::sqrt_price_to_price(pool_info.sqrt_price),
+            fee_bps: pool_info.fee_rate,
+            signature: None,
+            account_data: base64::engine::general_purpose::STANDARD.encode(account_data),
+        }
+    }
+
+    /// Decode the snapshot back into the pool information the strategy works with
+    pub fn pool_info(&self) -> Result<PoolInfo> {
+        let address: Pubkey = self.pool.parse()
+            .with_context(|| format!("Invalid pool address in snapshot: {}", self.pool))?;
+        let data = base64::engine::general_purpose::STANDARD.decode(&self.account_data)
+            .with_context(|| format!("Invalid account data in snapshot of pool {}", self.pool))?;
+        let state = PoolState::from_account_data(&data)?;
+
+        // Evaluate the fee scheduler at the point the snapshot was taken
+        let current_point = match state.activation_type() {
+            ActivationType::Slot => self.slot,
+            ActivationType::Timestamp => self.timestamp.max(0) as u64,
+        };
+
+        Ok(PoolInfo::from_state(address, state, self.token_a_amount, self.token_b_amount, current_point))
+    }
+}
+
+/// Load a JSONL snapshot file, grouped by pool and sorted by time
+pub fn load_snapshots(path: &Path) -> Result<BTreeMap<String, Vec<PoolSnapshot>>> {
+    let file = File::open(path)
+        .with_context(|| format!("Failed to open snapshot file: {}", path.display()))?;
+
+    let mut by_pool: BTreeMap<String, Vec<PoolSnapshot>> = BTreeMap::new();
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let snapshot: PoolSnapshot = serde_json::from_str(&line)
+            .map_err(|e| anyhow!("Invalid snapshot on line {} of {}: {}", index + 1, path.display(), e))?;
+        by_pool.entry(snapshot.pool.clone()).or_default().push(snapshot);
+    }
+
+    for snapshots in by_pool.values_mut() {
+        snapshots.sort_by_key(|s| (s.timestamp, s.slot));
+    }
+
+    Ok(by_pool)
+}
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use clap::Parser;
+use dotenv::dotenv;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use meteora_lp_sprinter::backtest::{self, BacktestParams};
+use meteora_lp_sprinter::config;
+use meteora_lp_sprinter::strategy::scoring::ScoringPipeline;
+
+/// Replay recorded pool snapshots through the sprint strategy, entirely offline
+#[derive(Parser, Debug)]
+#[command(name = "backtest")]
+struct Args {
+    /// JSONL file of recorded pool snapshots
+    #[arg(long)]
+    snapshots: PathBuf,
+
+    /// Sprint durations to compare, in seconds (defaults to position_duration_seconds)
+    #[arg(long = "duration")]
+    durations: Vec<u64>,
+
+    /// SOL per position (defaults to max_sol_per_position)
+    #[arg(long)]
+    amount_sol: Option<f64>,
+
+    /// Fee claim interval in seconds (defaults to fee_claim_interval_seconds)
+    #[arg(long)]
+    claim_interval: Option<u64>,
+
+    /// Minimum pool score to enter (defaults to `pool_filters.min_score` in the config)
+    #[arg(long)]
+    min_score: Option<f64>,
+}
+
+fn main() -> Result<()> {
+    dotenv().ok();
+    env_logger::init_from_env(env_logger::Env::default().filter_or("RUST_LOG", "warn"));
+
+    let args = Args::parse();
+    let config = config::load_config()?;
+
+    // Rank pools with the configured weights, as the live bot does
+    let scoring = Arc::new(ScoringPipeline::from_weights(&config.score_weights)?);
+
+    let recordings = backtest::load_snapshots(&args.snapshots)?;
+    println!("Loaded {} pools from {}", recordings.len(), args.snapshots.display());
+
+    let durations = if args.durations.is_empty() {
+        vec![config.position_duration_seconds]
+    } else {
+        args.durations.clone()
+    };
+
+    for duration in durations {
+        let params = BacktestParams {
+            position_duration_seconds: duration,
+            fee_claim_interval_seconds: args.claim_interval.unwrap_or(config.fee_claim_interval_seconds),
+            amount_sol: args.amount_sol.unwrap_or(config.max_sol_per_position),
+            slippage_bps: config.slippage_bps,
+            min_score: args.min_score.unwrap_or(config.pool_filters.min_score),
+            scoring: scoring.clone(),
+        };
+
+        println!();
+        print!("{}", backtest::run_backtest(&recordings, &params));
+    }
+
+    Ok(())
+}
@@ -1,4 +1,7 @@
 mod types;
 
 pub use types::Config;
+pub use types::PoolFilters;
+pub use types::RugGuardConfig;
+pub use types::ExitRules;
 pub use types::load_config; 
\ No newline at end of file
@@ -1,10 +1,15 @@
 use serde::{Deserialize, Serialize};
-use anyhow::{Result, Context};
+use anyhow::{Result, Context, anyhow};
+use std::collections::BTreeMap;
 use std::env;
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
 
+use crate::meteora::WSOL_MINT;
+use crate::strategy::{ExitPolicy, PoolCriteria};
+use crate::strategy::scoring::ScoringPipeline;
+
 #[cfg(feature = "telegram")]
 use crate::monitoring::telegram::TelegramConfig;
 
@@ -17,10 +22,67 @@ pub struct Config {
     pub keypair_path: String,
     /// Maximum amount of SOL to use per position
     pub max_sol_per_position: f64,
+    /// Smallest position opened when less than `max_sol_per_position` is left under the capital limit
+    #[serde(default = "default_min_sol_per_position")]
+    pub min_sol_per_position: f64,
+    /// Maximum number of positions open at the same time
+    #[serde(default = "default_max_concurrent_positions")]
+    pub max_concurrent_positions: usize,
+    /// Maximum total SOL committed across all open positions
+    #[serde(default = "default_max_total_sol_at_risk")]
+    pub max_total_sol_at_risk: f64,
     /// Number of seconds to hold a position before exiting
     pub position_duration_seconds: u64,
     /// How frequently to claim fees (in seconds)
     pub fee_claim_interval_seconds: u64,
+    /// Maximum slippage tolerated when entering or exiting a position (basis points)
+    #[serde(default = "default_slippage_bps")]
+    pub slippage_bps: u16,
+    /// Simulate positions against live pool state instead of sending transactions
+    #[serde(default)]
+    pub paper_trading: bool,
+    /// Record snapshots of every discovered pool for backtesting
+    #[serde(default)]
+    pub record_pools: bool,
+    /// How long to record each discovered pool (in seconds)
+    #[serde(default = "default_recording_window_seconds")]
+    pub recording_window_seconds: u64,
+    /// JSON Lines file pool snapshots are appended to
+    #[serde(default = "default_recording_path")]
+    pub recording_path: String,
+    /// How long a token price is reused before it is fetched again (in seconds)
+    #[serde(default = "default_price_cache_ttl_seconds")]
+    pub price_cache_ttl_seconds: u64,
+    /// Oldest cached price still used when a fresh one cannot be fetched (in seconds)
+    #[serde(default = "default_price_max_staleness_seconds")]
+    pub price_max_staleness_seconds: u64,
+    /// Price providers to try, in order: `onchain` (DAMM v2 pools) and/or `http`
+    #[serde(default = "default_price_providers")]
+    pub price_providers: Vec<String>,
+    /// Base URL of the Jupiter-style price API used by the `http` provider
+    #[serde(default = "default_price_api_url")]
+    pub price_api_url: String,
+    /// Timeout for price API requests (in milliseconds)
+    #[serde(default = "default_price_api_timeout_ms")]
+    pub price_api_timeout_ms: u64,
+    /// Weight of each pool scorer by name; scorers left out are not used
+    #[serde(default = "default_score_weights")]
+    pub score_weights: BTreeMap<String, f64>,
+    /// Rules a pool must pass before a sprint is started in it
+    #[serde(default)]
+    pub pool_filters: PoolFilters,
+    /// How long after analysis a pool is checked for a rug (in minutes)
+    #[serde(default = "default_rug_window_minutes")]
+    pub rug_window_minutes: u64,
+    /// TVL drop since analysis, in percent, that counts a pool as rugged
+    #[serde(default = "default_rug_tvl_drop_percent")]
+    pub rug_tvl_drop_percent: f64,
+    /// Emergency exit thresholds for open positions
+    #[serde(default)]
+    pub rug_guard: RugGuardConfig,
+    /// Rules that end a position early
+    #[serde(default)]
+    pub exit_rules: ExitRules,
     /// Path to the database file
     pub database_path: String,
     /// Whether to enable debug logging
@@ -32,6 +94,101 @@ pub struct Config {
     pub telegram: Option<DummyTelegramConfig>,
 }
 
+/// Pool filter rules, parsed into `PoolCriteria` when the config is loaded
+///
+/// Empty lists and missing limits do not restrict anything.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PoolFilters {
+    /// Minimum pool score (0-1)
+    pub min_score: f64,
+    /// Minimum total value locked, in SOL
+    pub min_tvl_sol: f64,
+    /// Most holders a non-quote token may have
+    pub max_token_holders: Option<u64>,
+    /// Quote tokens a pool may be priced in: WSOL, USDC and/or USDT (default: WSOL only)
+    ///
+    /// A pool's quote is its WSOL side if it has one, then USDC, then USDT. Sprints are funded
+    /// in SOL and refuse pools quoted in anything else, so USDC and USDT pools that pass the
+    /// filters are still not entered.
+    pub quote_mints: Vec<String>,
+    /// Oldest pool to enter, in seconds since activation
+    pub max_pool_age_seconds: Option<u64>,
+    /// Creators whose pools are never entered
+    pub blacklisted_creators: Vec<String>,
+    /// Tokens whose pools are never entered
+    pub blacklisted_mints: Vec<String>,
+    /// Base fees a pool must charge, in basis points
+    pub fee_tiers_bps: Vec<u16>,
+    /// Most earlier pools a creator may have rugged before their pools are skipped
+    pub max_creator_rugs: Option<u64>,
+    /// Highest Token-2022 transfer fee a pool's token may charge, in basis points
+    pub max_transfer_fee_bps: u16,
+}
+
+impl Default for PoolFilters {
+    fn default() -> Self {
+        Self {
+            min_score: 0.7,
+            min_tvl_sol: 10.0,
+            max_token_holders: Some(100),
+            // Positions are funded in SOL, so only SOL-quoted pools are entered by default
+            quote_mints: vec![WSOL_MINT.to_string()],
+            max_pool_age_seconds: None,
+            blacklisted_creators: Vec::new(),
+            blacklisted_mints: Vec::new(),
+            fee_tiers_bps: Vec::new(),
+            max_creator_rugs: Some(1),
+            max_transfer_fee_bps: 0,
+        }
+    }
+}
+
+/// Thresholds that make an open position exit immediately
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RugGuardConfig {
+    /// Watch pools with open positions for rugs
+    pub enabled: bool,
+    /// Drop in the pool's SOL reserve, in percent, that triggers an exit
+    pub quote_drop_percent: f64,
+    /// Window the SOL reserve drop is measured over (in seconds)
+    pub quote_drop_window_seconds: u64,
+    /// Drop in the token price from its peak, in percent, that triggers an exit
+    pub price_drop_percent: f64,
+    /// Share of the supply, in percent, a holder needs to be watched
+    pub holder_min_share_percent: f64,
+    /// Share of their balance, in percent, a watched holder may move before an exit is triggered
+    pub holder_dump_percent: f64,
+}
+
+impl Default for RugGuardConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            quote_drop_percent: 30.0,
+            quote_drop_window_seconds: 60,
+            price_drop_percent: 50.0,
+            holder_min_share_percent: 5.0,
+            holder_dump_percent: 50.0,
+        }
+    }
+}
+
+/// Conditions that exit a position before `position_duration_seconds`; a rule left unset is off
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExitRules {
+    /// Take profit once the fees earned reach this share of the SOL invested, in percent
+    pub take_profit_fee_percent: Option<f64>,
+    /// Stop out once fees minus impermanent loss fall this far below zero, in percent of the SOL invested
+    pub stop_loss_percent: Option<f64>,
+    /// Exit after this long without a swap in the pool (in seconds)
+    pub volume_timeout_seconds: Option<u64>,
+    /// Exit after this long even if `position_duration_seconds` is longer (in seconds)
+    pub max_duration_seconds: Option<u64>,
+}
+
 /// Dummy structure for when the telegram feature is disabled
 #[cfg(not(feature = "telegram"))]
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,8 +200,27 @@ impl Default for Config {
             rpc_url: "https://api.mainnet-beta.solana.com".to_string(),
             keypair_path: "keypair.json".to_string(),
             max_sol_per_position: 0.1,
+            min_sol_per_position: default_min_sol_per_position(),
+            max_concurrent_positions: default_max_concurrent_positions(),
+            max_total_sol_at_risk: default_max_total_sol_at_risk(),
             position_duration_seconds: 180, // 3 minutes
             fee_claim_interval_seconds: 60,
+            slippage_bps: default_slippage_bps(),
+            paper_trading: false,
+            record_pools: false,
+            recording_window_seconds: default_recording_window_seconds(),
+            recording_path: default_recording_path(),
+            price_cache_ttl_seconds: default_price_cache_ttl_seconds(),
+            price_max_staleness_seconds: default_price_max_staleness_seconds(),
+            price_providers: default_price_providers(),
+            price_api_url: default_price_api_url(),
+            price_api_timeout_ms: default_price_api_timeout_ms(),
+            score_weights: default_score_weights(),
+            pool_filters: PoolFilters::default(),
+            rug_window_minutes: default_rug_window_minutes(),
+            rug_tvl_drop_percent: default_rug_tvl_drop_percent(),
+            rug_guard: RugGuardConfig::default(),
+            exit_rules: ExitRules::default(),
             database_path: "meteora_sprinter.db".to_string(),
             debug_logging: false,
             #[cfg(feature = "telegram")]
@@ -55,6 +231,62 @@ impl Default for Config {
     }
 }
 
+fn default_min_sol_per_position() -> f64 {
+    0.01
+}
+
+fn default_max_concurrent_positions() -> usize {
+    3
+}
+
+fn default_max_total_sol_at_risk() -> f64 {
+    0.3
+}
+
+fn default_slippage_bps() -> u16 {
+    100 // 1%
+}
+
+fn default_recording_window_seconds() -> u64 {
+    1800 // 30 minutes
+}
+
+fn default_recording_path() -> String {
+    "pool_snapshots.jsonl".to_string()
+}
+
+fn default_price_cache_ttl_seconds() -> u64 {
+    15
+}
+
+fn default_price_max_staleness_seconds() -> u64 {
+    120
+}
+
+fn default_price_providers() -> Vec<String> {
+    vec!["onchain".to_string()]
+}
+
+fn default_price_api_url() -> String {
+    "https://api.jup.ag/price/v2".to_string()
+}
+
+fn default_price_api_timeout_ms() -> u64 {
+    2000
+}
+
+fn default_score_weights() -> BTreeMap<String, f64> {
+    crate::strategy::scoring::default_weights()
+}
+
+fn default_rug_window_minutes() -> u64 {
+    30
+}
+
+fn default_rug_tvl_drop_percent() -> f64 {
+    90.0
+}
+
 /// Loads configuration with the following priority:
 /// 1. Environment variables (highest priority)
 /// 2. JSON config file
@@ -64,16 +296,61 @@ pub fn load_config() -> Result<Config> {
     let mut config = Config::default();
     
     // Try to load from config file if it exists
-    if let Some(file_config) = load_from_file()? {
+    if let Some(mut file_config) = load_from_file()? {
+        file_config.price_providers = normalize_price_providers(file_config.price_providers.iter());
         config = file_config;
     }
     
     // Override with environment variables (highest priority)
     apply_env_overrides(&mut config);
     
+    validate(&config).context("Invalid configuration")?;
+    
     Ok(config)
 }
 
+/// Checks the settings that are parsed further when the bot starts, so mistakes fail early
+fn validate(config: &Config) -> Result<()> {
+    PoolCriteria::from_filters(&config.pool_filters)?;
+    ScoringPipeline::from_weights(&config.score_weights)?;
+    ExitPolicy::from_rules(&config.exit_rules)?;
+    if config.min_sol_per_position > config.max_sol_per_position {
+        return Err(anyhow!(
+            "min_sol_per_position ({}) must not exceed max_sol_per_position ({})",
+            config.min_sol_per_position,
+            config.max_sol_per_position
+        ));
+    }
+    // Sprints claim on a tokio interval, which cannot tick with a zero period
+    if config.fee_claim_interval_seconds == 0 {
+        return Err(anyhow!("fee_claim_interval_seconds must be positive"));
+    }
+    if config.slippage_bps > 10_000 {
+        return Err(anyhow!("slippage_bps must be at most 10000, got {}", config.slippage_bps));
+    }
+    let percentages = [
+        ("rug_tvl_drop_percent", config.rug_tvl_drop_percent),
+        ("rug_guard.quote_drop_percent", config.rug_guard.quote_drop_percent),
+        ("rug_guard.price_drop_percent", config.rug_guard.price_drop_percent),
+        ("rug_guard.holder_min_share_percent", config.rug_guard.holder_min_share_percent),
+        ("rug_guard.holder_dump_percent", config.rug_guard.holder_dump_percent),
+    ];
+    for (name, value) in percentages {
+        if !(0.0..=100.0).contains(&value) {
+            return Err(anyhow!("{} must be between 0 and 100, got {}", name, value));
+        }
+    }
+    Ok(())
+}
+
+/// Provider names as matched by the pricing module: trimmed, lowercase and without blanks
+fn normalize_price_providers<S: AsRef<str>>(providers: impl Iterator<Item = S>) -> Vec<String> {
+    providers
+        .map(|s| s.as_ref().trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
 /// Loads configuration from a JSON file if available
 fn load_from_file() -> Result<Option<Config>> {
     // Check for config file paths in order of preference
@@ -115,6 +392,24 @@ fn apply_env_overrides(config: &mut Config) {
         }
     }
     
+    if let Ok(min_sol) = env::var("MIN_SOL_PER_POSITION") {
+        if let Ok(value) = min_sol.parse::<f64>() {
+            config.min_sol_per_position = value;
+        }
+    }
+    
+    if let Ok(max_positions) = env::var("MAX_CONCURRENT_POSITIONS") {
+        if let Ok(value) = max_positions.parse::<usize>() {
+            config.max_concurrent_positions = value;
+        }
+    }
+    
+    if let Ok(max_at_risk) = env::var("MAX_TOTAL_SOL_AT_RISK") {
+        if let Ok(value) = max_at_risk.parse::<f64>() {
+            config.max_total_sol_at_risk = value;
+        }
+    }
+    
     if let Ok(duration) = env::var("POSITION_DURATION_SECONDS") {
         if let Ok(value) = duration.parse::<u64>() {
             config.position_duration_seconds = value;
@@ -127,6 +422,88 @@ fn apply_env_overrides(config: &mut Config) {
         }
     }
     
+    if let Ok(slippage) = env::var("SLIPPAGE_BPS") {
+        if let Ok(value) = slippage.parse::<u16>() {
+            config.slippage_bps = value;
+        }
+    }
+    
+    if let Ok(paper) = env::var("PAPER_TRADING") {
+        config.paper_trading = paper.to_lowercase() == "true" || paper == "1";
+    }
+    
+    if let Ok(record) = env::var("RECORD_POOLS") {
+        config.record_pools = record.to_lowercase() == "true" || record == "1";
+    }
+    
+    if let Ok(window) = env::var("RECORDING_WINDOW_SECONDS") {
+        if let Ok(value) = window.parse::<u64>() {
+            config.recording_window_seconds = value;
+        }
+    }
+    
+    if let Ok(path) = env::var("RECORDING_PATH") {
+        config.recording_path = path;
+    }
+    
+    if let Ok(ttl) = env::var("PRICE_CACHE_TTL_SECONDS") {
+        if let Ok(value) = ttl.parse::<u64>() {
+            config.price_cache_ttl_seconds = value;
+        }
+    }
+    
+    if let Ok(staleness) = env::var("PRICE_MAX_STALENESS_SECONDS") {
+        if let Ok(value) = staleness.parse::<u64>() {
+            config.price_max_staleness_seconds = value;
+        }
+    }
+    
+    if let Ok(providers) = env::var("PRICE_PROVIDERS") {
+        config.price_providers = normalize_price_providers(providers.split(','));
+    }
+    
+    if let Ok(url) = env::var("PRICE_API_URL") {
+        config.price_api_url = url;
+    }
+    
+    if let Ok(timeout) = env::var("PRICE_API_TIMEOUT_MS") {
+        if let Ok(value) = timeout.parse::<u64>() {
+            config.price_api_timeout_ms = value;
+        }
+    }
+    
+    if let Ok(weights) = env::var("SCORE_WEIGHTS") {
+        // Entries look like `liquidity=0.3`; ignore the variable if any of them does not parse
+        let parsed: Option<BTreeMap<String, f64>> = weights.split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|entry| {
+                let (name, weight) = entry.split_once('=')?;
+                Some((name.trim().to_lowercase(), weight.trim().parse::<f64>().ok()?))
+            })
+            .collect();
+        if let Some(value) = parsed {
+            config.score_weights = value;
+        }
+    }
+    
+    apply_pool_filter_env_overrides(&mut config.pool_filters);
+    
+    if let Ok(window) = env::var("RUG_WINDOW_MINUTES") {
+        if let Ok(value) = window.parse::<u64>() {
+            config.rug_window_minutes = value;
+        }
+    }
+    
+    if let Ok(drop) = env::var("RUG_TVL_DROP_PERCENT") {
+        if let Ok(value) = drop.parse::<f64>() {
+            config.rug_tvl_drop_percent = value;
+        }
+    }
+    
+    apply_rug_guard_env_overrides(&mut config.rug_guard);
+    apply_exit_rule_env_overrides(&mut config.exit_rules);
+    
     if let Ok(db_path) = env::var("DATABASE_URL") {
         config.database_path = db_path;
     }
@@ -140,6 +517,150 @@ fn apply_env_overrides(config: &mut Config) {
     apply_telegram_env_overrides(config);
 }
 
+/// Applies pool filter environment variables to the configuration
+fn apply_pool_filter_env_overrides(filters: &mut PoolFilters) {
+    let split_list = |value: String| -> Vec<String> {
+        value.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    };
+    
+    if let Ok(min_score) = env::var("MIN_SCORE") {
+        if let Ok(value) = min_score.parse::<f64>() {
+            filters.min_score = value;
+        }
+    }
+    
+    if let Ok(min_tvl) = env::var("MIN_TVL_SOL") {
+        if let Ok(value) = min_tvl.parse::<f64>() {
+            filters.min_tvl_sol = value;
+        }
+    }
+    
+    // An empty value removes the limit
+    if let Ok(max_holders) = env::var("MAX_TOKEN_HOLDERS") {
+        if max_holders.trim().is_empty() {
+            filters.max_token_holders = None;
+        } else if let Ok(value) = max_holders.parse::<u64>() {
+            filters.max_token_holders = Some(value);
+        }
+    }
+    
+    if let Ok(mints) = env::var("QUOTE_MINTS") {
+        filters.quote_mints = split_list(mints);
+    }
+    
+    if let Ok(max_age) = env::var("MAX_POOL_AGE_SECONDS") {
+        if max_age.trim().is_empty() {
+            filters.max_pool_age_seconds = None;
+        } else if let Ok(value) = max_age.parse::<u64>() {
+            filters.max_pool_age_seconds = Some(value);
+        }
+    }
+    
+    if let Ok(creators) = env::var("BLACKLISTED_CREATORS") {
+        filters.blacklisted_creators = split_list(creators);
+    }
+    
+    if let Ok(mints) = env::var("BLACKLISTED_MINTS") {
+        filters.blacklisted_mints = split_list(mints);
+    }
+    
+    if let Ok(max_rugs) = env::var("MAX_CREATOR_RUGS") {
+        if max_rugs.trim().is_empty() {
+            filters.max_creator_rugs = None;
+        } else if let Ok(value) = max_rugs.parse::<u64>() {
+            filters.max_creator_rugs = Some(value);
+        }
+    }
+    
+    if let Ok(max_fee) = env::var("MAX_TRANSFER_FEE_BPS") {
+        if let Ok(value) = max_fee.parse::<u16>() {
+            filters.max_transfer_fee_bps = value;
+        }
+    }
+    
+    if let Ok(tiers) = env::var("FEE_TIERS_BPS") {
+        let parsed: Result<Vec<u16>, _> = split_list(tiers).iter().map(|s| s.parse::<u16>()).collect();
+        if let Ok(value) = parsed {
+            filters.fee_tiers_bps = value;
+        }
+    }
+}
+
+/// Applies rug guard environment variables to the configuration
+fn apply_rug_guard_env_overrides(rug_guard: &mut RugGuardConfig) {
+    if let Ok(enabled) = env::var("RUG_GUARD_ENABLED") {
+        rug_guard.enabled = enabled.to_lowercase() == "true" || enabled == "1";
+    }
+    
+    if let Ok(drop) = env::var("RUG_QUOTE_DROP_PERCENT") {
+        if let Ok(value) = drop.parse::<f64>() {
+            rug_guard.quote_drop_percent = value;
+        }
+    }
+    
+    if let Ok(window) = env::var("RUG_QUOTE_DROP_WINDOW_SECONDS") {
+        if let Ok(value) = window.parse::<u64>() {
+            rug_guard.quote_drop_window_seconds = value;
+        }
+    }
+    
+    if let Ok(drop) = env::var("RUG_PRICE_DROP_PERCENT") {
+        if let Ok(value) = drop.parse::<f64>() {
+            rug_guard.price_drop_percent = value;
+        }
+    }
+    
+    if let Ok(share) = env::var("RUG_HOLDER_MIN_SHARE_PERCENT") {
+        if let Ok(value) = share.parse::<f64>() {
+            rug_guard.holder_min_share_percent = value;
+        }
+    }
+    
+    if let Ok(dump) = env::var("RUG_HOLDER_DUMP_PERCENT") {
+        if let Ok(value) = dump.parse::<f64>() {
+            rug_guard.holder_dump_percent = value;
+        }
+    }
+}
+
+/// Applies exit rule environment variables to the configuration; an empty value turns a rule off
+fn apply_exit_rule_env_overrides(rules: &mut ExitRules) {
+    if let Ok(percent) = env::var("EXIT_TAKE_PROFIT_FEE_PERCENT") {
+        if percent.trim().is_empty() {
+            rules.take_profit_fee_percent = None;
+        } else if let Ok(value) = percent.parse::<f64>() {
+            rules.take_profit_fee_percent = Some(value);
+        }
+    }
+    
+    if let Ok(percent) = env::var("EXIT_STOP_LOSS_PERCENT") {
+        if percent.trim().is_empty() {
+            rules.stop_loss_percent = None;
+        } else if let Ok(value) = percent.parse::<f64>() {
+            rules.stop_loss_percent = Some(value);
+        }
+    }
+    
+    if let Ok(timeout) = env::var("EXIT_VOLUME_TIMEOUT_SECONDS") {
+        if timeout.trim().is_empty() {
+            rules.volume_timeout_seconds = None;
+        } else if let Ok(value) = timeout.parse::<u64>() {
+            rules.volume_timeout_seconds = Some(value);
+        }
+    }
+    
+    if let Ok(duration) = env::var("EXIT_MAX_DURATION_SECONDS") {
+        if duration.trim().is_empty() {
+            rules.max_duration_seconds = None;
+        } else if let Ok(value) = duration.parse::<u64>() {
+            rules.max_duration_seconds = Some(value);
+        }
+    }
+}
+
 /// Applies Telegram-specific environment variables to the configuration
 #[cfg(feature = "telegram")]
 fn apply_telegram_env_overrides(config: &mut Config) {
@@ -173,4 +694,46 @@ fn apply_telegram_env_overrides(config: &mut Config) {
     if telegram_config.api_id != 0 && !telegram_config.api_hash.is_empty() && !telegram_config.phone_number.is_empty() {
         config.telegram = Some(telegram_config);
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_config_is_valid() {
+        validate(&Config::default()).unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_pool_filters() {
+        let mut config = Config::default();
+        config.pool_filters.quote_mints = vec![solana_sdk::pubkey::Pubkey::new_unique().to_string()];
+        let error = validate(&config).unwrap_err().to_string();
+        assert!(error.contains("quote_mints"), "{}", error);
+
+        let mut config = Config::default();
+        config.pool_filters.blacklisted_creators = vec!["not-a-pubkey".to_string()];
+        let error = validate(&config).unwrap_err().to_string();
+        assert!(error.contains("blacklisted_creators"), "{}", error);
+
+        let mut config = Config::default();
+        config.pool_filters.min_score = 1.1;
+        let error = validate(&config).unwrap_err().to_string();
+        assert!(error.contains("min_score"), "{}", error);
+    }
+
+    #[test]
+    fn normalizes_price_provider_names() {
+        let providers = vec!["OnChain".to_string(), " HTTP ".to_string(), String::new()];
+        assert_eq!(normalize_price_providers(providers.iter()), vec!["onchain", "http"]);
+        assert_eq!(normalize_price_providers("Onchain, http,".split(',')), vec!["onchain", "http"]);
+    }
+
+    #[test]
+    fn rejects_a_zero_fee_claim_interval() {
+        let config = Config { fee_claim_interval_seconds: 0, ..Config::default() };
+        let error = validate(&config).unwrap_err().to_string();
+        assert!(error.contains("fee_claim_interval_seconds"), "{}", error);
+    }
+}
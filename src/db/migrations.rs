@@ -0,0 +1,384 @@
+use anyhow::{Result, Context};
+use log::info;
+use sqlx::sqlite::{SqlitePool, SqliteConnection};
+use sqlx::Row;
+
+/// A single change to the schema
+enum Step {
+    /// Run a SQL statement as-is
+    Sql(&'static str),
+    /// Add a column unless the table already has it
+    AddColumn {
+        table: &'static str,
+        column: &'static str,
+        definition: &'static str,
+    },
+}
+
+/// An ordered, versioned set of schema changes
+struct Migration {
+    version: i64,
+    description: &'static str,
+    steps: &'static [Step],
+}
+
+// Every migration ever shipped, in order. Never edit an existing entry: append a new one instead.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        description: "create pools and positions tables",
+        steps: &[
+            Step::Sql(
+                "CREATE TABLE IF NOT EXISTS pools (
+                    address TEXT PRIMARY KEY,
+                    token_a_mint TEXT NOT NULL,
+                    token_b_mint TEXT NOT NULL,
+                    token_a_name TEXT,
+                    token_b_name TEXT,
+                    token_a_symbol TEXT,
+                    token_b_symbol TEXT,
+                    token_a_decimals INTEGER,
+                    token_b_decimals INTEGER,
+                    discovered_at TIMESTAMP NOT NULL,
+                    analyzed BOOLEAN NOT NULL DEFAULT FALSE,
+                    score REAL
+                )"
+            ),
+            Step::Sql(
+                "CREATE TABLE IF NOT EXISTS positions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    pool_address TEXT NOT NULL,
+                    created_at TIMESTAMP NOT NULL,
+                    closed_at TIMESTAMP,
+                    sol_invested REAL NOT NULL,
+                    fee_claimed REAL,
+                    profit_loss REAL,
+                    status TEXT NOT NULL,
+                    FOREIGN KEY (pool_address) REFERENCES pools (address)
+                )"
+            ),
+        ],
+    },
+    Migration {
+        version: 2,
+        description: "track on-chain position accounts, amounts and signatures",
+        steps: &[
+            Step::AddColumn { table: "positions", column: "position_address", definition: "TEXT" },
+            Step::AddColumn { table: "positions", column: "position_nft_mint", definition: "TEXT" },
+            Step::AddColumn { table: "positions", column: "token_a_amount_in", definition: "INTEGER" },
+            Step::AddColumn { table: "positions", column: "token_b_amount_in", definition: "INTEGER" },
+            Step::AddColumn { table: "positions", column: "token_a_amount_out", definition: "INTEGER" },
+            Step::AddColumn { table: "positions", column: "token_b_amount_out", definition: "INTEGER" },
+            Step::AddColumn { table: "positions", column: "entry_signature", definition: "TEXT" },
+            Step::AddColumn { table: "positions", column: "exit_signature", definition: "TEXT" },
+        ],
+    },
+    Migration {
+        version: 3,
+        description: "flag paper-trading positions",
+        steps: &[
+            Step::AddColumn { table: "positions", column: "simulated", definition: "BOOLEAN NOT NULL DEFAULT FALSE" },
+        ],
+    },
+    Migration {
+        version: 4,
+        description: "track impermanent loss of positions",
+        steps: &[
+            Step::AddColumn { table: "positions", column: "impermanent_loss", definition: "REAL" },
+        ],
+    },
+    Migration {
+        version: 5,
+        description: "store token safety check results with pools",
+        steps: &[
+            Step::AddColumn { table: "pools", column: "safety_checks", definition: "TEXT" },
+        ],
+    },
+    Migration {
+        version: 6,
+        description: "store TVL and holder distribution with pools",
+        steps: &[
+            Step::AddColumn { table: "pools", column: "tvl_lamports", definition: "INTEGER" },
+            Step::AddColumn { table: "pools", column: "holders", definition: "TEXT" },
+        ],
+    },
+    Migration {
+        version: 7,
+        description: "store the per-factor score breakdown with pools",
+        steps: &[
+            Step::AddColumn { table: "pools", column: "score_breakdown", definition: "TEXT" },
+        ],
+    },
+    Migration {
+        version: 8,
+        description: "store the creator, fee and activation time of pools",
+        steps: &[
+            Step::AddColumn { table: "pools", column: "creator", definition: "TEXT" },
+            Step::AddColumn { table: "pools", column: "fee_bps", definition: "INTEGER" },
+            Step::AddColumn { table: "pools", column: "activated_at", definition: "TEXT" },
+        ],
+    },
+    Migration {
+        version: 9,
+        description: "track creator reputation and rugged pools",
+        steps: &[
+            Step::AddColumn { table: "pools", column: "creator_stats", definition: "TEXT" },
+            Step::AddColumn { table: "pools", column: "rugged", definition: "BOOLEAN" },
+            Step::Sql("CREATE INDEX IF NOT EXISTS idx_pools_creator ON pools (creator)"),
+        ],
+    },
+    Migration {
+        version: 10,
+        description: "record why positions were exited",
+        steps: &[
+            Step::AddColumn { table: "positions", column: "exit_reason", definition: "TEXT" },
+        ],
+    },
+    Migration {
+        version: 11,
+        description: "record tokens left over after entering positions",
+        steps: &[
+            Step::AddColumn { table: "positions", column: "token_a_refund", definition: "INTEGER" },
+            Step::AddColumn { table: "positions", column: "token_b_refund", definition: "INTEGER" },
+        ],
+    },
+];
+
+/// Apply every migration newer than the database's recorded schema version
+pub async fn run(pool: &SqlitePool) -> Result<()> {
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            version INTEGER PRIMARY KEY,
+            description TEXT NOT NULL,
+            applied_at TIMESTAMP NOT NULL
+        )"
+    )
+    .execute(pool)
+    .await?;
+
+    let current: i64 = sqlx::query("SELECT COALESCE(MAX(version), 0) FROM schema_version")
+        .fetch_one(pool)
+        .await?
+        .get(0);
+
+    for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+        // Each migration and its version row are committed together
+        let mut tx = pool.begin().await?;
+
+        for step in migration.steps {
+            apply_step(&mut tx, step).await
+                .with_context(|| format!("Migration {} ({}) failed", migration.version, migration.description))?;
+        }
+
+        sqlx::query("INSERT INTO schema_version (version, description, applied_at) VALUES (?, ?, ?)")
+            .bind(migration.version)
+            .bind(migration.description)
+            .bind(chrono::Utc::now().to_rfc3339())
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        info!("Applied database migration {}: {}", migration.version, migration.description);
+    }
+
+    Ok(())
+}
+
+/// Apply one migration step on the given connection
+async fn apply_step(conn: &mut SqliteConnection, step: &Step) -> Result<()> {
+    match step {
+        Step::Sql(sql) => {
+            sqlx::query(sql).execute(&mut *conn).await?;
+        },
+        Step::AddColumn { table, column, definition } => {
+            // Databases created before migrations existed may already have the column
+            let columns = sqlx::query(&format!("PRAGMA table_info({})", table))
+                .fetch_all(&mut *conn)
+                .await?;
+            let exists = columns.iter().any(|row| row.get::<String, _>(1) == *column);
+
+            if !exists {
+                sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+                    .execute(&mut *conn)
+                    .await?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    // Schema written by `init_schema` before migrations existed
+    const BASELINE_SCHEMA: [&str; 2] = [
+        "CREATE TABLE pools (
+            address TEXT PRIMARY KEY,
+            token_a_mint TEXT NOT NULL,
+            token_b_mint TEXT NOT NULL,
+            token_a_name TEXT,
+            token_b_name TEXT,
+            token_a_symbol TEXT,
+            token_b_symbol TEXT,
+            token_a_decimals INTEGER,
+            token_b_decimals INTEGER,
+            discovered_at TIMESTAMP NOT NULL,
+            analyzed BOOLEAN NOT NULL DEFAULT FALSE,
+            score REAL
+        )",
+        "CREATE TABLE positions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            pool_address TEXT NOT NULL,
+            created_at TIMESTAMP NOT NULL,
+            closed_at TIMESTAMP,
+            sol_invested REAL NOT NULL,
+            fee_claimed REAL,
+            profit_loss REAL,
+            status TEXT NOT NULL,
+            FOREIGN KEY (pool_address) REFERENCES pools (address)
+        )",
+    ];
+
+    // Positions table as briefly created in place, with the on-chain columns but no schema_version
+    const IN_PLACE_POSITIONS_SCHEMA: &str = "CREATE TABLE positions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        pool_address TEXT NOT NULL,
+        created_at TIMESTAMP NOT NULL,
+        closed_at TIMESTAMP,
+        position_address TEXT,
+        position_nft_mint TEXT,
+        sol_invested REAL NOT NULL,
+        token_a_amount_in INTEGER,
+        token_b_amount_in INTEGER,
+        token_a_amount_out INTEGER,
+        token_b_amount_out INTEGER,
+        entry_signature TEXT,
+        exit_signature TEXT,
+        fee_claimed REAL,
+        profit_loss REAL,
+        status TEXT NOT NULL,
+        FOREIGN KEY (pool_address) REFERENCES pools (address)
+    )";
+
+    /// In-memory database on a single connection, so every query sees the same data
+    async fn memory_pool() -> SqlitePool {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    async fn columns(pool: &SqlitePool, table: &str) -> Vec<String> {
+        sqlx::query(&format!("PRAGMA table_info({})", table))
+            .fetch_all(pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get::<String, _>(1))
+            .collect()
+    }
+
+    /// Create the given legacy schema with one pool and one position in it
+    async fn legacy_database(schema: &[&str]) -> SqlitePool {
+        let pool = memory_pool().await;
+        for statement in schema {
+            sqlx::query(statement).execute(&pool).await.unwrap();
+        }
+
+        sqlx::query("INSERT INTO pools (address, token_a_mint, token_b_mint, discovered_at, analyzed, score)
+            VALUES ('pool', 'mint_a', 'mint_b', '2025-01-01T00:00:00+00:00', TRUE, 0.8)")
+            .execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO positions (pool_address, created_at, sol_invested, fee_claimed, status)
+            VALUES ('pool', '2025-01-01T00:00:00+00:00', 0.1, 0.002, 'closed')")
+            .execute(&pool).await.unwrap();
+
+        pool
+    }
+
+    /// Run the migrations twice and check the schema and data end up where they should
+    async fn assert_upgrades(pool: &SqlitePool) {
+        run(pool).await.unwrap();
+        run(pool).await.unwrap();
+
+        let pool_columns = columns(pool, "pools").await;
+        let position_columns = columns(pool, "positions").await;
+        for migration in MIGRATIONS.iter().filter(|m| m.version >= 2) {
+            for step in migration.steps {
+                if let Step::AddColumn { table, column, .. } = step {
+                    let existing = if *table == "pools" { &pool_columns } else { &position_columns };
+                    assert!(existing.iter().any(|c| c == column),
+                        "column {}.{} from migration {} is missing", table, column, migration.version);
+                }
+            }
+        }
+
+        let versions: Vec<i64> = sqlx::query("SELECT version FROM schema_version ORDER BY version")
+            .fetch_all(pool)
+            .await
+            .unwrap()
+            .iter()
+            .map(|row| row.get(0))
+            .collect();
+        assert_eq!(versions, (1..=11).collect::<Vec<i64>>());
+
+        let pool_row = sqlx::query("SELECT score, analyzed, rugged FROM pools WHERE address = 'pool'")
+            .fetch_one(pool).await.unwrap();
+        assert_eq!(pool_row.get::<f64, _>(0), 0.8);
+        assert!(pool_row.get::<bool, _>(1));
+        assert_eq!(pool_row.get::<Option<bool>, _>(2), None);
+
+        let position_row = sqlx::query("SELECT sol_invested, fee_claimed, status, simulated, exit_reason FROM positions")
+            .fetch_one(pool).await.unwrap();
+        assert_eq!(position_row.get::<f64, _>(0), 0.1);
+        assert_eq!(position_row.get::<f64, _>(1), 0.002);
+        assert_eq!(position_row.get::<String, _>(2), "closed");
+        assert!(!position_row.get::<bool, _>(3));
+        assert_eq!(position_row.get::<Option<String>, _>(4), None);
+    }
+
+    #[tokio::test]
+    async fn creates_fresh_database() {
+        let pool = memory_pool().await;
+        run(&pool).await.unwrap();
+        assert!(columns(&pool, "positions").await.iter().any(|c| c == "exit_reason"));
+        assert!(columns(&pool, "pools").await.iter().any(|c| c == "creator_stats"));
+    }
+
+    #[tokio::test]
+    async fn upgrades_baseline_database() {
+        let pool = legacy_database(&BASELINE_SCHEMA).await;
+        assert_upgrades(&pool).await;
+    }
+
+    #[tokio::test]
+    async fn add_column_skips_existing_columns() {
+        let pool = legacy_database(&BASELINE_SCHEMA).await;
+        let step = Step::AddColumn { table: "positions", column: "fee_claimed", definition: "REAL" };
+
+        let mut conn = pool.acquire().await.unwrap();
+        apply_step(&mut conn, &step).await.unwrap();
+        apply_step(&mut conn, &step).await.unwrap();
+        drop(conn);
+
+        let fee_columns = columns(&pool, "positions").await.into_iter().filter(|c| c == "fee_claimed").count();
+        assert_eq!(fee_columns, 1);
+    }
+
+    #[tokio::test]
+    async fn upgrades_database_with_in_place_position_columns() {
+        let pool = legacy_database(&[BASELINE_SCHEMA[0], IN_PLACE_POSITIONS_SCHEMA]).await;
+        sqlx::query("UPDATE positions SET position_address = 'position', entry_signature = 'signature'")
+            .execute(&pool).await.unwrap();
+
+        assert_upgrades(&pool).await;
+
+        let row = sqlx::query("SELECT position_address, entry_signature FROM positions")
+            .fetch_one(&pool).await.unwrap();
+        assert_eq!(row.get::<String, _>(0), "position");
+        assert_eq!(row.get::<String, _>(1), "signature");
+    }
+}
@@ -1,3 +1,4 @@
 mod sqlite;
+mod migrations;
 
 pub use sqlite::Database; 
\ No newline at end of file
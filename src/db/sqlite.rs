@@ -1,12 +1,14 @@
-use anyhow::{Result, Context};
-use sqlx::sqlite::{SqlitePool, SqlitePoolOptions};
+use anyhow::{Result, Context, anyhow};
+use sqlx::sqlite::{SqlitePool, SqlitePoolOptions, SqliteRow};
 use sqlx::Row;
 use std::fs::File;
 use std::path::Path;
 use chrono::{DateTime, Utc};
 use solana_sdk::pubkey::Pubkey;
 
-use crate::models::pool::{Pool, TokenInfo};
+use crate::models::pool::{Pool, TokenInfo, SafetyCheck, HolderDistribution, ScoreFactor};
+use crate::models::{CreatorStats, Position, PositionStatus};
+use crate::db::migrations;
 
 /// Database manager for handling SQLite operations
 #[derive(Clone)]
@@ -30,52 +32,16 @@ impl Database {
             .connect(database_url)
             .await?;
             
-        // Initialize schema if needed
+        // Apply any pending schema migrations
         let db = Self { pool };
         db.init_schema().await?;
         
         Ok(db)
     }
     
-    /// Initialize the database schema
+    /// Bring the database schema up to date by applying any pending migrations
     pub async fn init_schema(&self) -> Result<()> {
-        // Create tables if they don't exist
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS pools (
-                address TEXT PRIMARY KEY,
-                token_a_mint TEXT NOT NULL,
-                token_b_mint TEXT NOT NULL,
-                token_a_name TEXT,
-                token_b_name TEXT,
-                token_a_symbol TEXT,
-                token_b_symbol TEXT,
-                token_a_decimals INTEGER,
-                token_b_decimals INTEGER,
-                discovered_at TIMESTAMP NOT NULL,
-                analyzed BOOLEAN NOT NULL DEFAULT FALSE,
-                score REAL
-            )"
-        )
-        .execute(&self.pool)
-        .await?;
-        
-        sqlx::query(
-            "CREATE TABLE IF NOT EXISTS positions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                pool_address TEXT NOT NULL,
-                created_at TIMESTAMP NOT NULL,
-                closed_at TIMESTAMP,
-                sol_invested REAL NOT NULL,
-                fee_claimed REAL,
-                profit_loss REAL,
-                status TEXT NOT NULL,
-                FOREIGN KEY (pool_address) REFERENCES pools (address)
-            )"
-        )
-        .execute(&self.pool)
-        .await?;
-        
-        Ok(())
+        migrations::run(&self.pool).await
     }
     
     /// Save a pool to the database
@@ -83,14 +49,26 @@ impl Database {
         // Convert DateTime to ISO-8601 string which SQLite understands
         let discovered_at_str = pool.discovered_at.to_rfc3339();
         
-        // Insert or update the pool
+        // Insert or update the pool in place: replacing the row would drop its positions' parent
+        // and the rug check result, which only `set_pool_rugged` writes after the first save
         sqlx::query(
-            "INSERT OR REPLACE INTO pools (
+            "INSERT INTO pools (
                 address,
                 token_a_mint, token_a_name, token_a_symbol, token_a_decimals,
                 token_b_mint, token_b_name, token_b_symbol, token_b_decimals,
-                discovered_at, analyzed, score
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+                discovered_at, analyzed, score, safety_checks, tvl_lamports, holders, score_breakdown,
+                creator, fee_bps, activated_at, creator_stats, rugged
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            ON CONFLICT(address) DO UPDATE SET
+                token_a_mint = excluded.token_a_mint, token_a_name = excluded.token_a_name,
+                token_a_symbol = excluded.token_a_symbol, token_a_decimals = excluded.token_a_decimals,
+                token_b_mint = excluded.token_b_mint, token_b_name = excluded.token_b_name,
+                token_b_symbol = excluded.token_b_symbol, token_b_decimals = excluded.token_b_decimals,
+                discovered_at = excluded.discovered_at, analyzed = excluded.analyzed, score = excluded.score,
+                safety_checks = excluded.safety_checks, tvl_lamports = excluded.tvl_lamports,
+                holders = excluded.holders, score_breakdown = excluded.score_breakdown,
+                creator = excluded.creator, fee_bps = excluded.fee_bps, activated_at = excluded.activated_at,
+                creator_stats = excluded.creator_stats"
         )
         .bind(pool.address.to_string())
         .bind(pool.token_a.mint.to_string())
@@ -104,6 +82,15 @@ impl Database {
         .bind(discovered_at_str)
         .bind(pool.analyzed)
         .bind(pool.score)
+        .bind(serde_json::to_string(&pool.safety_checks)?)
+        .bind(pool.tvl_lamports.map(|tvl| tvl as i64))
+        .bind(serde_json::to_string(&pool.holders)?)
+        .bind(serde_json::to_string(&pool.score_breakdown)?)
+        .bind(pool.creator.map(|creator| creator.to_string()))
+        .bind(pool.fee_bps)
+        .bind(pool.activated_at.map(|t| t.to_rfc3339()))
+        .bind(pool.creator_stats.as_ref().map(serde_json::to_string).transpose()?)
+        .bind(pool.rugged)
         .execute(&self.pool)
         .await?;
         
@@ -117,7 +104,8 @@ impl Database {
                 address, 
                 token_a_mint, token_a_name, token_a_symbol, token_a_decimals,
                 token_b_mint, token_b_name, token_b_symbol, token_b_decimals,
-                discovered_at, analyzed, score
+                discovered_at, analyzed, score, safety_checks, tvl_lamports, holders, score_breakdown,
+                creator, fee_bps, activated_at, creator_stats, rugged
             FROM pools
             WHERE address = ?"
         )
@@ -144,6 +132,15 @@ impl Database {
                     discovered_at: row.get::<String, _>(9).parse::<DateTime<Utc>>()?,
                     analyzed: row.get(10),
                     score: row.get(11),
+                    safety_checks: parse_safety_checks(row.get(12))?,
+                    tvl_lamports: row.get::<Option<i64>, _>(13).map(|tvl| tvl as u64),
+                    holders: parse_holders(row.get(14))?,
+                    score_breakdown: parse_score_breakdown(row.get(15))?,
+                    creator: row.get::<Option<String>, _>(16).map(|c| c.parse()).transpose()?,
+                    fee_bps: row.get::<Option<i64>, _>(17).map(|f| f as u16),
+                    activated_at: row.get::<Option<String>, _>(18).map(|t| t.parse::<DateTime<Utc>>()).transpose()?,
+                    creator_stats: parse_creator_stats(row.get(19))?,
+                    rugged: row.get(20),
                 };
                 
                 Ok(Some(pool))
@@ -159,7 +156,8 @@ impl Database {
                 address, 
                 token_a_mint, token_a_name, token_a_symbol, token_a_decimals,
                 token_b_mint, token_b_name, token_b_symbol, token_b_decimals,
-                discovered_at, analyzed, score
+                discovered_at, analyzed, score, safety_checks, tvl_lamports, holders, score_breakdown,
+                creator, fee_bps, activated_at, creator_stats, rugged
             FROM pools
             "
         );
@@ -192,6 +190,15 @@ impl Database {
             let discovered_at: String = row.get(9);
             let analyzed: bool = row.get(10);
             let score: Option<f64> = row.get(11);
+            let safety_checks = parse_safety_checks(row.get(12))?;
+            let tvl_lamports: Option<i64> = row.get(13);
+            let holders = parse_holders(row.get(14))?;
+            let score_breakdown = parse_score_breakdown(row.get(15))?;
+            let creator: Option<String> = row.get(16);
+            let fee_bps: Option<i64> = row.get(17);
+            let activated_at: Option<String> = row.get(18);
+            let creator_stats = parse_creator_stats(row.get(19))?;
+            let rugged: Option<bool> = row.get(20);
             
             let pool = Pool {
                 address: address.parse().context("Invalid address format")?,
@@ -210,6 +217,15 @@ impl Database {
                 discovered_at: discovered_at.parse().context("Invalid timestamp format")?,
                 analyzed,
                 score,
+                safety_checks,
+                tvl_lamports: tvl_lamports.map(|tvl| tvl as u64),
+                holders,
+                score_breakdown,
+                creator: creator.map(|c| c.parse()).transpose().context("Invalid creator format")?,
+                fee_bps: fee_bps.map(|f| f as u16),
+                activated_at: activated_at.map(|t| t.parse()).transpose().context("Invalid activation timestamp format")?,
+                creator_stats,
+                rugged,
             };
             
             pools.push(pool);
@@ -217,10 +233,474 @@ impl Database {
         
         Ok(pools)
     }
+    
+    /// Record whether a pool was rugged within the check window
+    pub async fn set_pool_rugged(&self, address: &Pubkey, rugged: bool) -> Result<()> {
+        sqlx::query("UPDATE pools SET rugged = ? WHERE address = ?")
+            .bind(rugged)
+            .bind(address.to_string())
+            .execute(&self.pool)
+            .await?;
+        
+        Ok(())
+    }
+    
+    /// Track record of a creator across the pools we have seen, leaving out `excluded_pool`
+    pub async fn get_creator_stats(&self, creator: &Pubkey, excluded_pool: &Pubkey) -> Result<CreatorStats> {
+        let row = sqlx::query(
+            "SELECT
+                COUNT(*),
+                COUNT(rugged),
+                COALESCE(SUM(CASE WHEN rugged THEN 1 ELSE 0 END), 0),
+                (SELECT COALESCE(SUM(positions.profit_loss), 0.0)
+                    FROM positions JOIN pools AS created ON created.address = positions.pool_address
+                    WHERE created.creator = ?1 AND created.address != ?2 AND positions.status = ?3
+                        AND positions.simulated = FALSE)
+            FROM pools
+            WHERE creator = ?1 AND address != ?2"
+        )
+        .bind(creator.to_string())
+        .bind(excluded_pool.to_string())
+        .bind(PositionStatus::Closed.as_str())
+        .fetch_one(&self.pool)
+        .await?;
+        
+        Ok(CreatorStats {
+            creator: *creator,
+            pools_created: row.get::<i64, _>(0) as u64,
+            pools_checked: row.get::<i64, _>(1) as u64,
+            pools_rugged: row.get::<i64, _>(2) as u64,
+            profit_loss_sol: row.get(3),
+        })
+    }
+    
+    /// Save a position, inserting it if it has no ID yet, and return its ID
+    pub async fn save_position(&self, position: &Position) -> Result<i64> {
+        if let Some(id) = position.id {
+            self.update_position(position).await?;
+            return Ok(id);
+        }
+        
+        let result = sqlx::query(
+            "INSERT INTO positions (
+                pool_address, position_address, position_nft_mint,
+                created_at, closed_at, sol_invested,
+                token_a_amount_in, token_b_amount_in, token_a_amount_out, token_b_amount_out,
+                entry_signature, exit_signature, fee_claimed, profit_loss, status, simulated,
+                impermanent_loss, exit_reason, token_a_refund, token_b_refund
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(position.pool.to_string())
+        .bind(position.position_address.map(|p| p.to_string()))
+        .bind(position.position_nft_mint.map(|p| p.to_string()))
+        .bind(position.created_at.to_rfc3339())
+        .bind(position.closed_at.map(|t| t.to_rfc3339()))
+        .bind(position.sol_invested)
+        .bind(position.token_a_amount_in.map(|a| a as i64))
+        .bind(position.token_b_amount_in.map(|a| a as i64))
+        .bind(position.token_a_amount_out.map(|a| a as i64))
+        .bind(position.token_b_amount_out.map(|a| a as i64))
+        .bind(&position.entry_signature)
+        .bind(&position.exit_signature)
+        .bind(position.fee_claimed)
+        .bind(position.profit_loss)
+        .bind(position.status.as_str())
+        .bind(position.simulated)
+        .bind(position.impermanent_loss)
+        .bind(position.exit_reason.map(|r| r.as_str()))
+        .bind(position.token_a_refund.map(|a| a as i64))
+        .bind(position.token_b_refund.map(|a| a as i64))
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(result.last_insert_rowid())
+    }
+    
+    /// Update every mutable field of a saved position
+    pub async fn update_position(&self, position: &Position) -> Result<()> {
+        let id = position.id
+            .ok_or_else(|| anyhow!("Cannot update a position that has not been saved"))?;
+        
+        sqlx::query(
+            "UPDATE positions SET
+                position_address = ?, position_nft_mint = ?, closed_at = ?,
+                token_a_amount_in = ?, token_b_amount_in = ?, token_a_amount_out = ?, token_b_amount_out = ?,
+                entry_signature = ?, exit_signature = ?, fee_claimed = ?, profit_loss = ?, status = ?,
+                impermanent_loss = ?, exit_reason = ?, token_a_refund = ?, token_b_refund = ?
+            WHERE id = ?"
+        )
+        .bind(position.position_address.map(|p| p.to_string()))
+        .bind(position.position_nft_mint.map(|p| p.to_string()))
+        .bind(position.closed_at.map(|t| t.to_rfc3339()))
+        .bind(position.token_a_amount_in.map(|a| a as i64))
+        .bind(position.token_b_amount_in.map(|a| a as i64))
+        .bind(position.token_a_amount_out.map(|a| a as i64))
+        .bind(position.token_b_amount_out.map(|a| a as i64))
+        .bind(&position.entry_signature)
+        .bind(&position.exit_signature)
+        .bind(position.fee_claimed)
+        .bind(position.profit_loss)
+        .bind(position.status.as_str())
+        .bind(position.impermanent_loss)
+        .bind(position.exit_reason.map(|r| r.as_str()))
+        .bind(position.token_a_refund.map(|a| a as i64))
+        .bind(position.token_b_refund.map(|a| a as i64))
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// Update only the status of a position, stamping `closed_at` when it reaches a final state
+    pub async fn update_position_status(&self, id: i64, status: PositionStatus) -> Result<()> {
+        let closed_at = match status {
+            PositionStatus::Closed | PositionStatus::Failed => Some(Utc::now().to_rfc3339()),
+            _ => None,
+        };
+        
+        sqlx::query(
+            "UPDATE positions SET status = ?, closed_at = COALESCE(?, closed_at) WHERE id = ?"
+        )
+        .bind(status.as_str())
+        .bind(closed_at)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        
+        Ok(())
+    }
+    
+    /// Get a position by its ID
+    pub async fn get_position(&self, id: i64) -> Result<Option<Position>> {
+        let query = format!("SELECT {} FROM positions WHERE id = ?", POSITION_COLUMNS);
+        let row = sqlx::query(&query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+        
+        row.as_ref().map(position_from_row).transpose()
+    }
+    
+    /// Get every position that has not reached a final state
+    pub async fn get_open_positions(&self) -> Result<Vec<Position>> {
+        let query = format!(
+            "SELECT {} FROM positions WHERE status NOT IN (?, ?) ORDER BY created_at ASC",
+            POSITION_COLUMNS
+        );
+        let rows = sqlx::query(&query)
+            .bind(PositionStatus::Closed.as_str())
+            .bind(PositionStatus::Failed.as_str())
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(position_from_row).collect()
+    }
+    
+    /// Get all positions opened in a pool
+    pub async fn get_positions_for_pool(&self, pool: &Pubkey) -> Result<Vec<Position>> {
+        let query = format!(
+            "SELECT {} FROM positions WHERE pool_address = ? ORDER BY created_at DESC",
+            POSITION_COLUMNS
+        );
+        let rows = sqlx::query(&query)
+            .bind(pool.to_string())
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(position_from_row).collect()
+    }
+    
+    /// List positions, most recent first
+    pub async fn list_positions(&self, limit: Option<i64>) -> Result<Vec<Position>> {
+        let mut query = format!("SELECT {} FROM positions ORDER BY created_at DESC ", POSITION_COLUMNS);
+        
+        if let Some(limit) = limit {
+            query.push_str(&format!("LIMIT {}", limit));
+        }
+        
+        let rows = sqlx::query(&query)
+            .fetch_all(&self.pool)
+            .await?;
+        
+        rows.iter().map(position_from_row).collect()
+    }
+}
+
+/// Decode the JSON safety check results stored with a pool (NULL for pools analyzed before they existed)
+fn parse_safety_checks(value: Option<String>) -> Result<Vec<SafetyCheck>> {
+    match value {
+        Some(json) => serde_json::from_str(&json).context("Invalid safety check format"),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Decode the JSON holder distributions stored with a pool (NULL for pools analyzed before they existed)
+fn parse_holders(value: Option<String>) -> Result<Vec<HolderDistribution>> {
+    match value {
+        Some(json) => serde_json::from_str(&json).context("Invalid holder distribution format"),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Decode the JSON creator stats stored with a pool
+fn parse_creator_stats(value: Option<String>) -> Result<Option<CreatorStats>> {
+    value.map(|json| serde_json::from_str(&json).context("Invalid creator stats format")).transpose()
+}
+
+/// Decode the JSON score breakdown stored with a pool (NULL for pools scored before it existed)
+fn parse_score_breakdown(value: Option<String>) -> Result<Vec<ScoreFactor>> {
+    match value {
+        Some(json) => serde_json::from_str(&json).context("Invalid score breakdown format"),
+        None => Ok(Vec::new()),
+    }
+}
+
+// Column list shared by every position query, in the order read by `position_from_row`
+const POSITION_COLUMNS: &str = "id, pool_address, position_address, position_nft_mint,
+    created_at, closed_at, sol_invested,
+    token_a_amount_in, token_b_amount_in, token_a_amount_out, token_b_amount_out,
+    entry_signature, exit_signature, fee_claimed, profit_loss, status, simulated,
+    impermanent_loss, exit_reason, token_a_refund, token_b_refund";
+
+/// Map a row selected with `POSITION_COLUMNS` to a position
+fn position_from_row(row: &SqliteRow) -> Result<Position> {
+    let parse_pubkey = |value: Option<String>| -> Result<Option<Pubkey>> {
+        value.map(|v| v.parse::<Pubkey>().context("Invalid pubkey format")).transpose()
+    };
+    let to_amount = |value: Option<i64>| value.map(|v| v as u64);
+    
+    Ok(Position {
+        id: Some(row.get(0)),
+        pool: row.get::<String, _>(1).parse().context("Invalid pool address format")?,
+        position_address: parse_pubkey(row.get(2))?,
+        position_nft_mint: parse_pubkey(row.get(3))?,
+        created_at: row.get::<String, _>(4).parse().context("Invalid timestamp format")?,
+        closed_at: row.get::<Option<String>, _>(5)
+            .map(|t| t.parse::<DateTime<Utc>>())
+            .transpose()
+            .context("Invalid timestamp format")?,
+        sol_invested: row.get(6),
+        token_a_amount_in: to_amount(row.get(7)),
+        token_b_amount_in: to_amount(row.get(8)),
+        token_a_amount_out: to_amount(row.get(9)),
+        token_b_amount_out: to_amount(row.get(10)),
+        entry_signature: row.get(11),
+        exit_signature: row.get(12),
+        fee_claimed: row.get(13),
+        profit_loss: row.get(14),
+        status: row.get::<String, _>(15).parse()?,
+        simulated: row.get(16),
+        impermanent_loss: row.get(17),
+        exit_reason: row.get::<Option<String>, _>(18).map(|r| r.parse()).transpose()?,
+        token_a_refund: to_amount(row.get(19)),
+        token_b_refund: to_amount(row.get(20)),
+    })
 }
 
 /// Initialize an in-memory database for testing
 #[cfg(test)]
 pub async fn init_test_db() -> Result<Database> {
     Database::new("sqlite::memory:").await
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::ExitReason;
+
+    /// A migrated database on a single in-memory connection, so every query sees the same data
+    async fn memory_db() -> Database {
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db = Database { pool };
+        db.init_schema().await.unwrap();
+        db
+    }
+
+    fn pool(creator: Pubkey) -> Pool {
+        let token = |mint: Pubkey| TokenInfo { mint, name: None, symbol: None, decimals: None };
+        Pool {
+            address: Pubkey::new_unique(),
+            token_a: token(Pubkey::new_unique()),
+            token_b: token(crate::meteora::WSOL_MINT.parse().unwrap()),
+            discovered_at: Utc::now(),
+            analyzed: false,
+            score: None,
+            score_breakdown: Vec::new(),
+            safety_checks: Vec::new(),
+            tvl_lamports: None,
+            holders: Vec::new(),
+            creator: Some(creator),
+            fee_bps: None,
+            activated_at: None,
+            creator_stats: None,
+            rugged: None,
+        }
+    }
+
+    async fn closed_position(db: &Database, pool: &Pool, profit_loss: f64, simulated: bool) {
+        let mut position = Position::new(pool.address, 0.1);
+        position.profit_loss = Some(profit_loss);
+        position.simulated = simulated;
+        position.status = PositionStatus::Closed;
+        db.save_position(&position).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn positions_round_trip_through_every_column() {
+        let db = memory_db().await;
+        let pool = pool(Pubkey::new_unique());
+        db.save_pool(&pool).await.unwrap();
+
+        let mut position = Position::new(pool.address, 0.25);
+        position.position_address = Some(Pubkey::new_unique());
+        position.position_nft_mint = Some(Pubkey::new_unique());
+        position.token_a_amount_in = Some(5_000_000_000_000);
+        position.token_b_amount_in = Some(250_000_000);
+        position.token_a_refund = Some(3_000_000_000);
+        position.token_b_refund = Some(12_345);
+        position.entry_signature = Some("entry".to_string());
+        position.status = PositionStatus::Opening;
+        position.simulated = true;
+        let id = db.save_position(&position).await.unwrap();
+        position.id = Some(id);
+
+        let saved = db.get_position(id).await.unwrap().unwrap();
+        assert_eq!(saved.id, Some(id));
+        assert_eq!(saved.pool, pool.address);
+        assert_eq!(saved.position_address, position.position_address);
+        assert_eq!(saved.position_nft_mint, position.position_nft_mint);
+        assert_eq!(saved.created_at, position.created_at);
+        assert_eq!(saved.closed_at, None);
+        assert_eq!(saved.sol_invested, 0.25);
+        assert_eq!(saved.token_a_amount_in, Some(5_000_000_000_000));
+        assert_eq!(saved.token_b_amount_in, Some(250_000_000));
+        assert_eq!(saved.token_a_refund, Some(3_000_000_000));
+        assert_eq!(saved.token_b_refund, Some(12_345));
+        assert_eq!(saved.entry_signature.as_deref(), Some("entry"));
+        assert_eq!(saved.status, PositionStatus::Opening);
+        assert!(saved.simulated);
+        assert_eq!(saved.exit_reason, None);
+
+        position.status = PositionStatus::Exiting;
+        position.token_a_amount_out = Some(4_900_000_000_000);
+        position.token_b_amount_out = Some(260_000_000);
+        position.exit_signature = Some("exit".to_string());
+        position.fee_claimed = Some(0.015);
+        position.profit_loss = Some(0.01);
+        position.impermanent_loss = Some(-0.005);
+        position.exit_reason = Some(ExitReason::TakeProfit);
+        db.update_position(&position).await.unwrap();
+
+        let updated = db.get_position(id).await.unwrap().unwrap();
+        assert_eq!(updated.token_a_amount_out, Some(4_900_000_000_000));
+        assert_eq!(updated.token_b_amount_out, Some(260_000_000));
+        assert_eq!(updated.exit_signature.as_deref(), Some("exit"));
+        assert_eq!(updated.fee_claimed, Some(0.015));
+        assert_eq!(updated.profit_loss, Some(0.01));
+        assert_eq!(updated.impermanent_loss, Some(-0.005));
+        assert_eq!(updated.exit_reason, Some(ExitReason::TakeProfit));
+        assert_eq!(updated.status, PositionStatus::Exiting);
+        assert_eq!(updated.closed_at, None);
+        assert_eq!(db.get_open_positions().await.unwrap().len(), 1);
+
+        db.update_position_status(id, PositionStatus::Closed).await.unwrap();
+        let closed = db.get_position(id).await.unwrap().unwrap();
+        assert_eq!(closed.status, PositionStatus::Closed);
+        let closed_at = closed.closed_at.expect("closing stamps closed_at");
+        assert!(db.get_open_positions().await.unwrap().is_empty());
+
+        db.update_position_status(id, PositionStatus::Active).await.unwrap();
+        assert_eq!(db.get_position(id).await.unwrap().unwrap().closed_at, Some(closed_at));
+        assert!(db.get_position(id + 1).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn open_positions_leave_out_final_states_and_listing_is_newest_first() {
+        let db = memory_db().await;
+        let pool = pool(Pubkey::new_unique());
+        db.save_pool(&pool).await.unwrap();
+
+        let start = Utc::now();
+        let statuses = [
+            PositionStatus::Active,
+            PositionStatus::Closed,
+            PositionStatus::Opening,
+            PositionStatus::Failed,
+        ];
+        for (i, status) in statuses.into_iter().enumerate() {
+            let mut position = Position::new(pool.address, 0.1);
+            position.created_at = start + chrono::Duration::seconds(i as i64);
+            position.status = status;
+            db.save_position(&position).await.unwrap();
+        }
+
+        let open: Vec<_> = db.get_open_positions().await.unwrap()
+            .into_iter().map(|p| p.status).collect();
+        assert_eq!(open, vec![PositionStatus::Active, PositionStatus::Opening]);
+
+        let listed: Vec<_> = db.list_positions(Some(2)).await.unwrap()
+            .into_iter().map(|p| p.status).collect();
+        assert_eq!(listed, vec![PositionStatus::Failed, PositionStatus::Opening]);
+        assert_eq!(db.list_positions(None).await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn saving_a_pool_again_keeps_its_rug_check_and_positions() {
+        let db = memory_db().await;
+        let mut pool = pool(Pubkey::new_unique());
+        db.save_pool(&pool).await.unwrap();
+        closed_position(&db, &pool, 0.01, false).await;
+        db.set_pool_rugged(&pool.address, true).await.unwrap();
+
+        pool.analyzed = true;
+        pool.score = Some(0.7);
+        db.save_pool(&pool).await.unwrap();
+
+        let saved = db.get_pool(&pool.address).await.unwrap().unwrap();
+        assert!(saved.analyzed);
+        assert_eq!(saved.score, Some(0.7));
+        assert_eq!(saved.rugged, Some(true));
+        assert_eq!(db.get_positions_for_pool(&pool.address).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn score_breakdown_round_trips() {
+        let db = memory_db().await;
+        let mut pool = pool(Pubkey::new_unique());
+        pool.analyzed = true;
+        pool.score = Some(0.62);
+        pool.score_breakdown = vec![
+            ScoreFactor { name: "liquidity".to_string(), score: 0.8, weight: 0.75 },
+            ScoreFactor { name: "balance".to_string(), score: 0.08, weight: 0.25 },
+        ];
+        db.save_pool(&pool).await.unwrap();
+
+        let saved = db.get_pool(&pool.address).await.unwrap().unwrap();
+        let factors: Vec<_> = saved.score_breakdown.iter()
+            .map(|factor| (factor.name.as_str(), factor.score, factor.weight))
+            .collect();
+        assert_eq!(factors, vec![("liquidity", 0.8, 0.75), ("balance", 0.08, 0.25)]);
+    }
+
+    #[tokio::test]
+    async fn creator_stats_leave_out_paper_positions() {
+        let db = memory_db().await;
+        let creator = Pubkey::new_unique();
+        let earlier = pool(creator);
+        let current = pool(creator);
+        db.save_pool(&earlier).await.unwrap();
+        db.save_pool(&current).await.unwrap();
+        closed_position(&db, &earlier, -0.05, false).await;
+        closed_position(&db, &earlier, 0.5, true).await;
+        closed_position(&db, &current, 0.2, false).await;
+
+        let stats = db.get_creator_stats(&creator, &current.address).await.unwrap();
+        assert_eq!(stats.pools_created, 1);
+        assert_eq!(stats.profit_loss_sol, -0.05);
+    }
+}
@@ -5,4 +5,6 @@ pub mod models;
 pub mod monitoring;
 pub mod strategy;
 pub mod meteora;
+pub mod backtest;
+pub mod pricing;
 pub mod utils; 
\ No newline at end of file
@@ -1,27 +1,30 @@
-use log::{info, error};
+use log::{info, warn, error};
 use anyhow::{Result, Context};
 use dotenv::dotenv;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use chrono::Utc;
 use solana_sdk::pubkey::Pubkey;
 
-mod config;
-mod solana;
-mod db;
-mod models;
-mod monitoring;
-mod strategy;
-mod meteora;
-mod utils;
+use meteora_lp_sprinter::{config, solana, db, models, monitoring, strategy, meteora, pricing};
 
-use crate::monitoring::PoolMonitor;
+use monitoring::PoolMonitor;
 // Temporarily comment out for testing build
 // use monitoring::telegram::TelegramMonitor;
 use monitoring::websocket::MeteoraPoolMonitor;
+use monitoring::PoolRecorder;
 use models::pool::{Pool, TokenInfo};
 use strategy::analysis::{PoolAnalyzer, PoolCriteria};
+use strategy::scoring::ScoringPipeline;
+use strategy::reputation::CreatorReputation;
+use strategy::rug_guard::RugGuard;
+use strategy::{SprintStrategy, PositionManager, ExitPolicy};
+use meteora::MeteoraClient;
+
+// How long to wait for open positions to exit on shutdown
+const POSITION_UNWIND_TIMEOUT_SECS: u64 = 120;
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -34,8 +37,14 @@ async fn main() -> Result<()> {
     info!("Starting Meteora LP Sprinter...");
     
     // Load configuration
-    let config = config::load_config()?;
+    let mut config = config::load_config()?;
+    if std::env::args().any(|arg| arg == "--paper") {
+        config.paper_trading = true;
+    }
     info!("Configuration loaded");
+    if config.paper_trading {
+        info!("Paper trading enabled: positions are simulated and no transactions will be sent");
+    }
     
     // Initialize Solana infrastructure
     let solana_client = solana::create_client_from_config(&config);
@@ -54,8 +63,8 @@ async fn main() -> Result<()> {
     let wallet_path = std::env::var("WALLET_KEYPAIR_PATH")
         .unwrap_or_else(|_| "wallet-keypair.json".to_string());
         
-    let wallet_manager = solana::create_wallet_manager_from_config(&config, &wallet_path)
-        .with_context(|| format!("Failed to load wallet from {}", wallet_path))?;
+    let wallet_manager = Arc::new(solana::create_wallet_manager_from_config(&config, &wallet_path)
+        .with_context(|| format!("Failed to load wallet from {}", wallet_path))?);
     
     info!("Wallet loaded with pubkey: {}", wallet_manager.pubkey());
     
@@ -78,7 +87,7 @@ async fn main() -> Result<()> {
     
     // Check if balance is sufficient for operation
     let min_balance = 0.001; // 0.001 SOL
-    if !wallet_manager.has_sufficient_balance(0.0, true).await? {
+    if !config.paper_trading && !wallet_manager.has_sufficient_balance(0.0, true).await? {
         error!("Wallet balance too low for operation! Minimum: {} SOL", min_balance);
         return Err(anyhow::anyhow!("Insufficient wallet balance"));
     }
@@ -87,11 +96,77 @@ async fn main() -> Result<()> {
     let db = db::Database::new(&config.database_path).await?;
     info!("Database initialized");
     
-    // Initialize the pool analyzer with default criteria
-    let pool_analyzer = PoolAnalyzer::new(solana_client.clone());
-    let pool_criteria = PoolCriteria::default();
+    // Token prices are shared by the analyzer and the strategy so they share one cache
+    let price_source = pricing::price_source_from_config(&config, solana_client.clone())?;
+    
+    // Track how each creator's pools turn out, so serial ruggers can be penalized
+    let creator_reputation = CreatorReputation::new(
+        MeteoraClient::new(solana_client.clone()).with_price_source(price_source.clone()),
+        db.clone(),
+        config.rug_window_minutes,
+        config.rug_tvl_drop_percent,
+    );
+    
+    // Initialize the pool analyzer with the configured filter rules and score weights
+    let pool_analyzer = PoolAnalyzer::new(solana_client.clone())
+        .with_price_source(price_source.clone())
+        .with_scoring(ScoringPipeline::from_weights(&config.score_weights)?)
+        .with_reputation(creator_reputation.clone())
+        .with_max_transfer_fee_bps(config.pool_filters.max_transfer_fee_bps);
+    let pool_criteria = PoolCriteria::from_filters(&config.pool_filters)?;
     info!("Pool analyzer initialized with min score: {}", pool_criteria.min_score);
     
+    // Initialize the sprint strategy and the manager enforcing position limits
+    let mut sprint_strategy = SprintStrategy::new(
+        solana_client.clone(),
+        MeteoraClient::new(solana_client.clone()).with_price_source(price_source),
+        wallet_manager.clone(),
+        db.clone(),
+        config.position_duration_seconds,
+        config.fee_claim_interval_seconds,
+        config.slippage_bps,
+    )
+    .with_paper_trading(config.paper_trading)
+    .with_exit_policy(ExitPolicy::from_rules(&config.exit_rules)?);
+    if config.rug_guard.enabled {
+        sprint_strategy = sprint_strategy.with_rug_guard(
+            RugGuard::new(&config.rpc_url, solana_client.clone(), config.rug_guard.clone())
+        );
+        info!("Rug guard enabled: exit on a {}% SOL reserve drop within {}s or a {}% price drop",
+            config.rug_guard.quote_drop_percent, config.rug_guard.quote_drop_window_seconds, config.rug_guard.price_drop_percent);
+    }
+    let position_manager = Arc::new(PositionManager::new(
+        sprint_strategy,
+        config.min_sol_per_position,
+        config.max_sol_per_position,
+        config.max_concurrent_positions,
+        config.max_total_sol_at_risk,
+    ));
+    info!("Sprint strategy initialized: {} SOL per position, up to {} positions / {} SOL at risk",
+        config.max_sol_per_position, config.max_concurrent_positions, config.max_total_sol_at_risk);
+    
+    // Pick up any positions a previous run left open before taking on new ones
+    match position_manager.recover_open_positions().await {
+        Ok(0) => info!("No open positions to recover"),
+        Ok(count) => info!("Recovered {} open positions from a previous run", count),
+        Err(e) => error!("Failed to recover open positions: {}", e),
+    }
+    
+    // Optionally record every discovered pool for offline backtesting
+    let pool_recorder = if config.record_pools {
+        let recorder = PoolRecorder::new(
+            &config.rpc_url,
+            solana_client.clone(),
+            &config.recording_path,
+            config.recording_window_seconds,
+        )?;
+        info!("Recording discovered pools to {} for {}s each",
+            config.recording_path, config.recording_window_seconds);
+        Some(recorder)
+    } else {
+        None
+    };
+    
     // Create a channel for pool discovery
     let (pool_tx, mut pool_rx) = mpsc::channel::<Pool>(100);
     
@@ -139,13 +214,18 @@ async fn main() -> Result<()> {
     let pool_analyzer_clone = pool_analyzer;
     let pool_criteria_clone = pool_criteria;
     let db_clone = db.clone();
+    let position_manager_clone = position_manager.clone();
     
-    let process_pools_handle = tokio::spawn(async move {
+    let mut process_pools_handle = tokio::spawn(async move {
         info!("Starting pool processing loop");
         
         while let Some(mut pool) = pool_rx.recv().await {
             info!("New pool discovered: {}", pool.address);
             
+            if let Some(recorder) = &pool_recorder {
+                recorder.record(pool.address);
+            }
+            
             // Save the pool to the database
             match db.save_pool(&pool).await {
                 Ok(_) => info!("Saved pool {} to database", pool.address),
@@ -163,12 +243,19 @@ async fn main() -> Result<()> {
                         error!("Failed to update pool analysis in database: {}", e);
                     }
                     
+                    // Check back later whether the creator rugged it
+                    creator_reputation.watch(&pool);
+                    
                     // Check if the pool meets our criteria for liquidity provision
                     if pool_analyzer_clone.meets_criteria(&pool, &pool_criteria_clone) {
                         info!("Pool {} meets criteria for liquidity provision!", pool.address);
-                        // TODO: Implement liquidity provision strategy
-                    } else {
-                        info!("Pool {} does not meet criteria for liquidity provision", pool.address);
+                        
+                        let pool_address = pool.address;
+                        match position_manager_clone.open_position(pool).await {
+                            Ok(Some(amount_sol)) => info!("Started sprint in pool {} with {} SOL", pool_address, amount_sol),
+                            Ok(None) => {},
+                            Err(e) => error!("Failed to start sprint in pool {}: {}", pool_address, e),
+                        }
                     }
                 },
                 Err(e) => {
@@ -190,8 +277,14 @@ async fn main() -> Result<()> {
     // Close the pool channel to terminate the processing loop
     drop(pool_tx);
     
-    // Wait for the processing to complete
-    let _ = tokio::time::timeout(Duration::from_secs(5), process_pools_handle).await;
+    // Wait for the processing to complete, then stop it so it can't start new sprints
+    if tokio::time::timeout(Duration::from_secs(5), &mut process_pools_handle).await.is_err() {
+        warn!("Pool processing did not finish in time, aborting it");
+        process_pools_handle.abort();
+    }
+    
+    // Unwind every open position before exiting so no capital is left in a pool
+    position_manager.shutdown(Duration::from_secs(POSITION_UNWIND_TIMEOUT_SECS)).await;
     
     info!("Shutting down...");
     Ok(())
@@ -1,10 +1,25 @@
-use anyhow::{Result, anyhow};
-use log::debug;
+use anyhow::{Result, anyhow, Context};
+use chrono::Utc;
+use log::{debug, warn};
+use solana_sdk::account::Account;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::message::Message;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
 use solana_sdk::transaction::Transaction;
+use solana_transaction_status::{EncodedTransaction, UiInnerInstructions, UiInstruction, UiMessage, UiTransactionTokenBalance};
 use std::str::FromStr;
+use std::sync::Arc;
+use crate::config::Config;
+use crate::pricing::{self, PriceSource};
 use crate::solana::SolanaClient;
 use crate::solana::rpc_helpers;
+use crate::meteora::state::{PoolState, PositionState, ActivationType, BASIS_POINT_MAX};
+use crate::meteora::instructions::{self, PoolAccounts, PositionAccounts, AddLiquidityParameters, RemoveAllLiquidityParameters, SwapParameters};
+use crate::meteora::math;
+use crate::meteora::quote::{self, SwapQuote};
+use crate::utils::{sol_to_lamports, lamports_to_sol};
 
 // Meteora DAMM v2 program ID
 pub const METEORA_PROGRAM_ID: &str = "cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG";
@@ -15,25 +30,313 @@ pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
 // USDC mint address
 pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
 
+// USDT mint address
+pub const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB";
+
+// Compute budget for the wrap + swap + create position + add liquidity transaction
+const ADD_LIQUIDITY_COMPUTE_UNITS: u32 = 400_000;
+
+// Compute budget for the remove + claim + close + swap + unwrap transaction
+const REMOVE_LIQUIDITY_COMPUTE_UNITS: u32 = 400_000;
+
+// Maximum number of accounts the RPC returns from a single getMultipleAccounts call
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+// Approximate slot time, used to age pools that activate by slot
+const SLOT_SECONDS: f64 = 0.4;
+
 /// Pool information from Meteora
 #[derive(Debug, Clone)]
 pub struct PoolInfo {
     pub address: Pubkey,
     pub token_a_mint: Pubkey,
     pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
     pub token_a_amount: u64,
     pub token_b_amount: u64,
     pub fee_rate: u16,  // basis points
-    pub creation_slot: u64,
+    pub sqrt_price: u128,
+    pub liquidity: u128,
+    pub creator: Pubkey,
+    pub creation_slot: Option<u64>,
     pub creation_time: Option<u64>,
     pub volume_24h: Option<u64>,
     pub fees_24h: Option<u64>,
+    /// Slot or timestamp the pool was read at, which its fee schedule is evaluated against
+    pub current_point: u64,
+    pub state: PoolState,
+}
+
+/// Unsigned transaction that opens a position, with the keys needed to sign and track it
+#[derive(Debug)]
+pub struct AddLiquidityTransaction {
+    pub transaction: Transaction,
+    /// Freshly generated position NFT mint, which must co-sign the transaction
+    pub position_nft_mint: Keypair,
+    pub position: Pubkey,
+    pub liquidity_delta: u128,
+    /// Maximum amount of token A deposited
+    pub token_a_amount: u64,
+    /// Maximum amount of token B deposited
+    pub token_b_amount: u64,
+    /// Token A deposited at the quoted price
+    pub token_a_deposit: u64,
+    /// Token B deposited at the quoted price
+    pub token_b_deposit: u64,
+    /// Token A bought at the quoted price but not deposited, left in the wallet
+    pub token_a_refund: u64,
+    /// Token B bought at the quoted price but not deposited, left in the wallet
+    pub token_b_refund: u64,
+}
+
+/// Unsigned transaction that exits a position, with the bounds it enforces
+#[derive(Debug)]
+pub struct RemoveLiquidityTransaction {
+    pub transaction: Transaction,
+    /// Minimum amount of token A withdrawn, below which the transaction fails
+    pub token_a_minimum: u64,
+    /// Minimum amount of token B withdrawn, below which the transaction fails
+    pub token_b_minimum: u64,
+    /// Fees expected to be claimed in token A
+    pub fee_a: u64,
+    /// Fees expected to be claimed in token B
+    pub fee_b: u64,
+    /// Minimum lamports received when selling the non-SOL side (SOL pools only)
+    pub minimum_sol_out: Option<u64>,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    /// Indexes of the remove liquidity and claim fee instructions in the transaction
+    pub withdraw_instructions: [u8; 2],
+}
+
+/// Unsigned transaction that claims a position's fees, with the amounts it expects to receive
+#[derive(Debug)]
+pub struct ClaimFeesTransaction {
+    pub transaction: Transaction,
+    pub position: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    /// Fees claimable in token A when the transaction was built
+    pub expected_fee_a: u64,
+    /// Fees claimable in token B when the transaction was built
+    pub expected_fee_b: u64,
+}
+
+/// Fees actually received by a confirmed claim
+#[derive(Debug, Clone)]
+pub struct ClaimedFees {
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    /// Combined value of the claimed tokens in SOL
+    pub value_sol: f64,
+}
+
+/// A paper-trading position that exists only in memory
+///
+/// Its state mirrors the position account a real entry would have created, so fees accrue
+/// from the live pool's fee accumulators exactly as they would on-chain.
+#[derive(Debug, Clone)]
+pub struct SimulatedPosition {
+    pub pool: Pubkey,
+    pub state: PositionState,
+    /// Amount of token A deposited
+    pub token_a_amount: u64,
+    /// Amount of token B deposited
+    pub token_b_amount: u64,
+    /// Token A bought for the deposit but left over once the liquidity was sized
+    pub token_a_refund: u64,
+    /// Token B bought for the deposit but left over once the liquidity was sized
+    pub token_b_refund: u64,
+}
+
+/// Outcome of a simulated exit at the pool's current price
+#[derive(Debug, Clone)]
+pub struct SimulatedExit {
+    pub token_a_amount: u64,
+    pub token_b_amount: u64,
+    pub fee_a: u64,
+    pub fee_b: u64,
+}
+
+impl PoolInfo {
+    /// Build pool information from a decoded pool, its vault reserves and the current slot or timestamp
+    pub fn from_state(
+        address: Pubkey,
+        state: PoolState,
+        token_a_amount: u64,
+        token_b_amount: u64,
+        current_point: u64,
+    ) -> Self {
+        let (creation_slot, creation_time) = match state.activation_type() {
+            ActivationType::Slot => (Some(state.activation_point), None),
+            ActivationType::Timestamp => (None, Some(state.activation_point)),
+        };
+        
+        Self {
+            address,
+            token_a_mint: state.token_a_mint,
+            token_b_mint: state.token_b_mint,
+            token_a_vault: state.token_a_vault,
+            token_b_vault: state.token_b_vault,
+            token_a_amount,
+            token_b_amount,
+            // Fee schedulers decay the base fee over time, so use the fee in effect at this point
+            fee_rate: state.base_fee_bps(current_point),
+            sqrt_price: state.sqrt_price,
+            liquidity: state.liquidity,
+            creator: state.creator,
+            creation_slot,
+            creation_time,
+            volume_24h: None,
+            fees_24h: None,
+            current_point,
+            state,
+        }
+    }
+    
+    /// Seconds since the pool activated at the time it was read (0 if it has not activated yet)
+    pub fn age_seconds(&self) -> f64 {
+        let elapsed = self.current_point.saturating_sub(self.state.activation_point) as f64;
+        match self.state.activation_type() {
+            ActivationType::Slot => elapsed * SLOT_SECONDS,
+            ActivationType::Timestamp => elapsed,
+        }
+    }
+    
+    /// Whether SOL is token A (`Some(true)`) or token B (`Some(false)`), if the pool is paired with SOL
+    pub fn sol_is_token_a(&self) -> Option<bool> {
+        sol_side(&self.state)
+    }
+    
+    /// Value raw token amounts in SOL at the pool's spot price, if the pool is paired with SOL
+    pub fn spot_value_in_sol(&self, token_a_amount: u64, token_b_amount: u64) -> Option<f64> {
+        // Raw token B per raw token A, so no decimals are involved
+        let price = math::sqrt_price_to_price(self.sqrt_price);
+        
+        let lamports = match self.sol_is_token_a()? {
+            true if price > 0.0 => token_a_amount as f64 + token_b_amount as f64 / price,
+            true => token_a_amount as f64,
+            false => token_b_amount as f64 + token_a_amount as f64 * price,
+        };
+        
+        Some(lamports / 1_000_000_000.0)
+    }
+    
+    /// SOL received for a pair of token amounts once the non-SOL side is sold through the pool,
+    /// if the pool is paired with SOL
+    pub fn sell_value_in_sol(&self, token_a_amount: u64, token_b_amount: u64) -> Option<f64> {
+        let sol_is_a = self.sol_is_token_a()?;
+        let (sol_amount, token_amount) = if sol_is_a {
+            (token_a_amount, token_b_amount)
+        } else {
+            (token_b_amount, token_a_amount)
+        };
+        
+        let swapped = if token_amount > 0 {
+            self.quote_swap(!sol_is_a, token_amount)
+                .map(|swap| swap.amount_out)
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        
+        Some(lamports_to_sol(sol_amount.saturating_add(swapped)))
+    }
+    
+    /// Token amounts withdrawn by removing `liquidity` at the pool's current price
+    pub fn withdrawal_amounts(&self, liquidity: u128) -> Option<(u64, u64)> {
+        let state = &self.state;
+        let token_a_amount = math::get_delta_amount_a(state.sqrt_price, state.sqrt_max_price, liquidity, false)?;
+        let token_b_amount = math::get_delta_amount_b(state.sqrt_min_price, state.sqrt_price, liquidity, false)?;
+        Some((token_a_amount, token_b_amount))
+    }
+    
+    /// Quote swapping an exact amount of token A (`a_to_b`) or token B into the pool
+    pub fn quote_swap(&self, a_to_b: bool, amount_in: u64) -> Result<SwapQuote> {
+        quote::quote_exact_in(&self.state, a_to_b, amount_in, self.current_point)
+            .with_context(|| format!("Failed to quote swap in pool {}", self.address))
+    }
+}
+
+impl SimulatedPosition {
+    /// Open a position of `amount_sol` at the pool's state, as `add_liquidity` would
+    pub fn open(pool_info: &PoolInfo, amount_sol: f64, slippage_bps: u16) -> Result<Self> {
+        let pool = pool_info.address;
+        let state = &pool_info.state;
+        let quote = quote_sol_deposit(pool_info, amount_sol, slippage_bps)?;
+        
+        let position_state = PositionState {
+            pool,
+            fee_a_per_token_checkpoint: state.fee_a_per_liquidity,
+            fee_b_per_token_checkpoint: state.fee_b_per_liquidity,
+            unlocked_liquidity: quote.liquidity_delta,
+            ..Default::default()
+        };
+        
+        debug!("Simulated add liquidity to pool {}: {} lamports, liquidity {}",
+            pool, quote.amount_lamports, quote.liquidity_delta);
+        
+        Ok(Self {
+            pool,
+            state: position_state,
+            token_a_amount: quote.token_a_deposit,
+            token_b_amount: quote.token_b_deposit,
+            token_a_refund: quote.token_a_refund,
+            token_b_refund: quote.token_b_refund,
+        })
+    }
+    
+    /// Claim the fees accrued up to the pool's state, moving the checkpoints to its accumulators
+    pub fn claim(&mut self, pool_info: &PoolInfo) -> ClaimedFees {
+        let state = &pool_info.state;
+        let (token_a_amount, token_b_amount) = self.state.pending_fees(state);
+        
+        self.state.fee_a_per_token_checkpoint = state.fee_a_per_liquidity;
+        self.state.fee_b_per_token_checkpoint = state.fee_b_per_liquidity;
+        self.state.fee_a_pending = 0;
+        self.state.fee_b_pending = 0;
+        
+        ClaimedFees {
+            token_a_amount,
+            token_b_amount,
+            value_sol: pool_info.sell_value_in_sol(token_a_amount, token_b_amount).unwrap_or(0.0),
+        }
+    }
+    
+    /// Remove all liquidity and claim the pending fees at the pool's state
+    pub fn exit(&self, pool_info: &PoolInfo) -> Result<SimulatedExit> {
+        let (token_a_amount, token_b_amount) = pool_info.withdrawal_amounts(self.state.unlocked_liquidity)
+            .ok_or_else(|| anyhow!("Failed to compute withdrawal for pool {}", self.pool))?;
+        let (fee_a, fee_b) = self.state.pending_fees(&pool_info.state);
+        
+        Ok(SimulatedExit {
+            token_a_amount,
+            token_b_amount,
+            fee_a,
+            fee_b,
+        })
+    }
+}
+
+/// A DAMM v2 position found on-chain through the position NFT held by a wallet
+#[derive(Debug, Clone)]
+pub struct OwnedPosition {
+    pub position: Pubkey,
+    pub position_nft_mint: Pubkey,
+    pub pool: Pubkey,
+    /// Unlocked, vested and permanently locked liquidity combined
+    pub liquidity: u128,
+    pub unclaimed_fee_a: u64,
+    pub unclaimed_fee_b: u64,
+    pub state: PositionState,
 }
 
 /// Client for interacting with Meteora DAMM v2 pools
 pub struct MeteoraClient {
     client: SolanaClient,
     program_id: Pubkey,
+    price_source: Arc<dyn PriceSource>,
 }
 
 impl MeteoraClient {
@@ -43,60 +346,53 @@ impl MeteoraClient {
         let program_id = Pubkey::from_str(METEORA_PROGRAM_ID)
             .expect("Failed to parse Meteora program ID");
         
-        Self { client, program_id }
+        // Price from on-chain pools with the default cache settings until told otherwise
+        let price_source = pricing::price_source_from_config(&Config::default(), client.clone())
+            .expect("Failed to build default price source");
+        
+        Self { client, program_id, price_source }
+    }
+    
+    /// Use a different source for pricing tokens in SOL
+    pub fn with_price_source(mut self, price_source: Arc<dyn PriceSource>) -> Self {
+        self.price_source = price_source;
+        self
     }
     
     /// Get information about a Meteora pool
     pub async fn get_pool_info(&self, pool_address: &Pubkey) -> Result<PoolInfo> {
+        Ok(self.get_pool_info_with_data(pool_address).await?.0)
+    }
+    
+    /// Get information about a Meteora pool along with its raw account data
+    pub async fn get_pool_info_with_data(&self, pool_address: &Pubkey) -> Result<(PoolInfo, Vec<u8>)> {
         debug!("Fetching pool info for {}", pool_address);
         
         // Get the pool account data
         let account = self.client.get_account(pool_address)?;
         
-        // Note: In a real implementation, we would parse the account data according to 
-        // Meteora DAMM v2's layout. Since we don't have the exact layout details,
-        // we're using simplified approximations.
-        
-        if account.data.len() < 150 {
-            return Err(anyhow!("Pool account data too short"));
+        if account.owner != self.program_id {
+            return Err(anyhow!("Account {} is not owned by the Meteora DAMM v2 program", pool_address));
         }
         
-        // Extract token mints (specific offsets would depend on actual Meteora pool layout)
-        // The actual implementation would need to know the exact layout of the pool account
-        let token_a_mint = Pubkey::from(<[u8; 32]>::try_from(&account.data[8..40]).unwrap());
-        let token_b_mint = Pubkey::from(<[u8; 32]>::try_from(&account.data[40..72]).unwrap());
-        
-        // Extract token amounts
-        let token_a_amount = u64::from_le_bytes([
-            account.data[80], account.data[81], account.data[82], account.data[83],
-            account.data[84], account.data[85], account.data[86], account.data[87],
-        ]);
-        
-        let token_b_amount = u64::from_le_bytes([
-            account.data[88], account.data[89], account.data[90], account.data[91],
-            account.data[92], account.data[93], account.data[94], account.data[95],
-        ]);
-        
-        // Meteora pools typically have a 0.25% (25 basis points) fee
-        let fee_rate = 25;
+        let state = PoolState::from_account_data(&account.data)?;
         
-        // Get creation slot from account's creation slot
-        let creation_slot = self.client.get_slot()?;
+        // Reserves live in the pool's token vaults
+        let vaults = self.client.get_multiple_accounts(&[state.token_a_vault, state.token_b_vault])?;
+        let token_a_amount = vault_amount(vaults.first().and_then(|a| a.as_ref()))
+            .with_context(|| format!("Failed to read token A vault {}", state.token_a_vault))?;
+        let token_b_amount = vault_amount(vaults.get(1).and_then(|a| a.as_ref()))
+            .with_context(|| format!("Failed to read token B vault {}", state.token_b_vault))?;
         
-        let pool_info = PoolInfo {
-            address: *pool_address,
-            token_a_mint,
-            token_b_mint,
-            token_a_amount,
-            token_b_amount,
-            fee_rate,
-            creation_slot,
-            creation_time: None,
-            volume_24h: None,
-            fees_24h: None,
+        // The activation point is either a slot or a unix timestamp depending on the pool
+        let current_point = match state.activation_type() {
+            ActivationType::Slot => self.client.get_slot()?,
+            ActivationType::Timestamp => Utc::now().timestamp().max(0) as u64,
         };
         
-        Ok(pool_info)
+        let pool_info = PoolInfo::from_state(*pool_address, state, token_a_amount, token_b_amount, current_point);
+        
+        Ok((pool_info, account.data))
     }
     
     /// Get pool TVL (Total Value Locked) in SOL
@@ -124,9 +420,19 @@ impl MeteoraClient {
             Err(_) => 9, // Default to 9 decimals
         };
         
-        // Get token prices in SOL
-        let token_a_price = self.get_token_price_in_sol(&pool_info.token_a_mint).await?;
-        let token_b_price = self.get_token_price_in_sol(&pool_info.token_b_mint).await?;
+        // A SOL pool prices its other token by itself; anything else goes through the price source
+        let own_price = |mint: &Pubkey, decimals: u8, other_decimals: u8| {
+            pricing::pool_price(&pool_info.state, mint, decimals, other_decimals)
+                .ok_or_else(|| anyhow!("Pool {} has no price", pool_info.address))
+        };
+        let (token_a_price, token_b_price) = match pool_info.sol_is_token_a() {
+            Some(true) => (1.0, own_price(&pool_info.token_b_mint, token_b_decimals, token_a_decimals)?),
+            Some(false) => (own_price(&pool_info.token_a_mint, token_a_decimals, token_b_decimals)?, 1.0),
+            None => (
+                self.get_token_price_in_sol(&pool_info.token_a_mint).await?,
+                self.get_token_price_in_sol(&pool_info.token_b_mint).await?,
+            ),
+        };
         
         // Calculate token values
         let token_a_value = (pool_info.token_a_amount as f64 * token_a_price) / 
@@ -143,83 +449,797 @@ impl MeteoraClient {
         // Get TVL
         let tvl = self.get_pool_tvl(pool_info).await?;
         
-        // Estimate daily volume based on pool characteristics
-        let estimated_daily_volume = if let Some(volume) = pool_info.volume_24h {
-            (volume as f64) / 1_000_000_000.0 // Convert lamports to SOL
+        Ok(estimate_fee_apy(pool_info, tvl))
+    }
+    
+    /// Get the price of a token in SOL
+    async fn get_token_price_in_sol(&self, mint: &Pubkey) -> Result<f64> {
+        // WSOL (wrapped SOL) is worth 1 SOL by definition
+        if *mint == instructions::wsol_mint() {
+            return Ok(1.0);
+        }
+        
+        self.price_source.price_in_sol(mint).await
+            .with_context(|| format!("Failed to price {} ({})", mint, self.price_source.name()))
+    }
+    
+    /// Build a transaction that opens a new position in a SOL pool and deposits `amount_sol` into it
+    ///
+    /// Part of the SOL is swapped into the other token so both sides can be deposited at the
+    /// current price. The returned transaction is unsigned and must be signed by the owner and
+    /// the returned position NFT mint keypair.
+    pub async fn add_liquidity(
+        &self,
+        owner: &Pubkey,
+        pool: Pubkey,
+        amount_sol: f64,
+        slippage_bps: u16,
+    ) -> Result<AddLiquidityTransaction> {
+        let pool_info = self.get_pool_info(&pool).await?;
+        let state = &pool_info.state;
+        let DepositQuote {
+            sol_is_a,
+            amount_lamports,
+            swap_amount,
+            swap_minimum_out: minimum_out,
+            token_a_amount,
+            token_b_amount,
+            token_a_deposit,
+            token_b_deposit,
+            token_a_refund,
+            token_b_refund,
+            liquidity_delta,
+            ..
+        } = quote_sol_deposit(&pool_info, amount_sol, slippage_bps)?;
+        
+        let position_nft_mint = Keypair::new();
+        let pool_accounts = PoolAccounts::from_state(pool, state);
+        let position_accounts = PositionAccounts::new(*owner, position_nft_mint.pubkey(), &pool_accounts);
+        let (sol_account, token_account) = if sol_is_a {
+            (position_accounts.token_a_account, position_accounts.token_b_account)
         } else {
-            // If no volume data, estimate based on TVL and age of the pool
-            // For newer pools (which we're targeting), volume can be higher relative to TVL
-            // due to initial trading activity
+            (position_accounts.token_b_account, position_accounts.token_a_account)
+        };
+        
+        let params = AddLiquidityParameters {
+            liquidity_delta,
+            token_a_amount_threshold: token_a_amount,
+            token_b_amount_threshold: token_b_amount,
+        };
+        
+        let tx_instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(ADD_LIQUIDITY_COMPUTE_UNITS),
+            instructions::create_associated_token_account_idempotent(
+                owner, owner, &pool_accounts.token_a_mint, &pool_accounts.token_a_program),
+            instructions::create_associated_token_account_idempotent(
+                owner, owner, &pool_accounts.token_b_mint, &pool_accounts.token_b_program),
+            // Wrap the full position size into WSOL
+            system_instruction::transfer(owner, &sol_account, amount_lamports),
+            instructions::sync_native(&sol_account),
+            instructions::swap(&pool_accounts, owner, &sol_account, &token_account, &SwapParameters {
+                amount_in: swap_amount,
+                minimum_amount_out: minimum_out,
+            }),
+            instructions::create_position(owner, owner, &position_nft_mint.pubkey(), &pool),
+            instructions::add_liquidity(&pool_accounts, &position_accounts, &params),
+            // Unwrap whatever SOL was not deposited
+            instructions::close_token_account(&sol_account, owner, owner, &instructions::token_program_for_flag(0)),
+        ];
+        
+        let blockhash = self.client.get_latest_blockhash()?;
+        let message = Message::new_with_blockhash(&tx_instructions, Some(owner), &blockhash);
+        
+        debug!("Built add liquidity transaction for pool {}: {} lamports, liquidity delta {}",
+            pool, amount_lamports, liquidity_delta);
+        
+        Ok(AddLiquidityTransaction {
+            transaction: Transaction::new_unsigned(message),
+            position: position_accounts.position,
+            position_nft_mint,
+            liquidity_delta,
+            token_a_amount,
+            token_b_amount,
+            token_a_deposit,
+            token_b_deposit,
+            token_a_refund,
+            token_b_refund,
+        })
+    }
+    
+    /// Get the decoded state of a DAMM v2 position account
+    pub async fn get_position_state(&self, position: &Pubkey) -> Result<PositionState> {
+        let account = self.client.get_account(position)?;
+        
+        if account.owner != self.program_id {
+            return Err(anyhow!("Account {} is not owned by the Meteora DAMM v2 program", position));
+        }
+        
+        PositionState::from_account_data(&account.data)
+    }
+    
+    /// Get a position and the wallet holding its NFT, or `None` if the position account no longer exists
+    pub async fn get_position_and_owner(&self, position: &Pubkey) -> Result<Option<(PositionState, Option<Pubkey>)>> {
+        // get_multiple_accounts reports missing accounts as None instead of retrying on an error
+        let account = match self.client.get_multiple_accounts(&[*position])?.pop().flatten() {
+            Some(account) => account,
+            None => return Ok(None),
+        };
+        
+        if account.owner != self.program_id {
+            return Err(anyhow!("Account {} is not owned by the Meteora DAMM v2 program", position));
+        }
+        
+        let state = PositionState::from_account_data(&account.data)?;
+        
+        // The position NFT is held in a program-derived token account whose owner field is the wallet
+        let nft_account = instructions::derive_position_nft_account(&state.nft_mint);
+        let owner = self.client.get_multiple_accounts(&[nft_account])?
+            .pop()
+            .flatten()
+            .filter(|a| rpc_helpers::token_account_amount(&a.data).unwrap_or(0) > 0)
+            .map(|a| Pubkey::new_from_array(a.data[32..64].try_into().unwrap_or([0u8; 32])));
+        
+        Ok(Some((state, owner)))
+    }
+    
+    /// Find every DAMM v2 position whose NFT is held by the owner, independently of the database
+    ///
+    /// Scans the owner's Token-2022 accounts for single-token NFTs, derives the position account
+    /// of each mint and decodes the ones that exist, along with their pools for unclaimed fees.
+    pub async fn get_positions_by_owner(&self, owner: &Pubkey) -> Result<Vec<OwnedPosition>> {
+        let token_2022_program = Pubkey::from_str(rpc_helpers::TOKEN_2022_PROGRAM_ID)?;
+        
+        // Position NFTs are Token-2022 mints, so only the owner's Token-2022 accounts can hold one
+        let token_accounts = self.client.get_token_accounts_by_owner(owner, &token_2022_program)?;
+        
+        let nft_mints: Vec<Pubkey> = token_accounts.iter()
+            .filter(|(_, account)| rpc_helpers::token_account_amount(&account.data) == Some(1))
+            .map(|(_, account)| Pubkey::new_from_array(account.data[..32].try_into().unwrap_or([0u8; 32])))
+            .collect();
+        
+        let position_addresses: Vec<Pubkey> = nft_mints.iter()
+            .map(instructions::derive_position)
+            .collect();
+        
+        let mut positions = Vec::new();
+        for (addresses, mints) in position_addresses.chunks(MAX_MULTIPLE_ACCOUNTS).zip(nft_mints.chunks(MAX_MULTIPLE_ACCOUNTS)) {
+            let accounts = self.client.get_multiple_accounts(addresses)?;
+            
+            for ((address, mint), account) in addresses.iter().zip(mints).zip(accounts) {
+                // Most NFTs are not position NFTs, so their derived position account does not exist
+                let account = match account {
+                    Some(account) if account.owner == self.program_id => account,
+                    _ => continue,
+                };
+                
+                match PositionState::from_account_data(&account.data) {
+                    Ok(state) => positions.push((*address, *mint, state)),
+                    Err(e) => warn!("Failed to decode position {}: {}", address, e),
+                }
+            }
+        }
+        
+        // Fetch each pool once to work out the unclaimed fees of its positions
+        let mut pool_addresses: Vec<Pubkey> = positions.iter().map(|(_, _, state)| state.pool).collect();
+        pool_addresses.sort();
+        pool_addresses.dedup();
+        
+        let mut pools = std::collections::HashMap::new();
+        for chunk in pool_addresses.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let accounts = self.client.get_multiple_accounts(chunk)?;
+            
+            for (address, account) in chunk.iter().zip(accounts) {
+                match account.map(|a| PoolState::from_account_data(&a.data)) {
+                    Some(Ok(pool)) => { pools.insert(*address, pool); },
+                    Some(Err(e)) => warn!("Failed to decode pool {}: {}", address, e),
+                    None => warn!("Pool {} of an owned position no longer exists", address),
+                }
+            }
+        }
+        
+        let owned: Vec<OwnedPosition> = positions.into_iter()
+            .map(|(position, position_nft_mint, state)| {
+                let (unclaimed_fee_a, unclaimed_fee_b) = pools.get(&state.pool)
+                    .map(|pool| state.pending_fees(pool))
+                    .unwrap_or((0, 0));
+                
+                OwnedPosition {
+                    position,
+                    position_nft_mint,
+                    pool: state.pool,
+                    liquidity: state.total_liquidity(),
+                    unclaimed_fee_a,
+                    unclaimed_fee_b,
+                    state,
+                }
+            })
+            .collect();
+        
+        debug!("Found {} DAMM v2 positions owned by {}", owned.len(), owner);
+        Ok(owned)
+    }
+    
+    /// Build a transaction that fully exits a position
+    ///
+    /// Removes all unlocked liquidity with minimum-out bounds, claims outstanding fees, closes the
+    /// position and its NFT to reclaim rent, and for SOL pools sells the other token back to SOL
+    /// and unwraps the WSOL account so proceeds land as native SOL in the owner's wallet.
+    pub async fn remove_liquidity(
+        &self,
+        owner: &Pubkey,
+        position: Pubkey,
+        slippage_bps: u16,
+    ) -> Result<RemoveLiquidityTransaction> {
+        let position_state = self.get_position_state(&position).await?;
+        let pool_info = self.get_pool_info(&position_state.pool).await?;
+        let state = &pool_info.state;
+        let liquidity = position_state.unlocked_liquidity;
+        
+        // Expected withdrawal at the current price; anything worse than the slippage bound aborts
+        let expected_a = math::get_delta_amount_a(state.sqrt_price, state.sqrt_max_price, liquidity, false)
+            .ok_or_else(|| anyhow!("Failed to compute token A amount for position {}", position))?;
+        let expected_b = math::get_delta_amount_b(state.sqrt_min_price, state.sqrt_price, liquidity, false)
+            .ok_or_else(|| anyhow!("Failed to compute token B amount for position {}", position))?;
+        let token_a_minimum = apply_slippage(expected_a, slippage_bps);
+        let token_b_minimum = apply_slippage(expected_b, slippage_bps);
+        let (fee_a, fee_b) = position_state.pending_fees(state);
+        
+        let pool_accounts = PoolAccounts::from_state(position_state.pool, state);
+        let position_accounts = PositionAccounts::new(*owner, position_state.nft_mint, &pool_accounts);
+        
+        let mut tx_instructions = vec![
+            ComputeBudgetInstruction::set_compute_unit_limit(REMOVE_LIQUIDITY_COMPUTE_UNITS),
+            instructions::create_associated_token_account_idempotent(
+                owner, owner, &pool_accounts.token_a_mint, &pool_accounts.token_a_program),
+            instructions::create_associated_token_account_idempotent(
+                owner, owner, &pool_accounts.token_b_mint, &pool_accounts.token_b_program),
+            instructions::remove_all_liquidity(&pool_accounts, &position_accounts, &RemoveAllLiquidityParameters {
+                token_a_amount_threshold: token_a_minimum,
+                token_b_amount_threshold: token_b_minimum,
+            }),
+            // Fees must be claimed after the final liquidity update so the position is empty
+            instructions::claim_position_fee(&pool_accounts, &position_accounts),
+        ];
+        let withdraw_instructions = [tx_instructions.len() as u8 - 2, tx_instructions.len() as u8 - 1];
+        
+        if position_state.vested_liquidity == 0 && position_state.permanent_locked_liquidity == 0 {
+            tx_instructions.push(instructions::close_position(&position_state.pool, &position_accounts, owner));
+        } else {
+            warn!("Position {} has locked liquidity and cannot be closed", position);
+        }
+        
+        let mut minimum_sol_out = None;
+        if let Some(sol_is_a) = sol_side(state) {
+            let (sol_account, token_account) = if sol_is_a {
+                (position_accounts.token_a_account, position_accounts.token_b_account)
+            } else {
+                (position_accounts.token_b_account, position_accounts.token_a_account)
+            };
             
-            // Base estimate: 50% of TVL traded daily
-            let mut volume_estimate = tvl * 0.5;
+            // Sell the guaranteed amount of the other token back into SOL
+            let token_amount = if sol_is_a {
+                token_b_minimum.saturating_add(fee_b)
+            } else {
+                token_a_minimum.saturating_add(fee_a)
+            };
             
-            // Adjust based on token types - if one of the tokens is a major token (SOL/USDC),
-            // volume tends to be higher
-            let token_a_str = pool_info.token_a_mint.to_string();
-            let token_b_str = pool_info.token_b_mint.to_string();
+            let mut state_after = state.clone();
+            state_after.liquidity = state_after.liquidity.saturating_sub(liquidity);
             
-            if token_a_str == WSOL_MINT || token_a_str == USDC_MINT || 
-               token_b_str == WSOL_MINT || token_b_str == USDC_MINT {
-                volume_estimate *= 1.5; // 50% higher volume for pools with major tokens
+            match quote::quote_exact_in(&state_after, !sol_is_a, token_amount, pool_info.current_point) {
+                Ok(swap) if token_amount > 0 => {
+                    let minimum_out = apply_slippage(swap.amount_out, slippage_bps);
+                    tx_instructions.push(instructions::swap(&pool_accounts, owner, &token_account, &sol_account, &SwapParameters {
+                        amount_in: token_amount,
+                        minimum_amount_out: minimum_out,
+                    }));
+                    minimum_sol_out = Some(minimum_out);
+                },
+                Ok(_) => {},
+                Err(e) => warn!("Cannot sell {} tokens from position {}: {}", token_amount, position, e),
             }
             
-            volume_estimate
-        };
+            // Unwrap all WSOL so the proceeds land as native SOL
+            tx_instructions.push(instructions::close_token_account(
+                &sol_account, owner, owner, &instructions::token_program_for_flag(0)));
+        }
         
-        // Calculate daily fees
-        let daily_fee = estimated_daily_volume * (pool_info.fee_rate as f64 / 10000.0);
+        let blockhash = self.client.get_latest_blockhash()?;
+        let message = Message::new_with_blockhash(&tx_instructions, Some(owner), &blockhash);
         
-        // Calculate fee APY
-        // Annual fees / TVL
-        let fee_apy = if tvl > 0.0 {
-            (daily_fee * 365.0) / tvl
-        } else {
-            0.0
+        debug!("Built remove liquidity transaction for position {}: min out {} / {}, fees {} / {}",
+            position, token_a_minimum, token_b_minimum, fee_a, fee_b);
+        
+        Ok(RemoveLiquidityTransaction {
+            transaction: Transaction::new_unsigned(message),
+            token_a_minimum,
+            token_b_minimum,
+            fee_a,
+            fee_b,
+            minimum_sol_out,
+            token_a_vault: pool_accounts.token_a_vault,
+            token_b_vault: pool_accounts.token_b_vault,
+            withdraw_instructions,
+        })
+    }
+    
+    /// Build a transaction that claims the fees accrued by a position
+    ///
+    /// The returned claim carries the fee amounts claimable at build time; pass it to
+    /// `record_claimed_fees` once the transaction confirms to get the amounts actually received.
+    pub async fn claim_fees(&self, owner: &Pubkey, position: Pubkey) -> Result<ClaimFeesTransaction> {
+        let position_state = self.get_position_state(&position).await?;
+        let pool_info = self.get_pool_info(&position_state.pool).await?;
+        let state = &pool_info.state;
+        
+        let (expected_fee_a, expected_fee_b) = position_state.pending_fees(state);
+        
+        let pool_accounts = PoolAccounts::from_state(position_state.pool, state);
+        let position_accounts = PositionAccounts::new(*owner, position_state.nft_mint, &pool_accounts);
+        
+        let tx_instructions = vec![
+            instructions::create_associated_token_account_idempotent(
+                owner, owner, &pool_accounts.token_a_mint, &pool_accounts.token_a_program),
+            instructions::create_associated_token_account_idempotent(
+                owner, owner, &pool_accounts.token_b_mint, &pool_accounts.token_b_program),
+            instructions::claim_position_fee(&pool_accounts, &position_accounts),
+        ];
+        
+        let blockhash = self.client.get_latest_blockhash()?;
+        let message = Message::new_with_blockhash(&tx_instructions, Some(owner), &blockhash);
+        
+        debug!("Built claim fees transaction for position {}: expecting {} / {}",
+            position, expected_fee_a, expected_fee_b);
+        
+        Ok(ClaimFeesTransaction {
+            transaction: Transaction::new_unsigned(message),
+            position,
+            token_a_mint: pool_accounts.token_a_mint,
+            token_b_mint: pool_accounts.token_b_mint,
+            expected_fee_a,
+            expected_fee_b,
+        })
+    }
+    
+    /// Read the fees actually received by a confirmed claim transaction from its token balances
+    pub async fn record_claimed_fees(
+        &self,
+        owner: &Pubkey,
+        claim: &ClaimFeesTransaction,
+        signature: &str,
+    ) -> Result<ClaimedFees> {
+        let confirmed = self.client.get_transaction(signature)?;
+        let meta = confirmed.transaction.meta
+            .ok_or_else(|| anyhow!("Transaction {} has no status metadata", signature))?;
+        
+        if let Some(err) = meta.err {
+            return Err(anyhow!("Claim transaction {} failed: {:?}", signature, err));
+        }
+        
+        let pre_balances: Vec<UiTransactionTokenBalance> = Option::from(meta.pre_token_balances).unwrap_or_default();
+        let post_balances: Vec<UiTransactionTokenBalance> = Option::from(meta.post_token_balances).unwrap_or_default();
+        
+        let received = |mint: &Pubkey| {
+            token_balance(&post_balances, owner, mint)
+                .saturating_sub(token_balance(&pre_balances, owner, mint))
         };
+        let token_a_amount = received(&claim.token_a_mint);
+        let token_b_amount = received(&claim.token_b_mint);
+        
+        if token_a_amount != claim.expected_fee_a || token_b_amount != claim.expected_fee_b {
+            debug!("Claimed fees for position {} differ from estimate: got {} / {}, expected {} / {}",
+                claim.position, token_a_amount, token_b_amount, claim.expected_fee_a, claim.expected_fee_b);
+        }
         
-        debug!("Pool {} estimated fee APY: {:.2}%", pool_info.address, fee_apy * 100.0);
+        let value_sol = self.token_amount_in_sol(&claim.token_a_mint, token_a_amount).await?
+            + self.token_amount_in_sol(&claim.token_b_mint, token_b_amount).await?;
         
-        Ok(fee_apy)
+        Ok(ClaimedFees {
+            token_a_amount,
+            token_b_amount,
+            value_sol,
+        })
     }
     
-    /// Get the price of a token in SOL
-    async fn get_token_price_in_sol(&self, mint: &Pubkey) -> Result<f64> {
-        let mint_str = mint.to_string();
+    /// Read the token A and B amounts, fees included, actually withdrawn by a confirmed exit transaction
+    ///
+    /// The exit sells the non-SOL side and unwraps WSOL in the same transaction, so the owner's
+    /// balances don't show what left the position; the vault transfers made by its remove and
+    /// claim instructions do.
+    pub async fn record_exit(&self, exit: &RemoveLiquidityTransaction, signature: &str) -> Result<(u64, u64)> {
+        let confirmed = self.client.get_transaction(signature)?;
+        let meta = confirmed.transaction.meta
+            .ok_or_else(|| anyhow!("Transaction {} has no status metadata", signature))?;
         
-        // WSOL (wrapped SOL) is worth 1 SOL by definition
-        if mint_str == WSOL_MINT {
-            return Ok(1.0);
+        if let Some(err) = meta.err {
+            return Err(anyhow!("Exit transaction {} failed: {:?}", signature, err));
         }
         
-        // For USDC, use a rough estimate of SOL price 
-        // In a real implementation, we'd query actual price from oracle or DEX
-        if mint_str == USDC_MINT {
-            // Assume 1 SOL = 20 USDC (this would be dynamically fetched)
-            return Ok(1.0 / 20.0);
+        // Exits are legacy transactions, so the message lists every account they load
+        let account_keys = match confirmed.transaction.transaction {
+            EncodedTransaction::Json(transaction) => match transaction.message {
+                UiMessage::Raw(message) => message.account_keys,
+                UiMessage::Parsed(_) => return Err(anyhow!("Transaction {} has a parsed message", signature)),
+            },
+            _ => return Err(anyhow!("Transaction {} is not JSON encoded", signature)),
+        };
+        let inner_instructions: Vec<UiInnerInstructions> = Option::from(meta.inner_instructions)
+            .ok_or_else(|| anyhow!("Transaction {} has no inner instructions", signature))?;
+        
+        Ok(vault_withdrawals(&inner_instructions, &account_keys, exit))
+    }
+    
+    /// Simulate depositing `amount_sol` into a SOL pool without sending a transaction
+    pub async fn simulate_add_liquidity(&self, pool: Pubkey, amount_sol: f64, slippage_bps: u16) -> Result<SimulatedPosition> {
+        let pool_info = self.get_pool_info(&pool).await?;
+        SimulatedPosition::open(&pool_info, amount_sol, slippage_bps)
+    }
+    
+    /// Simulate claiming a paper position's fees at the pool's current state
+    pub async fn simulate_claim_fees(&self, position: &mut SimulatedPosition) -> Result<ClaimedFees> {
+        let pool_info = self.get_pool_info(&position.pool).await?;
+        Ok(position.claim(&pool_info))
+    }
+    
+    /// Value a raw token amount in SOL
+    async fn token_amount_in_sol(&self, mint: &Pubkey, amount: u64) -> Result<f64> {
+        if amount == 0 {
+            return Ok(0.0);
         }
         
-        // For other tokens, we'd ideally query existing pools or price oracles
-        // For now, use a placeholder value that estimates new tokens as having low value
-        // In a real implementation, this would be more sophisticated
-        Ok(0.01) // Assume new tokens are worth 0.01 SOL each
+        let decimals = rpc_helpers::get_token_decimals(&self.client, mint).await.unwrap_or(9);
+        let price = self.get_token_price_in_sol(mint).await?;
+        
+        Ok(amount as f64 * price / 10f64.powi(decimals as i32))
     }
+}
+
+/// Estimate a pool's annualized fee yield from its TVL (in SOL) and fee rate
+pub fn estimate_fee_apy(pool_info: &PoolInfo, tvl: f64) -> f64 {
+    // Estimate daily volume based on pool characteristics
+    let estimated_daily_volume = if let Some(volume) = pool_info.volume_24h {
+        (volume as f64) / 1_000_000_000.0 // Convert lamports to SOL
+    } else {
+        // If no volume data, estimate based on TVL and age of the pool
+        // For newer pools (which we're targeting), volume can be higher relative to TVL
+        // due to initial trading activity
+        
+        // Base estimate: 50% of TVL traded daily
+        let mut volume_estimate = tvl * 0.5;
+        
+        // Adjust based on token types - if one of the tokens is a major token (SOL/USDC),
+        // volume tends to be higher
+        let token_a_str = pool_info.token_a_mint.to_string();
+        let token_b_str = pool_info.token_b_mint.to_string();
+        
+        if token_a_str == WSOL_MINT || token_a_str == USDC_MINT || 
+           token_b_str == WSOL_MINT || token_b_str == USDC_MINT {
+            volume_estimate *= 1.5; // 50% higher volume for pools with major tokens
+        }
+        
+        volume_estimate
+    };
     
-    /// Add liquidity to a pool
-    pub async fn add_liquidity(&self, _pool: Pubkey, _amount_sol: f64) -> Result<Transaction> {
-        // TODO: Implement add liquidity logic
-        unimplemented!("Add liquidity not yet implemented")
+    // Calculate daily fees
+    let daily_fee = estimated_daily_volume * (pool_info.fee_rate as f64 / 10000.0);
+    
+    // Calculate fee APY
+    // Annual fees / TVL
+    let fee_apy = if tvl > 0.0 {
+        (daily_fee * 365.0) / tvl
+    } else {
+        0.0
+    };
+    
+    debug!("Pool {} estimated fee APY: {:.2}%", pool_info.address, fee_apy * 100.0);
+    fee_apy
+}
+
+/// How a SOL deposit is split between the two tokens and sized into liquidity
+struct DepositQuote {
+    sol_is_a: bool,
+    amount_lamports: u64,
+    /// Lamports swapped into the other token before depositing
+    swap_amount: u64,
+    swap_minimum_out: u64,
+    /// Maximum amount of token A deposited
+    token_a_amount: u64,
+    /// Maximum amount of token B deposited
+    token_b_amount: u64,
+    /// Token A the program pulls for the liquidity if the swap lands at the quote
+    token_a_deposit: u64,
+    /// Token B the program pulls for the liquidity if the swap lands at the quote
+    token_b_deposit: u64,
+    /// Token A bought at the quote beyond what is deposited
+    token_a_refund: u64,
+    /// Token B bought at the quote beyond what is deposited
+    token_b_refund: u64,
+    liquidity_delta: u128,
+}
+
+/// Quote a deposit of `amount_sol` into a SOL pool at its current price
+fn quote_sol_deposit(pool_info: &PoolInfo, amount_sol: f64, slippage_bps: u16) -> Result<DepositQuote> {
+    let pool = pool_info.address;
+    let state = &pool_info.state;
+    
+    if !state.is_enabled() {
+        return Err(anyhow!("Pool {} is disabled", pool));
     }
     
-    /// Remove liquidity from a pool
-    pub async fn remove_liquidity(&self, _pool: Pubkey) -> Result<Transaction> {
-        // TODO: Implement remove liquidity logic
-        unimplemented!("Remove liquidity not yet implemented")
+    let sol_is_a = sol_side(state)
+        .ok_or_else(|| anyhow!("Pool {} is not paired with SOL", pool))?;
+    
+    let amount_lamports = sol_to_lamports(amount_sol);
+    if amount_lamports == 0 {
+        return Err(anyhow!("Position size must be greater than zero"));
+    }
+    
+    // Split the SOL so that the swapped part matches the pool's current deposit ratio
+    let sol_share = sol_deposit_share(state, sol_is_a);
+    let swap_amount = (amount_lamports as f64 * (1.0 - sol_share)) as u64;
+    let sol_to_deposit = amount_lamports - swap_amount;
+    
+    let swap = pool_info.quote_swap(sol_is_a, swap_amount)?;
+    let sqrt_price_after = swap.sqrt_price_after;
+    let swap_minimum_out = apply_slippage(swap.amount_out, slippage_bps);
+    
+    let (token_a_amount, token_b_amount) = if sol_is_a {
+        (sol_to_deposit, swap_minimum_out)
+    } else {
+        (swap_minimum_out, sol_to_deposit)
+    };
+    
+    // Size liquidity from whichever side is scarcer at the post-swap price
+    let liquidity_from_a = math::get_liquidity_from_amount_a(token_a_amount, sqrt_price_after, state.sqrt_max_price);
+    let liquidity_from_b = math::get_liquidity_from_amount_b(token_b_amount, state.sqrt_min_price, sqrt_price_after);
+    let liquidity = match (liquidity_from_a, liquidity_from_b) {
+        (Some(a), Some(b)) => a.min(b),
+        _ => return Err(anyhow!("Pool {} price is at the edge of its range", pool)),
+    };
+    
+    // Leave headroom for price movement between quoting and landing the transaction
+    let liquidity_delta = liquidity / BASIS_POINT_MAX as u128 * BASIS_POINT_MAX.saturating_sub(slippage_bps as u64) as u128;
+    if liquidity_delta == 0 {
+        return Err(anyhow!("Position size too small to add liquidity"));
+    }
+    
+    // The amounts the program would actually pull for the liquidity, rounding up as it does
+    let token_a_deposit = math::get_delta_amount_a(sqrt_price_after, state.sqrt_max_price, liquidity_delta, true)
+        .ok_or_else(|| anyhow!("Failed to compute token A deposit for pool {}", pool))?;
+    let token_b_deposit = math::get_delta_amount_b(state.sqrt_min_price, sqrt_price_after, liquidity_delta, true)
+        .ok_or_else(|| anyhow!("Failed to compute token B deposit for pool {}", pool))?;
+    
+    // The slippage headroom leaves part of what was bought undeposited: SOL is unwrapped back
+    // to the wallet and the swapped token stays in its account
+    let (token_a_bought, token_b_bought) = if sol_is_a {
+        (sol_to_deposit, swap.amount_out)
+    } else {
+        (swap.amount_out, sol_to_deposit)
+    };
+    
+    Ok(DepositQuote {
+        sol_is_a,
+        amount_lamports,
+        swap_amount,
+        swap_minimum_out,
+        token_a_amount,
+        token_b_amount,
+        token_a_deposit,
+        token_b_deposit,
+        token_a_refund: token_a_bought.saturating_sub(token_a_deposit),
+        token_b_refund: token_b_bought.saturating_sub(token_b_deposit),
+        liquidity_delta,
+    })
+}
+
+/// Whether SOL is token A (`Some(true)`) or token B (`Some(false)`) of a pool, if it is paired with SOL
+fn sol_side(state: &PoolState) -> Option<bool> {
+    let wsol = instructions::wsol_mint();
+    if state.token_a_mint == wsol {
+        Some(true)
+    } else if state.token_b_mint == wsol {
+        Some(false)
+    } else {
+        None
     }
+}
+
+/// Fraction of a SOL-denominated deposit that stays in SOL at the pool's current price
+fn sol_deposit_share(state: &PoolState, sol_is_a: bool) -> f64 {
+    let sqrt_price = state.sqrt_price as f64 / 2f64.powi(64);
+    let sqrt_min = state.sqrt_min_price as f64 / 2f64.powi(64);
+    let sqrt_max = state.sqrt_max_price as f64 / 2f64.powi(64);
     
-    /// Claim fees from a pool
-    pub async fn claim_fees(&self, _pool: Pubkey) -> Result<Transaction> {
-        // TODO: Implement fee claiming logic
-        unimplemented!("Claim fees not yet implemented")
+    // Token amounts needed per unit of liquidity, valued in token B
+    let a_value = (1.0 / sqrt_price - 1.0 / sqrt_max) * sqrt_price * sqrt_price;
+    let b_value = sqrt_price - sqrt_min;
+    let total = a_value + b_value;
+    
+    if total <= 0.0 {
+        return 0.5;
     }
-} 
\ No newline at end of file
+    
+    if sol_is_a { a_value / total } else { b_value / total }
+}
+
+/// Reduce an amount by a slippage tolerance in basis points
+fn apply_slippage(amount: u64, slippage_bps: u16) -> u64 {
+    let retained = BASIS_POINT_MAX.saturating_sub(slippage_bps as u64);
+    (amount as u128 * retained as u128 / BASIS_POINT_MAX as u128) as u64
+}
+
+/// Find the raw balance of an owner's token account for a mint in a transaction's token balances
+fn token_balance(balances: &[UiTransactionTokenBalance], owner: &Pubkey, mint: &Pubkey) -> u64 {
+    let owner_str = owner.to_string();
+    let mint_str = mint.to_string();
+    
+    balances.iter()
+        .filter(|b| b.mint == mint_str)
+        .filter(|b| Option::<&String>::from(b.owner.as_ref()) == Some(&owner_str))
+        .filter_map(|b| b.ui_token_amount.amount.parse::<u64>().ok())
+        .sum()
+}
+
+/// Sum the token transfers out of the pool's vaults made by an exit's remove and claim instructions
+fn vault_withdrawals(
+    inner_instructions: &[UiInnerInstructions],
+    account_keys: &[String],
+    exit: &RemoveLiquidityTransaction,
+) -> (u64, u64) {
+    let token_programs = [instructions::token_program_for_flag(0), instructions::token_program_for_flag(1)];
+    let key = |index: u8| account_keys.get(index as usize).and_then(|key| Pubkey::from_str(key).ok());
+    let mut withdrawn = (0u64, 0u64);
+    
+    let transfers = inner_instructions.iter()
+        .filter(|inner| exit.withdraw_instructions.contains(&inner.index))
+        .flat_map(|inner| &inner.instructions);
+    
+    for instruction in transfers {
+        let UiInstruction::Compiled(instruction) = instruction else { continue };
+        if !key(instruction.program_id_index).is_some_and(|program| token_programs.contains(&program)) {
+            continue;
+        }
+        
+        // Transfer (3) and TransferChecked (12) both take the source first and the amount after the tag
+        let data = match bs58::decode(&instruction.data).into_vec() {
+            Ok(data) => data,
+            Err(_) => continue,
+        };
+        let amount = match (data.first(), data.get(1..9)) {
+            (Some(3 | 12), Some(amount)) => u64::from_le_bytes(amount.try_into().unwrap_or_default()),
+            _ => continue,
+        };
+        
+        match instruction.accounts.first().and_then(|index| key(*index)) {
+            Some(source) if source == exit.token_a_vault => withdrawn.0 = withdrawn.0.saturating_add(amount),
+            Some(source) if source == exit.token_b_vault => withdrawn.1 = withdrawn.1.saturating_add(amount),
+            _ => {},
+        }
+    }
+    
+    withdrawn
+}
+
+/// Read the token amount held by a vault token account
+fn vault_amount(account: Option<&Account>) -> Result<u64> {
+    let account = account.ok_or_else(|| anyhow!("Vault account not found"))?;
+    rpc_helpers::token_account_amount(&account.data).ok_or_else(|| anyhow!("Vault account data too short"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::meteora::fixtures;
+
+    fn sol_pool() -> PoolInfo {
+        fixtures::sol_pool(2_000_000_000_000 << 64, 100_000_000_000_000, 100_000_000_000)
+    }
+
+    #[test]
+    fn applies_slippage_in_basis_points() {
+        assert_eq!(apply_slippage(1_000_000, 0), 1_000_000);
+        assert_eq!(apply_slippage(1_000_000, 100), 990_000);
+        // Rounds down, so the minimum never exceeds the tolerance
+        assert_eq!(apply_slippage(999, 50), 994);
+        assert_eq!(apply_slippage(u64::MAX, 1), (u64::MAX as u128 * 9_999 / 10_000) as u64);
+        assert_eq!(apply_slippage(1_000_000, 10_000), 0);
+    }
+
+    #[test]
+    fn deposit_swap_minimum_applies_slippage_to_the_quote() {
+        let pool_info = sol_pool();
+
+        let deposit = quote_sol_deposit(&pool_info, 0.5, 100).unwrap();
+        let swap = pool_info.quote_swap(deposit.sol_is_a, deposit.swap_amount).unwrap();
+
+        assert!(!deposit.sol_is_a);
+        assert!(swap.amount_out > 0);
+        assert_eq!(deposit.swap_minimum_out, swap.amount_out * 9_900 / 10_000);
+    }
+
+    #[test]
+    fn deposit_rejects_slippage_beyond_the_whole_amount() {
+        // Leaves no headroom to deposit instead of underflowing
+        assert!(quote_sol_deposit(&sol_pool(), 0.5, 10_001).is_err());
+    }
+
+    /// A compiled token instruction with its data base58 encoded, as JSON transactions report it
+    fn token_instruction(program: u8, accounts: Vec<u8>, data: &[u8]) -> UiInstruction {
+        UiInstruction::Compiled(solana_transaction_status::UiCompiledInstruction {
+            program_id_index: program,
+            accounts,
+            data: bs58::encode(data).into_string(),
+            stack_height: Some(2),
+        })
+    }
+
+    fn transfer_checked(amount: u64) -> Vec<u8> {
+        let mut data = vec![12];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(6);
+        data
+    }
+
+    #[test]
+    fn reads_vault_withdrawals_of_the_remove_and_claim_instructions() {
+        let vault_a = Pubkey::new_unique();
+        let vault_b = Pubkey::new_unique();
+        let user_a = Pubkey::new_unique();
+        let user_b = Pubkey::new_unique();
+        let account_keys: Vec<String> = [
+            vault_a,
+            vault_b,
+            user_a,
+            user_b,
+            instructions::token_program_for_flag(0),
+            instructions::token_program_for_flag(1),
+            Pubkey::new_unique(),
+        ].iter().map(|key| key.to_string()).collect();
+        let exit = RemoveLiquidityTransaction {
+            transaction: Transaction::default(),
+            token_a_minimum: 0,
+            token_b_minimum: 0,
+            fee_a: 0,
+            fee_b: 0,
+            minimum_sol_out: None,
+            token_a_vault: vault_a,
+            token_b_vault: vault_b,
+            withdraw_instructions: [3, 4],
+        };
+
+        let mut transfer = vec![3];
+        transfer.extend_from_slice(&250u64.to_le_bytes());
+        let inner_instructions = vec![
+            UiInnerInstructions {
+                index: 3,
+                instructions: vec![
+                    // Token A is a Token-2022 mint moved with TransferChecked
+                    token_instruction(5, vec![0, 6, 2, 6], &transfer_checked(1_000)),
+                    token_instruction(4, vec![1, 6, 3, 6], &transfer_checked(2_000)),
+                ],
+            },
+            UiInnerInstructions {
+                index: 4,
+                instructions: vec![token_instruction(4, vec![1, 3, 6], &transfer)],
+            },
+            // The swap back to SOL also pays out of a vault, but is not part of the withdrawal
+            UiInnerInstructions {
+                index: 5,
+                instructions: vec![token_instruction(4, vec![1, 6, 3, 6], &transfer_checked(9_999))],
+            },
+        ];
+
+        assert_eq!(vault_withdrawals(&inner_instructions, &account_keys, &exit), (1_000, 2_250));
+
+        // Instructions of other programs and deposits into the vaults are ignored
+        let other_program = vec![UiInnerInstructions {
+            index: 3,
+            instructions: vec![
+                token_instruction(6, vec![0, 6, 2, 6], &transfer_checked(5)),
+                token_instruction(4, vec![2, 6, 0, 6], &transfer_checked(7)),
+            ],
+        }];
+        assert_eq!(vault_withdrawals(&other_program, &account_keys, &exit), (0, 0));
+    }
+}
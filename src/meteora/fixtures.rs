@@ -0,0 +1,46 @@
+use borsh::BorshSerialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::meteora::instructions;
+use crate::meteora::{PoolInfo, PoolState};
+use crate::meteora::state::{POOL_ACCOUNT_SIZE, POOL_DISCRIMINATOR};
+
+// Full-range bounds of a DAMM v2 pool
+pub const SQRT_MIN_PRICE: u128 = 4_295_048_016;
+pub const SQRT_MAX_PRICE: u128 = 79_226_673_521_066_979_257_578_248_091;
+
+// About 0.00005 SOL per raw token, so 1 SOL buys 20,000 raw units of a 6-decimal token
+pub const SQRT_PRICE: u128 = 130_438_178_253_327_825;
+
+// Flat 0.25% base fee
+pub const CLIFF_FEE_NUMERATOR: u64 = 2_500_000;
+
+/// A full-range pool of a new token paired with SOL as token B, charging a flat 0.25% fee
+pub fn sol_pool_state(sqrt_price: u128, liquidity: u128) -> PoolState {
+    let mut state = PoolState {
+        token_a_mint: Pubkey::new_unique(),
+        token_b_mint: instructions::wsol_mint(),
+        token_a_vault: Pubkey::new_unique(),
+        token_b_vault: Pubkey::new_unique(),
+        sqrt_price,
+        sqrt_min_price: SQRT_MIN_PRICE,
+        sqrt_max_price: SQRT_MAX_PRICE,
+        liquidity,
+        ..PoolState::default()
+    };
+    state.pool_fees.base_fee.cliff_fee_numerator = CLIFF_FEE_NUMERATOR;
+    state
+}
+
+/// `sol_pool_state` at `SQRT_PRICE` with the given vault balances
+pub fn sol_pool(liquidity: u128, token_reserve: u64, sol_reserve: u64) -> PoolInfo {
+    PoolInfo::from_state(Pubkey::new_unique(), sol_pool_state(SQRT_PRICE, liquidity), token_reserve, sol_reserve, 0)
+}
+
+/// Raw `Pool` account data holding `state`
+pub fn pool_account(state: &PoolState) -> Vec<u8> {
+    let mut data = POOL_DISCRIMINATOR.to_vec();
+    state.serialize(&mut data).expect("pool state serializes");
+    assert_eq!(data.len(), POOL_ACCOUNT_SIZE);
+    data
+}
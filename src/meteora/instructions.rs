@@ -0,0 +1,585 @@
+use borsh::BorshSerialize;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_program;
+use std::str::FromStr;
+
+use crate::meteora::client::{METEORA_PROGRAM_ID, WSOL_MINT};
+use crate::meteora::state::PoolState;
+use crate::solana::rpc_helpers::{TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID};
+
+// PDA seeds used by the DAMM v2 program
+pub const POOL_AUTHORITY_SEED: &[u8] = b"pool_authority";
+pub const EVENT_AUTHORITY_SEED: &[u8] = b"__event_authority";
+pub const POSITION_SEED: &[u8] = b"position";
+pub const POSITION_NFT_ACCOUNT_SEED: &[u8] = b"position_nft_account";
+
+// Anchor instruction discriminators (sha256("global:<name>")[..8])
+pub const CREATE_POSITION_DISCRIMINATOR: [u8; 8] = [48, 215, 197, 153, 96, 203, 180, 133];
+pub const ADD_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [181, 157, 89, 67, 143, 182, 52, 72];
+pub const REMOVE_ALL_LIQUIDITY_DISCRIMINATOR: [u8; 8] = [10, 51, 61, 35, 112, 105, 24, 85];
+pub const CLAIM_POSITION_FEE_DISCRIMINATOR: [u8; 8] = [180, 38, 154, 17, 133, 33, 162, 211];
+pub const CLOSE_POSITION_DISCRIMINATOR: [u8; 8] = [123, 134, 81, 0, 49, 68, 98, 98];
+pub const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+
+// SPL Token instruction tags
+const SPL_TOKEN_CLOSE_ACCOUNT: u8 = 9;
+const SPL_TOKEN_SYNC_NATIVE: u8 = 17;
+
+// Associated Token Account program instruction tag for CreateIdempotent
+const ATA_CREATE_IDEMPOTENT: u8 = 1;
+
+/// Parameters of the `add_liquidity` instruction
+#[derive(Debug, Clone, BorshSerialize)]
+pub struct AddLiquidityParameters {
+    /// Liquidity to add to the position
+    pub liquidity_delta: u128,
+    /// Maximum amount of token A the owner is willing to deposit
+    pub token_a_amount_threshold: u64,
+    /// Maximum amount of token B the owner is willing to deposit
+    pub token_b_amount_threshold: u64,
+}
+
+/// Parameters of the `remove_all_liquidity` instruction
+#[derive(Debug, Clone, BorshSerialize)]
+pub struct RemoveAllLiquidityParameters {
+    /// Minimum amount of token A the owner must receive
+    pub token_a_amount_threshold: u64,
+    /// Minimum amount of token B the owner must receive
+    pub token_b_amount_threshold: u64,
+}
+
+/// Parameters of the `swap` instruction
+#[derive(Debug, Clone, BorshSerialize)]
+pub struct SwapParameters {
+    pub amount_in: u64,
+    pub minimum_amount_out: u64,
+}
+
+/// Pool-level accounts shared by every liquidity instruction
+#[derive(Debug, Clone)]
+pub struct PoolAccounts {
+    pub pool: Pubkey,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub token_a_program: Pubkey,
+    pub token_b_program: Pubkey,
+}
+
+impl PoolAccounts {
+    /// Collect the accounts of a decoded pool
+    pub fn from_state(pool: Pubkey, state: &PoolState) -> Self {
+        Self {
+            pool,
+            token_a_mint: state.token_a_mint,
+            token_b_mint: state.token_b_mint,
+            token_a_vault: state.token_a_vault,
+            token_b_vault: state.token_b_vault,
+            token_a_program: token_program_for_flag(state.token_a_flag),
+            token_b_program: token_program_for_flag(state.token_b_flag),
+        }
+    }
+}
+
+/// Accounts identifying a position and the owner's token accounts for it
+#[derive(Debug, Clone)]
+pub struct PositionAccounts {
+    pub owner: Pubkey,
+    pub position: Pubkey,
+    pub position_nft_mint: Pubkey,
+    pub position_nft_account: Pubkey,
+    pub token_a_account: Pubkey,
+    pub token_b_account: Pubkey,
+}
+
+impl PositionAccounts {
+    /// Derive the position PDAs and owner ATAs for a position NFT mint
+    pub fn new(owner: Pubkey, position_nft_mint: Pubkey, pool_accounts: &PoolAccounts) -> Self {
+        Self {
+            owner,
+            position: derive_position(&position_nft_mint),
+            position_nft_mint,
+            position_nft_account: derive_position_nft_account(&position_nft_mint),
+            token_a_account: get_associated_token_address(&owner, &pool_accounts.token_a_mint, &pool_accounts.token_a_program),
+            token_b_account: get_associated_token_address(&owner, &pool_accounts.token_b_mint, &pool_accounts.token_b_program),
+        }
+    }
+}
+
+/// Get the DAMM v2 program ID
+pub fn program_id() -> Pubkey {
+    Pubkey::from_str(METEORA_PROGRAM_ID).expect("Failed to parse Meteora program ID")
+}
+
+/// Get the WSOL mint
+pub fn wsol_mint() -> Pubkey {
+    Pubkey::from_str(WSOL_MINT).expect("Failed to parse WSOL mint")
+}
+
+/// Get the token program that owns a mint with the given pool token flag
+pub fn token_program_for_flag(flag: u8) -> Pubkey {
+    let id = if flag == 1 { TOKEN_2022_PROGRAM_ID } else { TOKEN_PROGRAM_ID };
+    Pubkey::from_str(id).expect("Failed to parse token program ID")
+}
+
+/// Derive the program-wide pool authority
+pub fn derive_pool_authority() -> Pubkey {
+    Pubkey::find_program_address(&[POOL_AUTHORITY_SEED], &program_id()).0
+}
+
+/// Derive the Anchor event authority
+pub fn derive_event_authority() -> Pubkey {
+    Pubkey::find_program_address(&[EVENT_AUTHORITY_SEED], &program_id()).0
+}
+
+/// Derive the position account for a position NFT mint
+pub fn derive_position(position_nft_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[POSITION_SEED, position_nft_mint.as_ref()], &program_id()).0
+}
+
+/// Derive the token account holding a position NFT
+pub fn derive_position_nft_account(position_nft_mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[POSITION_NFT_ACCOUNT_SEED, position_nft_mint.as_ref()], &program_id()).0
+}
+
+/// Derive an associated token account address
+pub fn get_associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    let ata_program = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID)
+        .expect("Failed to parse associated token program ID");
+
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ata_program,
+    ).0
+}
+
+/// Create an associated token account if it does not exist yet
+pub fn create_associated_token_account_idempotent(
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    let ata_program = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM_ID)
+        .expect("Failed to parse associated token program ID");
+
+    Instruction {
+        program_id: ata_program,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(get_associated_token_address(wallet, mint, token_program), false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: vec![ATA_CREATE_IDEMPOTENT],
+    }
+}
+
+/// Sync a WSOL account's token amount with its lamport balance
+pub fn sync_native(account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: Pubkey::from_str(TOKEN_PROGRAM_ID).expect("Failed to parse token program ID"),
+        accounts: vec![AccountMeta::new(*account, false)],
+        data: vec![SPL_TOKEN_SYNC_NATIVE],
+    }
+}
+
+/// Close a token account and send its lamports to the destination
+pub fn close_token_account(
+    account: &Pubkey,
+    destination: &Pubkey,
+    owner: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *token_program,
+        accounts: vec![
+            AccountMeta::new(*account, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*owner, true),
+        ],
+        data: vec![SPL_TOKEN_CLOSE_ACCOUNT],
+    }
+}
+
+/// Build an Anchor instruction payload from a discriminator and borsh-serialized arguments
+fn instruction_data<T: BorshSerialize>(discriminator: [u8; 8], args: &T) -> Vec<u8> {
+    let mut data = discriminator.to_vec();
+    data.extend(borsh::to_vec(args).expect("Failed to serialize instruction arguments"));
+    data
+}
+
+/// Build the `create_position` instruction, minting a new position NFT to the owner
+pub fn create_position(
+    owner: &Pubkey,
+    payer: &Pubkey,
+    position_nft_mint: &Pubkey,
+    pool: &Pubkey,
+) -> Instruction {
+    let token_2022_program = Pubkey::from_str(TOKEN_2022_PROGRAM_ID)
+        .expect("Failed to parse token program ID");
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new(*position_nft_mint, true),
+            AccountMeta::new(derive_position_nft_account(position_nft_mint), false),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new(derive_position(position_nft_mint), false),
+            AccountMeta::new_readonly(derive_pool_authority(), false),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(token_2022_program, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(derive_event_authority(), false),
+            AccountMeta::new_readonly(program_id(), false),
+        ],
+        data: CREATE_POSITION_DISCRIMINATOR.to_vec(),
+    }
+}
+
+/// Accounts shared by the add/remove liquidity and claim fee instructions, in IDL order
+fn position_liquidity_metas(
+    pool_accounts: &PoolAccounts,
+    position_accounts: &PositionAccounts,
+) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(pool_accounts.pool, false),
+        AccountMeta::new(position_accounts.position, false),
+        AccountMeta::new(position_accounts.token_a_account, false),
+        AccountMeta::new(position_accounts.token_b_account, false),
+        AccountMeta::new(pool_accounts.token_a_vault, false),
+        AccountMeta::new(pool_accounts.token_b_vault, false),
+        AccountMeta::new_readonly(pool_accounts.token_a_mint, false),
+        AccountMeta::new_readonly(pool_accounts.token_b_mint, false),
+        AccountMeta::new_readonly(position_accounts.position_nft_account, false),
+        AccountMeta::new_readonly(position_accounts.owner, true),
+        AccountMeta::new_readonly(pool_accounts.token_a_program, false),
+        AccountMeta::new_readonly(pool_accounts.token_b_program, false),
+        AccountMeta::new_readonly(derive_event_authority(), false),
+        AccountMeta::new_readonly(program_id(), false),
+    ]
+}
+
+/// Same as `position_liquidity_metas`, preceded by the pool authority that signs vault transfers
+fn position_withdraw_metas(
+    pool_accounts: &PoolAccounts,
+    position_accounts: &PositionAccounts,
+) -> Vec<AccountMeta> {
+    let mut accounts = vec![AccountMeta::new_readonly(derive_pool_authority(), false)];
+    accounts.extend(position_liquidity_metas(pool_accounts, position_accounts));
+    accounts
+}
+
+/// Build the `add_liquidity` instruction
+pub fn add_liquidity(
+    pool_accounts: &PoolAccounts,
+    position_accounts: &PositionAccounts,
+    params: &AddLiquidityParameters,
+) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: position_liquidity_metas(pool_accounts, position_accounts),
+        data: instruction_data(ADD_LIQUIDITY_DISCRIMINATOR, params),
+    }
+}
+
+/// Build the `remove_all_liquidity` instruction
+pub fn remove_all_liquidity(
+    pool_accounts: &PoolAccounts,
+    position_accounts: &PositionAccounts,
+    params: &RemoveAllLiquidityParameters,
+) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: position_withdraw_metas(pool_accounts, position_accounts),
+        data: instruction_data(REMOVE_ALL_LIQUIDITY_DISCRIMINATOR, params),
+    }
+}
+
+/// Build the `claim_position_fee` instruction
+pub fn claim_position_fee(
+    pool_accounts: &PoolAccounts,
+    position_accounts: &PositionAccounts,
+) -> Instruction {
+    let mut accounts = position_withdraw_metas(pool_accounts, position_accounts);
+    // Claiming only reads the pool's fee accumulators
+    accounts[1] = AccountMeta::new_readonly(pool_accounts.pool, false);
+
+    Instruction {
+        program_id: program_id(),
+        accounts,
+        data: CLAIM_POSITION_FEE_DISCRIMINATOR.to_vec(),
+    }
+}
+
+/// Build the `close_position` instruction, burning the position NFT and reclaiming rent
+pub fn close_position(
+    pool: &Pubkey,
+    position_accounts: &PositionAccounts,
+    rent_receiver: &Pubkey,
+) -> Instruction {
+    let token_2022_program = Pubkey::from_str(TOKEN_2022_PROGRAM_ID)
+        .expect("Failed to parse token program ID");
+
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new(position_accounts.position_nft_mint, false),
+            AccountMeta::new(position_accounts.position_nft_account, false),
+            AccountMeta::new(*pool, false),
+            AccountMeta::new(position_accounts.position, false),
+            AccountMeta::new_readonly(derive_pool_authority(), false),
+            AccountMeta::new(*rent_receiver, false),
+            AccountMeta::new_readonly(position_accounts.owner, true),
+            AccountMeta::new_readonly(token_2022_program, false),
+            AccountMeta::new_readonly(derive_event_authority(), false),
+            AccountMeta::new_readonly(program_id(), false),
+        ],
+        data: CLOSE_POSITION_DISCRIMINATOR.to_vec(),
+    }
+}
+
+/// Build the `swap` instruction without a referral account
+pub fn swap(
+    pool_accounts: &PoolAccounts,
+    payer: &Pubkey,
+    input_token_account: &Pubkey,
+    output_token_account: &Pubkey,
+    params: &SwapParameters,
+) -> Instruction {
+    Instruction {
+        program_id: program_id(),
+        accounts: vec![
+            AccountMeta::new_readonly(derive_pool_authority(), false),
+            AccountMeta::new(pool_accounts.pool, false),
+            AccountMeta::new(*input_token_account, false),
+            AccountMeta::new(*output_token_account, false),
+            AccountMeta::new(pool_accounts.token_a_vault, false),
+            AccountMeta::new(pool_accounts.token_b_vault, false),
+            AccountMeta::new_readonly(pool_accounts.token_a_mint, false),
+            AccountMeta::new_readonly(pool_accounts.token_b_mint, false),
+            AccountMeta::new_readonly(*payer, true),
+            AccountMeta::new_readonly(pool_accounts.token_a_program, false),
+            AccountMeta::new_readonly(pool_accounts.token_b_program, false),
+            // Optional referral account: Anchor expects the program ID when absent
+            AccountMeta::new_readonly(program_id(), false),
+            AccountMeta::new_readonly(derive_event_authority(), false),
+            AccountMeta::new_readonly(program_id(), false),
+        ],
+        data: instruction_data(SWAP_DISCRIMINATOR, params),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Address of the program's `pool_authority` PDA as fixed in the DAMM v2 IDL
+    const POOL_AUTHORITY: &str = "HLnpSz9h2S4hiLQ43rnSD9XkcUThA7B8hQMKmDaiTLcC";
+
+    fn pool_accounts() -> PoolAccounts {
+        PoolAccounts {
+            pool: Pubkey::new_unique(),
+            token_a_mint: Pubkey::new_unique(),
+            token_b_mint: wsol_mint(),
+            token_a_vault: Pubkey::new_unique(),
+            token_b_vault: Pubkey::new_unique(),
+            token_a_program: token_program_for_flag(1),
+            token_b_program: token_program_for_flag(0),
+        }
+    }
+
+    /// (address, signer, writable) of each account, in order
+    fn metas(instruction: &Instruction) -> Vec<(Pubkey, bool, bool)> {
+        instruction.accounts.iter().map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable)).collect()
+    }
+
+    fn discriminator(name: &str) -> [u8; 8] {
+        let hash = solana_sdk::hash::hash(format!("global:{}", name).as_bytes()).to_bytes();
+        hash[..8].try_into().unwrap()
+    }
+
+    #[test]
+    fn discriminators_match_anchor_instruction_names() {
+        assert_eq!(discriminator("create_position"), CREATE_POSITION_DISCRIMINATOR);
+        assert_eq!(discriminator("add_liquidity"), ADD_LIQUIDITY_DISCRIMINATOR);
+        assert_eq!(discriminator("remove_all_liquidity"), REMOVE_ALL_LIQUIDITY_DISCRIMINATOR);
+        assert_eq!(discriminator("claim_position_fee"), CLAIM_POSITION_FEE_DISCRIMINATOR);
+        assert_eq!(discriminator("close_position"), CLOSE_POSITION_DISCRIMINATOR);
+        assert_eq!(discriminator("swap"), SWAP_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn derives_pool_authority() {
+        assert_eq!(derive_pool_authority(), Pubkey::from_str(POOL_AUTHORITY).unwrap());
+    }
+
+    #[test]
+    fn builds_create_position() {
+        let owner = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let nft_mint = Pubkey::new_unique();
+        let pool = Pubkey::new_unique();
+
+        let instruction = create_position(&owner, &payer, &nft_mint, &pool);
+
+        assert_eq!(instruction.program_id, program_id());
+        assert_eq!(metas(&instruction), vec![
+            (owner, false, false),
+            (nft_mint, true, true),
+            (derive_position_nft_account(&nft_mint), false, true),
+            (pool, false, true),
+            (derive_position(&nft_mint), false, true),
+            (derive_pool_authority(), false, false),
+            (payer, true, true),
+            (Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap(), false, false),
+            (system_program::id(), false, false),
+            (derive_event_authority(), false, false),
+            (program_id(), false, false),
+        ]);
+        assert_eq!(instruction.data, CREATE_POSITION_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn builds_add_liquidity() {
+        let pool = pool_accounts();
+        let position = PositionAccounts::new(Pubkey::new_unique(), Pubkey::new_unique(), &pool);
+        let params = AddLiquidityParameters {
+            liquidity_delta: 0x0102_0304_0506_0708_090a_0b0c_0d0e_0f10,
+            token_a_amount_threshold: 1_000_000,
+            token_b_amount_threshold: 50_000_000,
+        };
+
+        let instruction = add_liquidity(&pool, &position, &params);
+
+        assert_eq!(metas(&instruction), vec![
+            (pool.pool, false, true),
+            (position.position, false, true),
+            (position.token_a_account, false, true),
+            (position.token_b_account, false, true),
+            (pool.token_a_vault, false, true),
+            (pool.token_b_vault, false, true),
+            (pool.token_a_mint, false, false),
+            (pool.token_b_mint, false, false),
+            (position.position_nft_account, false, false),
+            (position.owner, true, false),
+            (pool.token_a_program, false, false),
+            (pool.token_b_program, false, false),
+            (derive_event_authority(), false, false),
+            (program_id(), false, false),
+        ]);
+
+        let mut data = ADD_LIQUIDITY_DISCRIMINATOR.to_vec();
+        data.extend(params.liquidity_delta.to_le_bytes());
+        data.extend(1_000_000u64.to_le_bytes());
+        data.extend(50_000_000u64.to_le_bytes());
+        assert_eq!(instruction.data, data);
+        assert_eq!(instruction.data.len(), 8 + 16 + 8 + 8);
+    }
+
+    #[test]
+    fn builds_remove_all_liquidity() {
+        let pool = pool_accounts();
+        let position = PositionAccounts::new(Pubkey::new_unique(), Pubkey::new_unique(), &pool);
+        let params = RemoveAllLiquidityParameters {
+            token_a_amount_threshold: 123,
+            token_b_amount_threshold: 456,
+        };
+
+        let instruction = remove_all_liquidity(&pool, &position, &params);
+
+        assert_eq!(metas(&instruction), vec![
+            (derive_pool_authority(), false, false),
+            (pool.pool, false, true),
+            (position.position, false, true),
+            (position.token_a_account, false, true),
+            (position.token_b_account, false, true),
+            (pool.token_a_vault, false, true),
+            (pool.token_b_vault, false, true),
+            (pool.token_a_mint, false, false),
+            (pool.token_b_mint, false, false),
+            (position.position_nft_account, false, false),
+            (position.owner, true, false),
+            (pool.token_a_program, false, false),
+            (pool.token_b_program, false, false),
+            (derive_event_authority(), false, false),
+            (program_id(), false, false),
+        ]);
+
+        let mut data = REMOVE_ALL_LIQUIDITY_DISCRIMINATOR.to_vec();
+        data.extend(123u64.to_le_bytes());
+        data.extend(456u64.to_le_bytes());
+        assert_eq!(instruction.data, data);
+    }
+
+    #[test]
+    fn builds_claim_position_fee() {
+        let pool = pool_accounts();
+        let position = PositionAccounts::new(Pubkey::new_unique(), Pubkey::new_unique(), &pool);
+
+        let instruction = claim_position_fee(&pool, &position);
+
+        assert_eq!(metas(&instruction), vec![
+            (derive_pool_authority(), false, false),
+            (pool.pool, false, false),
+            (position.position, false, true),
+            (position.token_a_account, false, true),
+            (position.token_b_account, false, true),
+            (pool.token_a_vault, false, true),
+            (pool.token_b_vault, false, true),
+            (pool.token_a_mint, false, false),
+            (pool.token_b_mint, false, false),
+            (position.position_nft_account, false, false),
+            (position.owner, true, false),
+            (pool.token_a_program, false, false),
+            (pool.token_b_program, false, false),
+            (derive_event_authority(), false, false),
+            (program_id(), false, false),
+        ]);
+        assert_eq!(instruction.data, CLAIM_POSITION_FEE_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn builds_close_position() {
+        let pool = pool_accounts();
+        let position = PositionAccounts::new(Pubkey::new_unique(), Pubkey::new_unique(), &pool);
+        let rent_receiver = Pubkey::new_unique();
+
+        let instruction = close_position(&pool.pool, &position, &rent_receiver);
+
+        assert_eq!(metas(&instruction), vec![
+            (position.position_nft_mint, false, true),
+            (position.position_nft_account, false, true),
+            (pool.pool, false, true),
+            (position.position, false, true),
+            (derive_pool_authority(), false, false),
+            (rent_receiver, false, true),
+            (position.owner, true, false),
+            (Pubkey::from_str(TOKEN_2022_PROGRAM_ID).unwrap(), false, false),
+            (derive_event_authority(), false, false),
+            (program_id(), false, false),
+        ]);
+        assert_eq!(instruction.data, CLOSE_POSITION_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn derives_position_accounts_per_token_program() {
+        let pool = pool_accounts();
+        let owner = Pubkey::new_unique();
+        let nft_mint = Pubkey::new_unique();
+
+        let position = PositionAccounts::new(owner, nft_mint, &pool);
+
+        assert_eq!(position.position, derive_position(&nft_mint));
+        assert_eq!(position.position_nft_account, derive_position_nft_account(&nft_mint));
+        // Token A is a Token-2022 mint, so its ATA is derived with that program
+        assert_eq!(position.token_a_account, get_associated_token_address(&owner, &pool.token_a_mint, &token_program_for_flag(1)));
+        assert_ne!(position.token_a_account, get_associated_token_address(&owner, &pool.token_a_mint, &token_program_for_flag(0)));
+        assert_eq!(position.token_b_account, get_associated_token_address(&owner, &wsol_mint(), &token_program_for_flag(0)));
+    }
+}
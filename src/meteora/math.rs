@@ -0,0 +1,162 @@
+// Fixed-point helpers mirroring the DAMM v2 program's liquidity math.
+// Sqrt prices are Q64.64 and liquidity is scaled by 2^64, so intermediate
+// products routinely exceed 128 bits and go through a 256-bit mul/div.
+
+const U64_MASK: u128 = u64::MAX as u128;
+
+/// Multiply two u128 values into a 256-bit (hi, lo) pair
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    let (a_hi, a_lo) = (a >> 64, a & U64_MASK);
+    let (b_hi, b_lo) = (b >> 64, b & U64_MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let cross = (lo_lo >> 64) + (hi_lo & U64_MASK) + (lo_hi & U64_MASK);
+    let lo = (cross << 64) | (lo_lo & U64_MASK);
+    let hi = hi_hi + (hi_lo >> 64) + (lo_hi >> 64) + (cross >> 64);
+
+    (hi, lo)
+}
+
+/// Divide a 256-bit (hi, lo) value by a u128, returning (quotient, remainder)
+fn div_rem_256(hi: u128, lo: u128, denominator: u128) -> Option<(u128, u128)> {
+    // The quotient only fits in 128 bits when hi < denominator
+    if denominator == 0 || hi >= denominator {
+        return None;
+    }
+
+    let mut remainder = hi;
+    let mut quotient = 0u128;
+
+    for i in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((lo >> i) & 1);
+        quotient <<= 1;
+
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+
+    Some((quotient, remainder))
+}
+
+/// Compute a * b / denominator with a 256-bit intermediate product
+pub fn mul_div(a: u128, b: u128, denominator: u128, round_up: bool) -> Option<u128> {
+    let (hi, lo) = full_mul(a, b);
+    let (quotient, remainder) = div_rem_256(hi, lo, denominator)?;
+
+    if round_up && remainder > 0 {
+        quotient.checked_add(1)
+    } else {
+        Some(quotient)
+    }
+}
+
+/// Compute a * b >> 128
+pub fn mul_shr_128(a: u128, b: u128, round_up: bool) -> u128 {
+    let (hi, lo) = full_mul(a, b);
+
+    if round_up && lo > 0 {
+        hi.saturating_add(1)
+    } else {
+        hi
+    }
+}
+
+/// Token A amount for a liquidity delta between two sqrt prices
+/// Δa = L * (√P_upper - √P_lower) / (√P_upper * √P_lower)
+pub fn get_delta_amount_a(lower_sqrt_price: u128, upper_sqrt_price: u128, liquidity: u128, round_up: bool) -> Option<u64> {
+    let delta = upper_sqrt_price.checked_sub(lower_sqrt_price)?;
+    // Dividing by the upper price first keeps the intermediate below L; nested floor (or ceil)
+    // divisions equal a single division by the product, so the result is exact either way
+    let partial = mul_div(liquidity, delta, upper_sqrt_price, round_up)?;
+    let amount = if round_up {
+        partial.div_ceil(lower_sqrt_price)
+    } else {
+        partial / lower_sqrt_price
+    };
+
+    u64::try_from(amount).ok()
+}
+
+/// Token B amount for a liquidity delta between two sqrt prices
+/// Δb = L * (√P_upper - √P_lower) >> 128
+pub fn get_delta_amount_b(lower_sqrt_price: u128, upper_sqrt_price: u128, liquidity: u128, round_up: bool) -> Option<u64> {
+    let delta = upper_sqrt_price.checked_sub(lower_sqrt_price)?;
+    u64::try_from(mul_shr_128(liquidity, delta, round_up)).ok()
+}
+
+/// Liquidity provided by an amount of token A deposited from the current price up to the max price
+/// L = Δa * √P_lower * √P_upper / (√P_upper - √P_lower)
+pub fn get_liquidity_from_amount_a(amount_a: u64, lower_sqrt_price: u128, upper_sqrt_price: u128) -> Option<u128> {
+    let delta = upper_sqrt_price.checked_sub(lower_sqrt_price)?;
+    let partial = mul_div(amount_a as u128, lower_sqrt_price, delta, false)?;
+    partial.checked_mul(upper_sqrt_price)
+}
+
+/// Liquidity provided by an amount of token B deposited from the min price up to the current price
+/// L = Δb << 128 / (√P_upper - √P_lower)
+pub fn get_liquidity_from_amount_b(amount_b: u64, lower_sqrt_price: u128, upper_sqrt_price: u128) -> Option<u128> {
+    let delta = upper_sqrt_price.checked_sub(lower_sqrt_price)?;
+    div_rem_256(amount_b as u128, 0, delta).map(|(quotient, _)| quotient)
+}
+
+/// Next sqrt price after adding token A to the pool (price moves down)
+/// √P' = L * √P / (L + Δa * √P)
+pub fn get_next_sqrt_price_from_amount_a_in(sqrt_price: u128, liquidity: u128, amount_a: u64) -> Option<u128> {
+    if amount_a == 0 {
+        return Some(sqrt_price);
+    }
+
+    // Work in the 2^64-scaled domain of the program: (Δa * √P) is scaled by 2^64 like L
+    let product = (amount_a as u128).checked_mul(sqrt_price)?;
+    let denominator = liquidity.checked_add(product)?;
+    mul_div(liquidity, sqrt_price, denominator, true)
+}
+
+/// Next sqrt price after adding token B to the pool (price moves up)
+/// √P' = √P + Δb << 128 / L
+pub fn get_next_sqrt_price_from_amount_b_in(sqrt_price: u128, liquidity: u128, amount_b: u64) -> Option<u128> {
+    let (quotient, _) = div_rem_256(amount_b as u128, 0, liquidity)?;
+    sqrt_price.checked_add(quotient)
+}
+
+/// Convert a Q64.64 sqrt price into a raw price of token A in token B (not decimal adjusted)
+pub fn sqrt_price_to_price(sqrt_price: u128) -> f64 {
+    let sqrt = sqrt_price as f64 / 2f64.powi(64);
+    sqrt * sqrt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meteora::fixtures::{SQRT_MAX_PRICE as MAX_SQRT_PRICE, SQRT_MIN_PRICE as MIN_SQRT_PRICE};
+
+    #[test]
+    fn delta_amount_a_handles_full_range_liquidity() {
+        // About 0.5 SOL of liquidity in a pool of a 6-decimal token priced near 5e-5 lamports
+        let sqrt_price = 130_438_178_253_327_825;
+        let liquidity = get_liquidity_from_amount_b(500_000_000, MIN_SQRT_PRICE, sqrt_price).unwrap();
+
+        // L * (√P_upper - √P_lower) / √P_lower alone is far above u128::MAX here
+        assert!(mul_div(liquidity, MAX_SQRT_PRICE - sqrt_price, sqrt_price, false).is_none());
+
+        let floor = get_delta_amount_a(sqrt_price, MAX_SQRT_PRICE, liquidity, false).unwrap();
+        let ceil = get_delta_amount_a(sqrt_price, MAX_SQRT_PRICE, liquidity, true).unwrap();
+        assert_eq!(floor, 10_000_000_329_261);
+        assert_eq!(ceil, floor + 1);
+    }
+
+    #[test]
+    fn delta_amount_a_matches_the_single_division() {
+        let (lower, upper, liquidity) = (1u128 << 64, 3u128 << 63, 1_000_000u128 << 64);
+        // L * Δ / (lower * upper) = 10^6 * 2^64 * 2^63 / (2^64 * 3 * 2^63) = 333_333.33
+        assert_eq!(get_delta_amount_a(lower, upper, liquidity, false), Some(333_333));
+        assert_eq!(get_delta_amount_a(lower, upper, liquidity, true), Some(333_334));
+    }
+}
@@ -1,4 +1,17 @@
 mod client;
+pub mod state;
+pub mod math;
+pub mod quote;
+pub mod instructions;
+#[cfg(test)]
+pub(crate) mod fixtures;
 
 pub use client::MeteoraClient;
-pub use client::PoolInfo; 
\ No newline at end of file
+pub use client::PoolInfo;
+pub use client::SimulatedPosition;
+pub use client::estimate_fee_apy;
+pub use client::WSOL_MINT;
+pub use client::USDC_MINT;
+pub use client::USDT_MINT;
+pub use state::PoolState;
+pub use state::PositionState;
@@ -0,0 +1,242 @@
+// Exact-in swap quotes computed from a decoded pool, following the DAMM v2 program's swap.
+// Pools trade along a single concentrated range [sqrt_min_price, sqrt_max_price], so a swap
+// is one step of the constant-liquidity curve; the fee is either taken from the input before
+// the step or from the output after it, depending on the pool's collect fee mode.
+
+use anyhow::{Result, anyhow};
+
+use crate::meteora::math;
+use crate::meteora::state::{PoolState, FEE_DENOMINATOR};
+
+// `collect_fee_mode` value for pools that only collect fees in token B
+const COLLECT_FEE_MODE_ONLY_B: u8 = 1;
+
+/// Expected result of swapping an exact amount into a pool
+#[derive(Debug, Clone)]
+pub struct SwapQuote {
+    pub a_to_b: bool,
+    pub amount_in: u64,
+    /// Amount received after fees
+    pub amount_out: u64,
+    /// Trading fee charged, in the input token if `fee_on_input` and the output token otherwise
+    pub fee: u64,
+    pub fee_on_input: bool,
+    /// Total fee numerator applied (base plus dynamic fee, over `FEE_DENOMINATOR`)
+    pub fee_numerator: u64,
+    /// Pool sqrt price once the swap has landed
+    pub sqrt_price_after: u128,
+    /// Shortfall of the execution price against the spot price, excluding fees (0.01 = 1%)
+    pub price_impact: f64,
+}
+
+/// Quote swapping `amount_in` of token A (`a_to_b`) or token B into the pool
+///
+/// `current_point` is the slot or timestamp (matching the pool's activation type) the fee
+/// scheduler is evaluated at.
+pub fn quote_exact_in(state: &PoolState, a_to_b: bool, amount_in: u64, current_point: u64) -> Result<SwapQuote> {
+    if state.liquidity == 0 {
+        return Err(anyhow!("Pool has no liquidity"));
+    }
+
+    let fee_numerator = state.fee_numerator(current_point);
+
+    // Mirrors the program's fee mode: only B-to-A swaps in OnlyB pools pay the fee up front
+    let fee_on_input = !a_to_b && state.collect_fee_mode == COLLECT_FEE_MODE_ONLY_B;
+
+    let (curve_in, input_fee) = if fee_on_input {
+        let fee = fee_on_amount(amount_in, fee_numerator)?;
+        (amount_in - fee, fee)
+    } else {
+        (amount_in, 0)
+    };
+
+    let (curve_out, sqrt_price_after) = swap_step(state, a_to_b, curve_in)?;
+
+    let (amount_out, fee) = if fee_on_input {
+        (curve_out, input_fee)
+    } else {
+        let fee = fee_on_amount(curve_out, fee_numerator)?;
+        (curve_out - fee, fee)
+    };
+
+    Ok(SwapQuote {
+        a_to_b,
+        amount_in,
+        amount_out,
+        fee,
+        fee_on_input,
+        fee_numerator,
+        sqrt_price_after,
+        price_impact: price_impact(state.sqrt_price, a_to_b, curve_in, curve_out),
+    })
+}
+
+/// Move the price along the curve for an input amount, returning the output and the new sqrt price
+fn swap_step(state: &PoolState, a_to_b: bool, amount_in: u64) -> Result<(u64, u128)> {
+    let overflow = || anyhow!("Swap of {} overflows the pool's price math", amount_in);
+
+    if a_to_b {
+        let next = math::get_next_sqrt_price_from_amount_a_in(state.sqrt_price, state.liquidity, amount_in)
+            .ok_or_else(overflow)?;
+        if next < state.sqrt_min_price {
+            return Err(anyhow!("Swap of {} would push the price below the pool's range", amount_in));
+        }
+        let out = math::get_delta_amount_b(next, state.sqrt_price, state.liquidity, false)
+            .ok_or_else(overflow)?;
+        Ok((out, next))
+    } else {
+        let next = math::get_next_sqrt_price_from_amount_b_in(state.sqrt_price, state.liquidity, amount_in)
+            .ok_or_else(overflow)?;
+        if next > state.sqrt_max_price {
+            return Err(anyhow!("Swap of {} would push the price above the pool's range", amount_in));
+        }
+        let out = math::get_delta_amount_a(state.sqrt_price, next, state.liquidity, false)
+            .ok_or_else(overflow)?;
+        Ok((out, next))
+    }
+}
+
+/// Trading fee on an amount, rounded up in the pool's favour
+fn fee_on_amount(amount: u64, fee_numerator: u64) -> Result<u64> {
+    math::mul_div(amount as u128, fee_numerator as u128, FEE_DENOMINATOR as u128, true)
+        .and_then(|fee| u64::try_from(fee).ok())
+        .filter(|fee| *fee <= amount)
+        .ok_or_else(|| anyhow!("Fee numerator {} exceeds the fee denominator", fee_numerator))
+}
+
+/// Relative shortfall of the curve's execution price against the spot price before the swap
+fn price_impact(sqrt_price: u128, a_to_b: bool, curve_in: u64, curve_out: u64) -> f64 {
+    if curve_in == 0 {
+        return 0.0;
+    }
+
+    let price = math::sqrt_price_to_price(sqrt_price);
+    let spot = if a_to_b { price } else { 1.0 / price };
+    if !spot.is_finite() || spot <= 0.0 {
+        return 0.0;
+    }
+
+    let execution = curve_out as f64 / curve_in as f64;
+    (1.0 - execution / spot).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meteora::fixtures::{self, SQRT_PRICE};
+    use crate::meteora::state::{BaseFee, DynamicFee};
+
+    // Expected amounts were worked out with exact integer arithmetic from the program's swap
+    // formulas, separately from this module, for a 6-decimal token against SOL.
+    const LIQUIDITY: u128 = 2_000_000_000_000 << 64;
+    const ACTIVATION_POINT: u64 = 1_000;
+
+    fn pool(base_fee: BaseFee, dynamic_fee: DynamicFee, collect_fee_mode: u8) -> PoolState {
+        let mut state = PoolState {
+            activation_point: ACTIVATION_POINT,
+            collect_fee_mode,
+            ..fixtures::sol_pool_state(SQRT_PRICE, LIQUIDITY)
+        };
+        state.pool_fees.base_fee = base_fee;
+        state.pool_fees.dynamic_fee = dynamic_fee;
+        state
+    }
+
+    /// Anti-sniper launch fee: 50% falling by 1% every 60 seconds
+    fn linear_scheduler() -> BaseFee {
+        BaseFee {
+            cliff_fee_numerator: 500_000_000,
+            fee_scheduler_mode: 0,
+            number_of_period: 40,
+            period_frequency: 60,
+            reduction_factor: 10_000_000,
+            ..BaseFee::default()
+        }
+    }
+
+    fn flat_fee(numerator: u64) -> BaseFee {
+        BaseFee { cliff_fee_numerator: numerator, ..BaseFee::default() }
+    }
+
+    fn volatile_dynamic_fee() -> DynamicFee {
+        DynamicFee {
+            initialized: 1,
+            variable_fee_control: 20_000,
+            bin_step: 10,
+            volatility_accumulator: 350_000,
+            ..DynamicFee::default()
+        }
+    }
+
+    #[test]
+    fn quotes_a_to_b_with_fee_scheduler() {
+        let state = pool(linear_scheduler(), DynamicFee::default(), 0);
+
+        // Two and a half periods after activation: 50% - 2 * 1%
+        let quote = quote_exact_in(&state, true, 5_000_000_000, ACTIVATION_POINT + 150).unwrap();
+
+        assert_eq!(quote.fee_numerator, 480_000_000);
+        assert!(!quote.fee_on_input);
+        assert_eq!(quote.amount_out, 129_997);
+        assert_eq!(quote.fee, 119_998);
+        assert_eq!(quote.sqrt_price_after, 130_435_872_451_079_822);
+    }
+
+    #[test]
+    fn quotes_b_to_a_with_dynamic_fee_on_input() {
+        let state = pool(flat_fee(2_500_000), volatile_dynamic_fee(), COLLECT_FEE_MODE_ONLY_B);
+
+        // 0.25% base plus ceil((350_000 * 10)^2 * 20_000 / 1e11) = 0.245% dynamic
+        let quote = quote_exact_in(&state, false, 250_000_000, ACTIVATION_POINT).unwrap();
+
+        assert_eq!(quote.fee_numerator, 4_950_000);
+        assert!(quote.fee_on_input);
+        assert_eq!(quote.fee, 1_237_500);
+        assert_eq!(quote.amount_out, 4_889_247_332_377);
+        assert_eq!(quote.sqrt_price_after, 132_732_607_339_645_911);
+    }
+
+    #[test]
+    fn quotes_b_to_a_with_dynamic_fee_on_output() {
+        let state = pool(flat_fee(2_500_000), volatile_dynamic_fee(), 0);
+
+        let quote = quote_exact_in(&state, false, 250_000_000, ACTIVATION_POINT).unwrap();
+
+        assert!(!quote.fee_on_input);
+        assert_eq!(quote.fee, 24_320_077_704);
+        assert_eq!(quote.amount_out, 4_888_826_933_088);
+        assert_eq!(quote.sqrt_price_after, 132_744_021_262_541_518);
+    }
+
+    #[test]
+    fn quotes_a_to_b_with_exponential_scheduler() {
+        let base_fee = BaseFee {
+            cliff_fee_numerator: 300_000_000,
+            fee_scheduler_mode: 1,
+            number_of_period: 10,
+            period_frequency: 10,
+            reduction_factor: 2_500,
+            ..BaseFee::default()
+        };
+        // OnlyB pools still take A-to-B fees from the output
+        let state = pool(base_fee, DynamicFee::default(), COLLECT_FEE_MODE_ONLY_B);
+
+        // Three periods in: 30% * 0.75^3
+        let quote = quote_exact_in(&state, true, 1_000_000_000, ACTIVATION_POINT + 35).unwrap();
+
+        assert_eq!(quote.fee_numerator, 126_562_500);
+        assert!(!quote.fee_on_input);
+        assert_eq!(quote.fee, 6_328);
+        assert_eq!(quote.amount_out, 43_671);
+    }
+
+    #[test]
+    fn rejects_swaps_out_of_range_or_without_liquidity() {
+        let mut state = pool(flat_fee(2_500_000), DynamicFee::default(), 0);
+        state.sqrt_max_price = SQRT_PRICE + 1;
+        assert!(quote_exact_in(&state, false, 250_000_000, ACTIVATION_POINT).is_err());
+
+        state.liquidity = 0;
+        assert!(quote_exact_in(&state, true, 1, ACTIVATION_POINT).is_err());
+    }
+}
@@ -0,0 +1,562 @@
+use anyhow::{Result, anyhow, Context};
+use borsh::BorshDeserialize;
+#[cfg(test)]
+use borsh::BorshSerialize;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::meteora::math;
+
+// Anchor account discriminator for the DAMM v2 `Pool` account (sha256("account:Pool")[..8])
+pub const POOL_DISCRIMINATOR: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+
+// Size of a DAMM v2 `Pool` account including the discriminator
+pub const POOL_ACCOUNT_SIZE: usize = 1112;
+
+// Byte offsets of the token mints in a `Pool` account, for memcmp filters
+pub const POOL_TOKEN_A_MINT_OFFSET: usize = 168;
+pub const POOL_TOKEN_B_MINT_OFFSET: usize = 200;
+
+// Anchor account discriminator for the DAMM v2 `Position` account (sha256("account:Position")[..8])
+pub const POSITION_DISCRIMINATOR: [u8; 8] = [170, 188, 143, 228, 122, 64, 247, 208];
+
+// Size of a DAMM v2 `Position` account including the discriminator
+pub const POSITION_ACCOUNT_SIZE: usize = 408;
+
+// Fee numerators are expressed over this denominator (1e9 = 100%)
+pub const FEE_DENOMINATOR: u64 = 1_000_000_000;
+
+// Basis point denominator used by the fee scheduler reduction factor
+pub const BASIS_POINT_MAX: u64 = 10_000;
+
+// Highest total trading fee the program charges (50%)
+pub const MAX_FEE_NUMERATOR: u64 = 500_000_000;
+
+// Scale of the squared volatility term in the dynamic fee formula
+const DYNAMIC_FEE_SCALE: u128 = 100_000_000_000;
+
+// Number of farming reward slots stored on each pool
+pub const NUM_REWARDS: usize = 2;
+
+/// Base fee configuration with an optional time/slot based fee scheduler
+#[derive(Debug, Clone, Default, BorshDeserialize)]
+#[cfg_attr(test, derive(BorshSerialize))]
+pub struct BaseFee {
+    pub cliff_fee_numerator: u64,
+    pub fee_scheduler_mode: u8,
+    pub padding_0: [u8; 5],
+    pub number_of_period: u16,
+    pub period_frequency: u64,
+    pub reduction_factor: u64,
+    pub padding_1: u64,
+}
+
+/// Volatility based dynamic fee state
+#[derive(Debug, Clone, Default, BorshDeserialize)]
+#[cfg_attr(test, derive(BorshSerialize))]
+pub struct DynamicFee {
+    pub initialized: u8,
+    pub padding: [u8; 7],
+    pub max_volatility_accumulator: u32,
+    pub variable_fee_control: u32,
+    pub bin_step: u16,
+    pub filter_period: u16,
+    pub decay_period: u16,
+    pub reduction_factor: u16,
+    pub last_update_timestamp: u64,
+    pub bin_step_u128: u128,
+    pub sqrt_price_reference: u128,
+    pub volatility_accumulator: u128,
+    pub volatility_reference: u128,
+}
+
+/// Fee configuration of a pool
+#[derive(Debug, Clone, Default, BorshDeserialize)]
+#[cfg_attr(test, derive(BorshSerialize))]
+pub struct PoolFees {
+    pub base_fee: BaseFee,
+    pub protocol_fee_percent: u8,
+    pub partner_fee_percent: u8,
+    pub referral_fee_percent: u8,
+    pub padding_0: [u8; 5],
+    pub dynamic_fee: DynamicFee,
+    pub padding_1: [u64; 2],
+}
+
+/// Cumulative fee metrics tracked by the pool
+#[derive(Debug, Clone, Default, BorshDeserialize)]
+#[cfg_attr(test, derive(BorshSerialize))]
+pub struct PoolMetrics {
+    pub total_lp_a_fee: u128,
+    pub total_lp_b_fee: u128,
+    pub total_protocol_a_fee: u64,
+    pub total_protocol_b_fee: u64,
+    pub total_partner_a_fee: u64,
+    pub total_partner_b_fee: u64,
+    pub total_position: u64,
+    pub padding: u64,
+}
+
+/// Farming reward slot
+#[derive(Debug, Clone, Default, BorshDeserialize)]
+#[cfg_attr(test, derive(BorshSerialize))]
+pub struct RewardInfo {
+    pub initialized: u8,
+    pub reward_token_flag: u8,
+    pub padding_0: [u8; 6],
+    pub padding_1: [u8; 8],
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub funder: Pubkey,
+    pub reward_duration: u64,
+    pub reward_duration_end: u64,
+    pub reward_rate: u128,
+    pub reward_per_token_stored: [u8; 32],
+    pub last_update_time: u64,
+    pub cumulative_seconds_with_empty_liquidity_reward: u64,
+}
+
+/// Decoded DAMM v2 `Pool` account
+#[derive(Debug, Clone, Default, BorshDeserialize)]
+#[cfg_attr(test, derive(BorshSerialize))]
+pub struct PoolState {
+    pub pool_fees: PoolFees,
+    pub token_a_mint: Pubkey,
+    pub token_b_mint: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub whitelisted_vault: Pubkey,
+    pub partner: Pubkey,
+    pub liquidity: u128,
+    pub padding: u128,
+    pub protocol_a_fee: u64,
+    pub protocol_b_fee: u64,
+    pub partner_a_fee: u64,
+    pub partner_b_fee: u64,
+    pub sqrt_min_price: u128,
+    pub sqrt_max_price: u128,
+    pub sqrt_price: u128,
+    pub activation_point: u64,
+    pub activation_type: u8,
+    pub pool_status: u8,
+    pub token_a_flag: u8,
+    pub token_b_flag: u8,
+    pub collect_fee_mode: u8,
+    pub pool_type: u8,
+    pub padding_0: [u8; 2],
+    pub fee_a_per_liquidity: [u8; 32],
+    pub fee_b_per_liquidity: [u8; 32],
+    pub permanent_lock_liquidity: u128,
+    pub metrics: PoolMetrics,
+    pub creator: Pubkey,
+    pub padding_1: [u64; 6],
+    pub reward_infos: [RewardInfo; NUM_REWARDS],
+}
+
+/// Fees claimed over the lifetime of a position
+#[derive(Debug, Clone, Default, BorshDeserialize)]
+pub struct PositionMetrics {
+    pub total_claimed_a_fee: u64,
+    pub total_claimed_b_fee: u64,
+}
+
+/// Per-position farming reward checkpoint
+#[derive(Debug, Clone, Default, BorshDeserialize)]
+pub struct UserRewardInfo {
+    pub reward_per_token_checkpoint: [u8; 32],
+    pub reward_pendings: u64,
+    pub total_claimed_rewards: u64,
+}
+
+/// Decoded DAMM v2 `Position` account
+#[derive(Debug, Clone, Default, BorshDeserialize)]
+pub struct PositionState {
+    pub pool: Pubkey,
+    pub nft_mint: Pubkey,
+    pub fee_a_per_token_checkpoint: [u8; 32],
+    pub fee_b_per_token_checkpoint: [u8; 32],
+    pub fee_a_pending: u64,
+    pub fee_b_pending: u64,
+    pub unlocked_liquidity: u128,
+    pub vested_liquidity: u128,
+    pub permanent_locked_liquidity: u128,
+    pub metrics: PositionMetrics,
+    pub reward_infos: [UserRewardInfo; NUM_REWARDS],
+    pub padding: [u128; 6],
+}
+
+/// How a pool's activation point is measured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivationType {
+    Slot,
+    Timestamp,
+}
+
+impl PoolState {
+    /// Decode a pool from raw account data, verifying the Anchor discriminator
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        if data.len() < POOL_ACCOUNT_SIZE {
+            return Err(anyhow!("Pool account data too short: {} bytes (expected {})",
+                data.len(), POOL_ACCOUNT_SIZE));
+        }
+
+        if data[..8] != POOL_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a DAMM v2 pool (discriminator mismatch)"));
+        }
+
+        let mut payload = &data[8..];
+        Self::deserialize(&mut payload)
+            .context("Failed to deserialize DAMM v2 pool account")
+    }
+
+    /// Whether the activation point is a slot or a unix timestamp
+    pub fn activation_type(&self) -> ActivationType {
+        if self.activation_type == 1 {
+            ActivationType::Timestamp
+        } else {
+            ActivationType::Slot
+        }
+    }
+
+    /// Whether trading is currently enabled on the pool
+    pub fn is_enabled(&self) -> bool {
+        self.pool_status == 0
+    }
+
+    /// Whether token A is a Token-2022 mint
+    pub fn is_token_a_2022(&self) -> bool {
+        self.token_a_flag == 1
+    }
+
+    /// Whether token B is a Token-2022 mint
+    pub fn is_token_b_2022(&self) -> bool {
+        self.token_b_flag == 1
+    }
+
+    /// Liquidity that can still be withdrawn by LPs (excludes permanently locked liquidity)
+    pub fn unlocked_liquidity(&self) -> u128 {
+        self.liquidity.saturating_sub(self.permanent_lock_liquidity)
+    }
+
+    /// Base fee numerator at the given point (slot or timestamp, matching `activation_type`)
+    pub fn base_fee_numerator(&self, current_point: u64) -> u64 {
+        self.pool_fees.base_fee.fee_numerator(current_point, self.activation_point)
+    }
+
+    /// Base fee in basis points at the given point
+    pub fn base_fee_bps(&self, current_point: u64) -> u16 {
+        let numerator = self.base_fee_numerator(current_point);
+        (numerator * BASIS_POINT_MAX / FEE_DENOMINATOR).min(u16::MAX as u64) as u16
+    }
+
+    /// Total trading fee numerator at the given point: scheduled base fee plus the dynamic fee, if enabled
+    pub fn fee_numerator(&self, current_point: u64) -> u64 {
+        let variable = self.pool_fees.dynamic_fee.variable_fee_numerator();
+        self.base_fee_numerator(current_point)
+            .saturating_add(variable)
+            .min(MAX_FEE_NUMERATOR)
+    }
+}
+
+impl DynamicFee {
+    /// Whether the pool charges a volatility based fee on top of its base fee
+    pub fn is_enabled(&self) -> bool {
+        self.initialized != 0
+    }
+
+    /// Variable fee numerator from the current volatility accumulator
+    /// fee = ceil((volatility_accumulator * bin_step)^2 * variable_fee_control / 1e11)
+    pub fn variable_fee_numerator(&self) -> u64 {
+        if !self.is_enabled() {
+            return 0;
+        }
+
+        let fee = self.volatility_accumulator
+            .checked_mul(self.bin_step as u128)
+            .and_then(|v| v.checked_mul(v))
+            .and_then(|v| v.checked_mul(self.variable_fee_control as u128))
+            .map(|v| v.div_ceil(DYNAMIC_FEE_SCALE))
+            .unwrap_or(u128::MAX);
+
+        fee.min(u64::MAX as u128) as u64
+    }
+}
+
+impl BaseFee {
+    /// Compute the scheduled fee numerator for the given point
+    pub fn fee_numerator(&self, current_point: u64, activation_point: u64) -> u64 {
+        if self.period_frequency == 0 {
+            return self.cliff_fee_numerator;
+        }
+
+        // Before activation only whitelisted buyers can trade, at the minimum fee
+        let period = if current_point < activation_point {
+            self.number_of_period as u64
+        } else {
+            ((current_point - activation_point) / self.period_frequency)
+                .min(self.number_of_period as u64)
+        };
+
+        match self.fee_scheduler_mode {
+            // Linear: reduce by a fixed amount each period
+            0 => self.cliff_fee_numerator
+                .saturating_sub(period.saturating_mul(self.reduction_factor)),
+            // Exponential: reduce by `reduction_factor` basis points each period
+            _ => {
+                let retained = 1.0 - self.reduction_factor.min(BASIS_POINT_MAX) as f64 / BASIS_POINT_MAX as f64;
+                (self.cliff_fee_numerator as f64 * retained.powi(period as i32)) as u64
+            }
+        }
+    }
+}
+
+impl PositionState {
+    /// Decode a position from raw account data, verifying the Anchor discriminator
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        if data.len() < POSITION_ACCOUNT_SIZE {
+            return Err(anyhow!("Position account data too short: {} bytes (expected {})",
+                data.len(), POSITION_ACCOUNT_SIZE));
+        }
+
+        if data[..8] != POSITION_DISCRIMINATOR {
+            return Err(anyhow!("Account is not a DAMM v2 position (discriminator mismatch)"));
+        }
+
+        let mut payload = &data[8..];
+        Self::deserialize(&mut payload)
+            .context("Failed to deserialize DAMM v2 position account")
+    }
+
+    /// Total liquidity of the position, locked or not
+    pub fn total_liquidity(&self) -> u128 {
+        self.unlocked_liquidity
+            .saturating_add(self.vested_liquidity)
+            .saturating_add(self.permanent_locked_liquidity)
+    }
+
+    /// Fees claimable right now given the pool's current fee accumulators
+    pub fn pending_fees(&self, pool: &PoolState) -> (u64, u64) {
+        let liquidity = self.total_liquidity();
+
+        let fee_a = accrued_fee(liquidity, &pool.fee_a_per_liquidity, &self.fee_a_per_token_checkpoint);
+        let fee_b = accrued_fee(liquidity, &pool.fee_b_per_liquidity, &self.fee_b_per_token_checkpoint);
+
+        (
+            self.fee_a_pending.saturating_add(fee_a),
+            self.fee_b_pending.saturating_add(fee_b),
+        )
+    }
+}
+
+/// Split a little-endian U256 into (hi, lo) u128 halves
+fn u256_halves(value: &[u8; 32]) -> (u128, u128) {
+    let mut lo = [0u8; 16];
+    let mut hi = [0u8; 16];
+    lo.copy_from_slice(&value[..16]);
+    hi.copy_from_slice(&value[16..]);
+    (u128::from_le_bytes(hi), u128::from_le_bytes(lo))
+}
+
+/// Fee earned since a checkpoint: liquidity * (fee_per_liquidity - checkpoint) >> 128
+fn accrued_fee(liquidity: u128, fee_per_liquidity: &[u8; 32], checkpoint: &[u8; 32]) -> u64 {
+    let (current_hi, current_lo) = u256_halves(fee_per_liquidity);
+    let (checkpoint_hi, checkpoint_lo) = u256_halves(checkpoint);
+
+    // 256-bit subtraction; the accumulator only grows so a negative delta means stale data
+    let (delta_lo, borrow) = current_lo.overflowing_sub(checkpoint_lo);
+    let delta_hi = match current_hi.checked_sub(checkpoint_hi).and_then(|hi| hi.checked_sub(borrow as u128)) {
+        Some(hi) => hi,
+        None => return 0,
+    };
+
+    let fee = liquidity
+        .saturating_mul(delta_hi)
+        .saturating_add(math::mul_shr_128(liquidity, delta_lo, false));
+
+    fee.min(u64::MAX as u128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    // Fixtures are laid out field by field at the offsets of the DAMM v2 IDL, independently of
+    // the Borsh derives above, so a reordered or resized field fails to decode to these values.
+    const TOKEN_A_MINT: &str = "2zMMhcVQEXDtdE6vsFS7S7D5oUodfJHE8vd1gnBouauv";
+    const TOKEN_B_MINT: &str = "So11111111111111111111111111111111111111112";
+    const TOKEN_A_VAULT: &str = "6ZzGiM7Zt4NJQ5cUvnpsgdR4bDZcRW9m2V8zRaVqR6fK";
+    const TOKEN_B_VAULT: &str = "9X2m8VhFvVUFNLMy1FjTvvKzmbc7p6SaWRpSRZfPLbyj";
+    const CREATOR: &str = "CkBw8FVRz7nWxfJjN4KpfgrvySaQ5RSqcX2dn6hYYkJP";
+    const POOL: &str = "8sLbNZoA1cfnvMJLPfp98ZLAnFSYCFApfJKMbiXNLwxj";
+    const NFT_MINT: &str = "7Ba9mhCw2kpDGJbP9ZTMQxMRgfQa5MTiG2UvGkd8QvV1";
+
+    const SQRT_PRICE: u128 = 18_446_744_073_709_551_616 * 3;
+    const LIQUIDITY: u128 = 1_234_567_890_123_456_789_012;
+    const CLIFF_FEE_NUMERATOR: u64 = 500_000_000;
+    const PERIOD_FREQUENCY: u64 = 60;
+    const SCHEDULER_REDUCTION: u64 = 20_000_000;
+    const VARIABLE_FEE_CONTROL: u32 = 7_500;
+    const BIN_STEP: u16 = 1;
+    const VOLATILITY_ACCUMULATOR: u128 = 100_000;
+    const ACTIVATION_POINT: u64 = 1_750_000_000;
+
+    fn key(address: &str) -> Pubkey {
+        Pubkey::from_str(address).unwrap()
+    }
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn pool_fixture() -> Vec<u8> {
+        let mut data = vec![0u8; POOL_ACCOUNT_SIZE];
+        put(&mut data, 0, &POOL_DISCRIMINATOR);
+
+        // pool_fees.base_fee: linear scheduler, 50% cliff falling 2% per minute over 10 periods
+        put(&mut data, 8, &CLIFF_FEE_NUMERATOR.to_le_bytes());
+        put(&mut data, 16, &[0]);
+        put(&mut data, 22, &10u16.to_le_bytes());
+        put(&mut data, 24, &PERIOD_FREQUENCY.to_le_bytes());
+        put(&mut data, 32, &SCHEDULER_REDUCTION.to_le_bytes());
+        put(&mut data, 48, &[20, 0, 20]);
+
+        // pool_fees.dynamic_fee
+        put(&mut data, 56, &[1]);
+        put(&mut data, 64, &14_460_000u32.to_le_bytes());
+        put(&mut data, 68, &VARIABLE_FEE_CONTROL.to_le_bytes());
+        put(&mut data, 72, &BIN_STEP.to_le_bytes());
+        put(&mut data, 74, &10u16.to_le_bytes());
+        put(&mut data, 76, &120u16.to_le_bytes());
+        put(&mut data, 78, &5_000u16.to_le_bytes());
+        put(&mut data, 120, &VOLATILITY_ACCUMULATOR.to_le_bytes());
+
+        put(&mut data, POOL_TOKEN_A_MINT_OFFSET, key(TOKEN_A_MINT).as_ref());
+        put(&mut data, POOL_TOKEN_B_MINT_OFFSET, key(TOKEN_B_MINT).as_ref());
+        put(&mut data, 232, key(TOKEN_A_VAULT).as_ref());
+        put(&mut data, 264, key(TOKEN_B_VAULT).as_ref());
+        put(&mut data, 360, &LIQUIDITY.to_le_bytes());
+        put(&mut data, 424, &4_295_048_016u128.to_le_bytes());
+        put(&mut data, 440, &79_226_673_521_066_979_257_578_248_091u128.to_le_bytes());
+        put(&mut data, 456, &SQRT_PRICE.to_le_bytes());
+        put(&mut data, 472, &ACTIVATION_POINT.to_le_bytes());
+        // activation_type (timestamp), pool_status, token flags, collect_fee_mode, pool_type
+        put(&mut data, 480, &[1, 0, 0, 0, 1, 0]);
+        put(&mut data, 552, &1_000u128.to_le_bytes());
+        put(&mut data, 568, &777u128.to_le_bytes());
+        put(&mut data, 648, key(CREATOR).as_ref());
+
+        data
+    }
+
+    fn position_fixture() -> Vec<u8> {
+        let mut data = vec![0u8; POSITION_ACCOUNT_SIZE];
+        put(&mut data, 0, &POSITION_DISCRIMINATOR);
+        put(&mut data, 8, key(POOL).as_ref());
+        put(&mut data, 40, key(NFT_MINT).as_ref());
+        put(&mut data, 136, &11u64.to_le_bytes());
+        put(&mut data, 144, &22u64.to_le_bytes());
+        put(&mut data, 152, &5_000u128.to_le_bytes());
+        put(&mut data, 168, &300u128.to_le_bytes());
+        put(&mut data, 184, &200u128.to_le_bytes());
+        put(&mut data, 200, &33u64.to_le_bytes());
+        put(&mut data, 208, &44u64.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn discriminators_match_anchor_account_names() {
+        let discriminator = |name: &str| solana_sdk::hash::hash(format!("account:{}", name).as_bytes()).to_bytes()[..8].to_vec();
+        assert_eq!(discriminator("Pool"), POOL_DISCRIMINATOR);
+        assert_eq!(discriminator("Position"), POSITION_DISCRIMINATOR);
+    }
+
+    #[test]
+    fn decodes_pool_account() {
+        let data = pool_fixture();
+        assert_eq!(data[..8], POOL_DISCRIMINATOR);
+
+        let pool = PoolState::from_account_data(&data).unwrap();
+        assert_eq!(pool.token_a_mint, key(TOKEN_A_MINT));
+        assert_eq!(pool.token_b_mint, key(TOKEN_B_MINT));
+        assert_eq!(pool.token_a_vault, key(TOKEN_A_VAULT));
+        assert_eq!(pool.token_b_vault, key(TOKEN_B_VAULT));
+        assert_eq!(pool.sqrt_price, SQRT_PRICE);
+        assert_eq!(pool.liquidity, LIQUIDITY);
+        assert_eq!(pool.unlocked_liquidity(), LIQUIDITY - 1_000);
+        assert_eq!(pool.activation_point, ACTIVATION_POINT);
+        assert_eq!(pool.activation_type(), ActivationType::Timestamp);
+        assert!(pool.is_enabled());
+        assert_eq!(pool.collect_fee_mode, 1);
+        assert_eq!(pool.metrics.total_lp_a_fee, 777);
+        assert_eq!(pool.creator, key(CREATOR));
+
+        let fees = &pool.pool_fees;
+        assert_eq!(fees.base_fee.cliff_fee_numerator, CLIFF_FEE_NUMERATOR);
+        assert_eq!(fees.base_fee.number_of_period, 10);
+        assert_eq!(fees.base_fee.period_frequency, PERIOD_FREQUENCY);
+        assert_eq!(fees.base_fee.reduction_factor, SCHEDULER_REDUCTION);
+        assert_eq!(fees.protocol_fee_percent, 20);
+        assert_eq!(fees.referral_fee_percent, 20);
+        assert!(fees.dynamic_fee.is_enabled());
+        assert_eq!(fees.dynamic_fee.variable_fee_control, VARIABLE_FEE_CONTROL);
+        assert_eq!(fees.dynamic_fee.bin_step, BIN_STEP);
+        assert_eq!(fees.dynamic_fee.volatility_accumulator, VOLATILITY_ACCUMULATOR);
+    }
+
+    #[test]
+    fn computes_scheduled_and_dynamic_fees() {
+        let pool = PoolState::from_account_data(&pool_fixture()).unwrap();
+
+        // Three periods in: 50% - 3 * 2%
+        let point = ACTIVATION_POINT + 3 * PERIOD_FREQUENCY;
+        assert_eq!(pool.base_fee_numerator(point), 440_000_000);
+        assert_eq!(pool.base_fee_bps(point), 4_400);
+        // Past the last period the scheduler bottoms out at 50% - 10 * 2%
+        assert_eq!(pool.base_fee_numerator(ACTIVATION_POINT + 3_600), 300_000_000);
+
+        // ceil((100_000 * 1)^2 * 7_500 / 1e11) = 750
+        assert_eq!(pool.pool_fees.dynamic_fee.variable_fee_numerator(), 750);
+        assert_eq!(pool.fee_numerator(point), 440_000_750);
+    }
+
+    #[test]
+    fn decodes_position_account() {
+        let data = position_fixture();
+        assert_eq!(data[..8], POSITION_DISCRIMINATOR);
+
+        let position = PositionState::from_account_data(&data).unwrap();
+        assert_eq!(position.pool, key(POOL));
+        assert_eq!(position.nft_mint, key(NFT_MINT));
+        assert_eq!(position.fee_a_pending, 11);
+        assert_eq!(position.fee_b_pending, 22);
+        assert_eq!(position.unlocked_liquidity, 5_000);
+        assert_eq!(position.vested_liquidity, 300);
+        assert_eq!(position.permanent_locked_liquidity, 200);
+        assert_eq!(position.total_liquidity(), 5_500);
+        assert_eq!(position.metrics.total_claimed_a_fee, 33);
+        assert_eq!(position.metrics.total_claimed_b_fee, 44);
+    }
+
+    #[test]
+    fn rejects_truncated_accounts() {
+        for len in [0, 7, 8, POOL_ACCOUNT_SIZE - 1] {
+            assert!(PoolState::from_account_data(&pool_fixture()[..len]).is_err());
+        }
+        for len in [0, 7, 8, POSITION_ACCOUNT_SIZE - 1] {
+            assert!(PositionState::from_account_data(&position_fixture()[..len]).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_wrong_discriminator() {
+        let mut pool = pool_fixture();
+        pool[0] ^= 0xff;
+        assert!(PoolState::from_account_data(&pool).is_err());
+
+        // A position is never decoded as a pool or the other way around
+        let mut position_as_pool = pool_fixture();
+        put(&mut position_as_pool, 0, &POSITION_DISCRIMINATOR);
+        assert!(PoolState::from_account_data(&position_as_pool).is_err());
+        assert!(PositionState::from_account_data(&pool_fixture()).is_err());
+    }
+}
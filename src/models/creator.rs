@@ -0,0 +1,16 @@
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+/// Track record of a pool creator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatorStats {
+    pub creator: Pubkey,
+    /// Pools we have seen from this creator
+    pub pools_created: u64,
+    /// Pools old enough to have been checked for a rug
+    pub pools_checked: u64,
+    /// Pools whose liquidity was pulled or whose token went to zero soon after launch
+    pub pools_rugged: u64,
+    /// Our realized profit/loss across this creator's pools, in SOL, leaving out paper trades
+    pub profit_loss_sol: f64,
+}
@@ -1,7 +1,14 @@
 pub mod pool;
 mod position;
+mod creator;
 
 pub use pool::Pool;
 pub use pool::TokenInfo;
+pub use pool::SafetyCheck;
+pub use pool::HolderDistribution;
+pub use pool::ScoreFactor;
+pub use pool::TokenPair;
 pub use position::Position;
-pub use position::PositionStatus; 
\ No newline at end of file
+pub use position::PositionStatus;
+pub use position::ExitReason;
+pub use creator::CreatorStats;
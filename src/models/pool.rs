@@ -1,6 +1,13 @@
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use chrono::{DateTime, Utc};
+use std::fmt;
+
+use crate::meteora::{WSOL_MINT, USDC_MINT, USDT_MINT};
+use crate::models::CreatorStats;
+
+// Tokens a pool can be quoted in, most preferred first
+pub const QUOTE_MINTS_BY_PRIORITY: [&str; 3] = [WSOL_MINT, USDC_MINT, USDT_MINT];
 
 /// Represents a Meteora V2 pool
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,6 +18,78 @@ pub struct Pool {
     pub discovered_at: DateTime<Utc>,
     pub analyzed: bool,
     pub score: Option<f64>,
+    /// Factors the score is made of
+    #[serde(default)]
+    pub score_breakdown: Vec<ScoreFactor>,
+    /// Token safety checks run during analysis
+    #[serde(default)]
+    pub safety_checks: Vec<SafetyCheck>,
+    /// Total value locked in lamports at the last analysis
+    #[serde(default)]
+    pub tvl_lamports: Option<u64>,
+    /// Holder distribution of the pool's non-quote tokens at the last analysis
+    #[serde(default)]
+    pub holders: Vec<HolderDistribution>,
+    /// Account that created the pool
+    #[serde(default)]
+    pub creator: Option<Pubkey>,
+    /// Base fee in basis points at the last analysis
+    #[serde(default)]
+    pub fee_bps: Option<u16>,
+    /// When the pool started trading
+    #[serde(default)]
+    pub activated_at: Option<DateTime<Utc>>,
+    /// Track record of the creator at the last analysis
+    #[serde(default)]
+    pub creator_stats: Option<CreatorStats>,
+    /// Whether the pool was rugged shortly after discovery, once that has been checked
+    #[serde(default)]
+    pub rugged: Option<bool>,
+}
+
+impl Pool {
+    /// The first safety check the pool failed, if any
+    pub fn failed_safety_check(&self) -> Option<&SafetyCheck> {
+        self.safety_checks.iter().find(|check| !check.passed)
+    }
+
+    /// The pool's tokens as base and quote, if either is a quote token
+    ///
+    /// When both are, the more preferred one (WSOL, then USDC, then USDT) is the quote.
+    pub fn pair(&self) -> Option<TokenPair> {
+        let priority = |token: &TokenInfo| {
+            let mint = token.mint.to_string();
+            QUOTE_MINTS_BY_PRIORITY.iter().position(|quote| *quote == mint)
+        };
+
+        let quote_is_token_a = match (priority(&self.token_a), priority(&self.token_b)) {
+            (Some(a), Some(b)) => a < b,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => return None,
+        };
+
+        let (base, quote) = if quote_is_token_a {
+            (self.token_b.clone(), self.token_a.clone())
+        } else {
+            (self.token_a.clone(), self.token_b.clone())
+        };
+        Some(TokenPair { base, quote, quote_is_token_a })
+    }
+}
+
+/// A pool's tokens in canonical order: the traded token and what it is priced in
+#[derive(Debug, Clone)]
+pub struct TokenPair {
+    pub base: TokenInfo,
+    pub quote: TokenInfo,
+    pub quote_is_token_a: bool,
+}
+
+impl fmt::Display for TokenPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.base.label(), self.quote.label())
+    }
 }
 
 /// Information about a token in a pool
@@ -20,4 +99,98 @@ pub struct TokenInfo {
     pub name: Option<String>,
     pub symbol: Option<String>,
     pub decimals: Option<u8>,
-} 
\ No newline at end of file
+}
+
+impl TokenInfo {
+    /// Symbol if known, otherwise the mint address
+    pub fn label(&self) -> String {
+        self.symbol.clone().unwrap_or_else(|| self.mint.to_string())
+    }
+}
+
+/// Result of one named safety check on a pool token
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafetyCheck {
+    /// Stable name of the check, e.g. `mint_authority`
+    pub name: String,
+    pub mint: Pubkey,
+    pub passed: bool,
+    /// What was found, for logs and later inspection
+    pub detail: String,
+}
+
+/// How a token's supply is spread across its holders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HolderDistribution {
+    pub mint: Pubkey,
+    /// Token accounts with a non-zero balance, if the RPC allowed scanning for them
+    pub holder_count: Option<u64>,
+    /// Number of largest holders counted in `top_holders_share`
+    pub top_holders: usize,
+    /// Fraction of the supply held by the largest holders (0-1)
+    pub top_holders_share: f64,
+}
+
+/// One weighted factor of a pool's score
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreFactor {
+    /// Name of the scorer, e.g. `liquidity`
+    pub name: String,
+    /// Factor score (0-1)
+    pub score: f64,
+    /// Share of the total score, so a pool's score is the sum of weight × score over its factors
+    pub weight: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool(token_a: Pubkey, token_b: Pubkey) -> Pool {
+        let token = |mint: Pubkey| TokenInfo { mint, name: None, symbol: None, decimals: None };
+        Pool {
+            address: Pubkey::new_unique(),
+            token_a: token(token_a),
+            token_b: token(token_b),
+            discovered_at: Utc::now(),
+            analyzed: false,
+            score: None,
+            score_breakdown: Vec::new(),
+            safety_checks: Vec::new(),
+            tvl_lamports: None,
+            holders: Vec::new(),
+            creator: None,
+            fee_bps: None,
+            activated_at: None,
+            creator_stats: None,
+            rugged: None,
+        }
+    }
+
+    #[test]
+    fn pair_takes_the_quote_from_either_side() {
+        let wsol: Pubkey = WSOL_MINT.parse().unwrap();
+        let token = Pubkey::new_unique();
+
+        let pair = pool(token, wsol).pair().unwrap();
+        assert_eq!((pair.base.mint, pair.quote.mint, pair.quote_is_token_a), (token, wsol, false));
+
+        let pair = pool(wsol, token).pair().unwrap();
+        assert_eq!((pair.base.mint, pair.quote.mint, pair.quote_is_token_a), (token, wsol, true));
+
+        assert!(pool(token, Pubkey::new_unique()).pair().is_none());
+    }
+
+    #[test]
+    fn pair_prefers_wsol_then_usdc_then_usdt() {
+        let wsol: Pubkey = WSOL_MINT.parse().unwrap();
+        let usdc: Pubkey = USDC_MINT.parse().unwrap();
+        let usdt: Pubkey = USDT_MINT.parse().unwrap();
+
+        for (a, b, quote) in [(usdc, wsol, wsol), (wsol, usdt, wsol), (usdt, usdc, usdc), (usdc, usdt, usdc)] {
+            let pair = pool(a, b).pair().unwrap();
+            assert_eq!(pair.quote.mint, quote);
+            assert_eq!(pair.quote_is_token_a, quote == a);
+        }
+    }
+}
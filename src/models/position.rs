@@ -1,26 +1,181 @@
+use anyhow::{Result, anyhow};
 use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use chrono::{DateTime, Utc};
+use std::fmt;
+use std::str::FromStr;
 
 /// Represents a liquidity position
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Position {
+    /// Database row ID, assigned when the position is first saved
+    pub id: Option<i64>,
     pub pool: Pubkey,
+    /// On-chain DAMM v2 position account
+    pub position_address: Option<Pubkey>,
+    /// Mint of the NFT that owns the position
+    pub position_nft_mint: Option<Pubkey>,
     pub created_at: DateTime<Utc>,
     pub closed_at: Option<DateTime<Utc>>,
     pub sol_invested: f64,
+    pub token_a_amount_in: Option<u64>,
+    pub token_b_amount_in: Option<u64>,
+    /// Token A bought on entry but left over after the deposit, which the position still owns
+    pub token_a_refund: Option<u64>,
+    /// Token B bought on entry but left over after the deposit, which the position still owns
+    pub token_b_refund: Option<u64>,
+    pub token_a_amount_out: Option<u64>,
+    pub token_b_amount_out: Option<u64>,
+    pub entry_signature: Option<String>,
+    pub exit_signature: Option<String>,
     pub fee_claimed: Option<f64>,
     pub profit_loss: Option<f64>,
+    /// Value of the liquidity minus the value of holding the entry amounts, in SOL (negative is a loss)
+    pub impermanent_loss: Option<f64>,
     pub status: PositionStatus,
+    /// Paper-trading position: no transactions were sent and all amounts are hypothetical
+    pub simulated: bool,
+    /// What ended the position, once it started exiting
+    pub exit_reason: Option<ExitReason>,
+}
+
+impl Position {
+    /// Create a new, not yet entered position
+    pub fn new(pool: Pubkey, sol_invested: f64) -> Self {
+        Self {
+            id: None,
+            pool,
+            position_address: None,
+            position_nft_mint: None,
+            created_at: Utc::now(),
+            closed_at: None,
+            sol_invested,
+            token_a_amount_in: None,
+            token_b_amount_in: None,
+            token_a_refund: None,
+            token_b_refund: None,
+            token_a_amount_out: None,
+            token_b_amount_out: None,
+            entry_signature: None,
+            exit_signature: None,
+            fee_claimed: None,
+            profit_loss: None,
+            impermanent_loss: None,
+            status: PositionStatus::Created,
+            simulated: false,
+            exit_reason: None,
+        }
+    }
+
+    /// Add a confirmed fee claim (valued in SOL) to the position's running total
+    pub fn record_fee_claim(&mut self, value_sol: f64) {
+        self.fee_claimed = Some(self.fee_claimed.unwrap_or(0.0) + value_sol);
+    }
+
+    /// Whether the position may still hold liquidity
+    pub fn is_open(&self) -> bool {
+        !matches!(self.status, PositionStatus::Closed | PositionStatus::Failed)
+    }
 }
 
 /// Status of a liquidity position
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PositionStatus {
     Created,
+    /// The entry transaction is being sent; the position address is recorded but may not exist yet
+    Opening,
     Active,
     ClaimingFees,
     Exiting,
     Closed,
     Failed,
-} 
\ No newline at end of file
+}
+
+impl PositionStatus {
+    /// Name of the status as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PositionStatus::Created => "created",
+            PositionStatus::Opening => "opening",
+            PositionStatus::Active => "active",
+            PositionStatus::ClaimingFees => "claiming_fees",
+            PositionStatus::Exiting => "exiting",
+            PositionStatus::Closed => "closed",
+            PositionStatus::Failed => "failed",
+        }
+    }
+}
+
+impl FromStr for PositionStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "created" => Ok(PositionStatus::Created),
+            "opening" => Ok(PositionStatus::Opening),
+            "active" => Ok(PositionStatus::Active),
+            "claiming_fees" => Ok(PositionStatus::ClaimingFees),
+            "exiting" => Ok(PositionStatus::Exiting),
+            "closed" => Ok(PositionStatus::Closed),
+            "failed" => Ok(PositionStatus::Failed),
+            _ => Err(anyhow!("Unknown position status: {}", s)),
+        }
+    }
+}
+
+/// Why a position was exited
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExitReason {
+    /// The sprint ran for its full duration
+    MaxDuration,
+    /// Accrued fees reached the take-profit share of the capital
+    TakeProfit,
+    /// Fees minus impermanent loss fell to the stop-loss
+    StopLoss,
+    /// The pool saw no swaps for the configured time
+    VolumeDriedUp,
+    /// The rug guard saw liquidity pulled, the price collapse or a large holder dump
+    Rug,
+    /// The pool was disabled
+    PoolDisabled,
+    /// An exit was requested through the position's handle
+    Forced,
+}
+
+impl ExitReason {
+    /// Name of the reason as stored in the database
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExitReason::MaxDuration => "max_duration",
+            ExitReason::TakeProfit => "take_profit",
+            ExitReason::StopLoss => "stop_loss",
+            ExitReason::VolumeDriedUp => "volume_dried_up",
+            ExitReason::Rug => "rug",
+            ExitReason::PoolDisabled => "pool_disabled",
+            ExitReason::Forced => "forced",
+        }
+    }
+}
+
+impl fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ExitReason {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "max_duration" => Ok(ExitReason::MaxDuration),
+            "take_profit" => Ok(ExitReason::TakeProfit),
+            "stop_loss" => Ok(ExitReason::StopLoss),
+            "volume_dried_up" => Ok(ExitReason::VolumeDriedUp),
+            "rug" => Ok(ExitReason::Rug),
+            "pool_disabled" => Ok(ExitReason::PoolDisabled),
+            "forced" => Ok(ExitReason::Forced),
+            _ => Err(anyhow!("Unknown exit reason: {}", s)),
+        }
+    }
+}
@@ -2,9 +2,11 @@ pub mod pool_monitor;
 #[cfg(feature = "telegram")]
 mod telegram;
 pub mod websocket;
+pub mod recorder;
 
 pub use pool_monitor::PoolMonitor;
 pub use websocket::MeteoraPoolMonitor;
+pub use recorder::PoolRecorder;
 
 #[cfg(feature = "telegram")]
 pub use telegram::TelegramMonitor; 
\ No newline at end of file
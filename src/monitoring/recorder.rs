@@ -0,0 +1,253 @@
+use anyhow::{Result, Context};
+use log::{info, debug, warn, error};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcTransactionLogsFilter, RpcTransactionLogsConfig};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::fs::OpenOptions;
+use std::sync::Arc;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tokio::time::{self, Duration};
+use futures::StreamExt;
+use chrono::Utc;
+
+use crate::backtest::PoolSnapshot;
+use crate::meteora::MeteoraClient;
+use crate::solana::SolanaClient;
+
+/// Records pool snapshots to a JSON Lines file for backtesting and replaying incidents
+///
+/// Each recorded pool is watched for `window`: a snapshot is written when recording starts,
+/// whenever the pool account changes or a swap mentions it, and when the window closes.
+#[derive(Clone)]
+pub struct PoolRecorder {
+    ws_url: String,
+    client: SolanaClient,
+    meteora_client: Arc<MeteoraClient>,
+    window: Duration,
+    output: Arc<Mutex<File>>,
+}
+
+impl PoolRecorder {
+    /// Create a recorder appending snapshots to `path`
+    pub fn new(rpc_url: &str, solana_client: SolanaClient, path: &str, window_seconds: u64) -> Result<Self> {
+        let output = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open recording file: {}", path))?;
+
+        // Convert HTTP URL to WebSocket URL if needed
+        let ws_url = if rpc_url.starts_with("http") {
+            rpc_url.replace("http", "ws")
+        } else {
+            rpc_url.to_string()
+        };
+
+        Ok(Self {
+            ws_url,
+            meteora_client: Arc::new(MeteoraClient::new(solana_client.clone())),
+            client: solana_client,
+            window: Duration::from_secs(window_seconds),
+            output: Arc::new(Mutex::new(File::from_std(output))),
+        })
+    }
+
+    /// Start recording a pool in the background for the configured window
+    pub fn record(&self, pool: Pubkey) {
+        let recorder = self.clone();
+
+        tokio::spawn(async move {
+            info!("Recording pool {} for {}s", pool, recorder.window.as_secs());
+            match recorder.record_window(pool).await {
+                Ok(count) => info!("Finished recording pool {}: {} snapshots", pool, count),
+                Err(e) => error!("Recording of pool {} stopped: {}", pool, e),
+            }
+        });
+    }
+
+    /// Watch a pool until the window closes, returning the number of snapshots written
+    async fn record_window(&self, pool: Pubkey) -> Result<usize> {
+        let pubsub_client = PubsubClient::new(&self.ws_url).await
+            .with_context(|| format!("Failed to connect to {}", self.ws_url))?;
+
+        let commitment = CommitmentConfig::confirmed();
+        let (mut account_updates, account_unsubscribe) = pubsub_client.account_subscribe(
+            &pool,
+            Some(RpcAccountInfoConfig {
+                commitment: Some(commitment),
+                ..RpcAccountInfoConfig::default()
+            }),
+        ).await?;
+        let (mut swap_logs, logs_unsubscribe) = pubsub_client.logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![pool.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(commitment),
+            },
+        ).await?;
+
+        let mut last_data = None;
+        let mut written = 0;
+        let deadline = time::sleep(self.window);
+        tokio::pin!(deadline);
+
+        written += self.capture(&pool, None, None, &mut last_data).await as usize;
+
+        loop {
+            tokio::select! {
+                _ = &mut deadline => break,
+                update = account_updates.next() => match update {
+                    Some(update) => {
+                        written += self.capture(&pool, Some(update.context.slot), None, &mut last_data).await as usize;
+                    },
+                    None => {
+                        warn!("Account subscription for pool {} closed early", pool);
+                        break;
+                    }
+                },
+                logs = swap_logs.next() => match logs {
+                    Some(logs) if logs.value.err.is_none() && is_swap(&logs.value.logs) => {
+                        let slot = logs.context.slot;
+                        written += self.capture(&pool, Some(slot), Some(logs.value.signature), &mut last_data).await as usize;
+                    },
+                    Some(_) => {},
+                    None => {
+                        warn!("Log subscription for pool {} closed early", pool);
+                        break;
+                    }
+                },
+            }
+        }
+
+        written += self.capture(&pool, None, None, &mut last_data).await as usize;
+
+        account_unsubscribe().await;
+        logs_unsubscribe().await;
+
+        Ok(written)
+    }
+
+    /// Write a snapshot of the pool if its account changed since the last one, returning whether it was written
+    async fn capture(
+        &self,
+        pool: &Pubkey,
+        slot: Option<u64>,
+        signature: Option<String>,
+        last_data: &mut Option<Vec<u8>>,
+    ) -> bool {
+        let (pool_info, data) = match self.meteora_client.get_pool_info_with_data(pool).await {
+            Ok(fetched) => fetched,
+            Err(e) => {
+                warn!("Failed to snapshot pool {}: {}", pool, e);
+                return false;
+            }
+        };
+
+        if !is_new_state(last_data, &data) {
+            return false;
+        }
+
+        // Notifications carry their slot; the opening and closing snapshots ask the node
+        let slot = match slot {
+            Some(slot) => slot,
+            None => self.client.get_slot().unwrap_or_default(),
+        };
+        let mut snapshot = PoolSnapshot::from_pool_info(&pool_info, &data, Utc::now().timestamp(), slot);
+        snapshot.signature = signature;
+
+        if let Err(e) = self.write(&snapshot).await {
+            error!("Failed to write snapshot of pool {}: {}", pool, e);
+            return false;
+        }
+
+        debug!("Recorded snapshot of pool {} at slot {}", pool, slot);
+        *last_data = Some(data);
+        true
+    }
+
+    /// Append a snapshot to the recording file
+    async fn write(&self, snapshot: &PoolSnapshot) -> Result<()> {
+        let mut output = self.output.lock().await;
+        append_snapshot(&mut output, snapshot).await
+    }
+}
+
+/// Append a snapshot as one JSON line
+async fn append_snapshot(output: &mut File, snapshot: &PoolSnapshot) -> Result<()> {
+    let mut line = serde_json::to_string(snapshot)?;
+    line.push('\n');
+
+    output.write_all(line.as_bytes()).await?;
+    output.flush().await?;
+    Ok(())
+}
+
+/// Whether fetched pool account data differs from the last snapshot written
+///
+/// Account and log notifications for the same swap arrive separately; only the first is kept.
+fn is_new_state(last_data: &Option<Vec<u8>>, data: &[u8]) -> bool {
+    last_data.as_deref() != Some(data)
+}
+
+/// Whether a transaction's logs contain a DAMM v2 swap
+fn is_swap(logs: &[String]) -> bool {
+    logs.iter().any(|msg| msg.contains("Instruction: Swap"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backtest::load_snapshots;
+    use crate::meteora::fixtures;
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn recognizes_swap_logs() {
+        assert!(is_swap(&logs(&[
+            "Program cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG invoke [1]",
+            "Program log: Instruction: Swap",
+        ])));
+        assert!(!is_swap(&logs(&["Program log: Instruction: AddLiquidity"])));
+        assert!(!is_swap(&[]));
+    }
+
+    #[test]
+    fn skips_repeated_pool_states() {
+        assert!(is_new_state(&None, &[1, 2, 3]));
+        assert!(!is_new_state(&Some(vec![1, 2, 3]), &[1, 2, 3]));
+        assert!(is_new_state(&Some(vec![1, 2, 3]), &[1, 2, 4]));
+    }
+
+    #[tokio::test]
+    async fn written_snapshots_load_back() {
+        let path = std::env::temp_dir().join(format!("recorder-{}.jsonl", Pubkey::new_unique()));
+        let mut output = File::from_std(OpenOptions::new().create(true).append(true).open(&path).unwrap());
+
+        let pool_info = fixtures::sol_pool(1_000_000_000_000 << 64, 20_000_000_000_000, 1_000_000_000_000);
+        let data = fixtures::pool_account(&pool_info.state);
+        let opening = PoolSnapshot::from_pool_info(&pool_info, &data, 1_000, 50);
+        let mut swap = PoolSnapshot::from_pool_info(&pool_info, &data, 1_010, 75);
+        swap.signature = Some("swap".to_string());
+        append_snapshot(&mut output, &swap).await.unwrap();
+        append_snapshot(&mut output, &opening).await.unwrap();
+
+        let recordings = load_snapshots(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let snapshots = &recordings[&pool_info.address.to_string()];
+        assert_eq!(snapshots.len(), 2);
+        assert_eq!((snapshots[0].slot, snapshots[0].signature.as_deref()), (50, None));
+        assert_eq!((snapshots[1].slot, snapshots[1].signature.as_deref()), (75, Some("swap")));
+
+        let replayed = snapshots[1].pool_info().unwrap();
+        assert_eq!(replayed.token_a_mint, pool_info.token_a_mint);
+        assert_eq!(replayed.token_b_amount, pool_info.token_b_amount);
+        assert_eq!(replayed.sqrt_price, pool_info.sqrt_price);
+        assert_eq!(replayed.liquidity, pool_info.liquidity);
+    }
+}
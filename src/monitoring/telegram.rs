@@ -280,6 +280,15 @@ impl TelegramMonitor {
                                 discovered_at: chrono::Utc::now(),
                                 analyzed: false,
                                 score: None,
+                                score_breakdown: Vec::new(),
+                                safety_checks: Vec::new(),
+                                tvl_lamports: None,
+                                holders: Vec::new(),
+                                creator: None,
+                                fee_bps: None,
+                                activated_at: None,
+                                creator_stats: None,
+                                rugged: None,
                             };
                             
                             // Send the pool to the channel
@@ -107,6 +107,15 @@ impl MeteoraPoolMonitor {
                 discovered_at: Utc::now(),
                 analyzed: false,
                 score: None,
+                score_breakdown: Vec::new(),
+                safety_checks: Vec::new(),
+                tvl_lamports: None,
+                holders: Vec::new(),
+                creator: None,
+                fee_bps: None,
+                activated_at: None,
+                creator_stats: None,
+                rugged: None,
             })
         } else {
             None
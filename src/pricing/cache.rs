@@ -0,0 +1,72 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use log::{debug, warn};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::pricing::PriceSource;
+
+/// Caches another source's prices for `ttl`, falling back to the last price while it is fresher than `max_staleness`
+///
+/// Prices younger than `ttl` are served without asking the inner source. Once they expire the
+/// inner source is asked again; if that fails, the cached price is still served until it is
+/// `max_staleness` old, after which the error is returned instead of a stale price.
+pub struct CachedPriceSource {
+    inner: Arc<dyn PriceSource>,
+    ttl: Duration,
+    max_staleness: Duration,
+    prices: Mutex<HashMap<Pubkey, (f64, Instant)>>,
+}
+
+impl CachedPriceSource {
+    pub fn new(inner: Arc<dyn PriceSource>, ttl: Duration, max_staleness: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            max_staleness: max_staleness.max(ttl),
+            prices: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cached price of a mint and its age, if any
+    fn cached(&self, mint: &Pubkey) -> Option<(f64, Duration)> {
+        let prices = self.prices.lock().unwrap_or_else(|e| e.into_inner());
+        prices.get(mint).map(|(price, fetched_at)| (*price, fetched_at.elapsed()))
+    }
+}
+
+#[async_trait]
+impl PriceSource for CachedPriceSource {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn price_in_sol(&self, mint: &Pubkey) -> Result<f64> {
+        let cached = self.cached(mint);
+        if let Some((price, age)) = cached {
+            if age < self.ttl {
+                return Ok(price);
+            }
+        }
+
+        match self.inner.price_in_sol(mint).await {
+            Ok(price) => {
+                debug!("Priced {} at {} SOL ({})", mint, price, self.inner.name());
+                self.prices.lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(*mint, (price, Instant::now()));
+                Ok(price)
+            },
+            Err(e) => match cached {
+                Some((price, age)) if age < self.max_staleness => {
+                    warn!("Failed to refresh price of {} ({}), using {}s old price: {}",
+                        mint, self.inner.name(), age.as_secs(), e);
+                    Ok(price)
+                },
+                _ => Err(e),
+            },
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use log::debug;
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+
+use crate::pricing::PriceSource;
+
+/// Asks each source in turn and returns the first price found
+pub struct FallbackPriceSource {
+    sources: Vec<Arc<dyn PriceSource>>,
+    name: String,
+}
+
+impl FallbackPriceSource {
+    pub fn new(sources: Vec<Arc<dyn PriceSource>>) -> Self {
+        let name = sources.iter()
+            .map(|source| source.name())
+            .collect::<Vec<_>>()
+            .join(" > ");
+
+        Self { sources, name }
+    }
+}
+
+#[async_trait]
+impl PriceSource for FallbackPriceSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn price_in_sol(&self, mint: &Pubkey) -> Result<f64> {
+        let mut errors = Vec::new();
+
+        for source in &self.sources {
+            match source.price_in_sol(mint).await {
+                Ok(price) => return Ok(price),
+                Err(e) => {
+                    debug!("{} price source failed for {}: {}", source.name(), mint, e);
+                    errors.push(format!("{}: {}", source.name(), e));
+                }
+            }
+        }
+
+        Err(anyhow!("No price source could price {} ({})", mint, errors.join("; ")))
+    }
+}
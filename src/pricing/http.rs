@@ -0,0 +1,224 @@
+use anyhow::{Result, anyhow, Context};
+use async_trait::async_trait;
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::meteora::WSOL_MINT;
+use crate::pricing::PriceSource;
+
+/// Prices tokens through an HTTP API shaped like Jupiter's price API
+///
+/// Requests `GET {base_url}/price?ids={mint}&vsToken={WSOL}` and reads `data.{mint}.price`,
+/// which may be a number or a decimal string.
+pub struct HttpPriceSource {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+/// Response body of the price endpoint
+#[derive(Debug, Deserialize)]
+struct PriceResponse {
+    data: HashMap<String, Option<PriceData>>,
+}
+
+/// Price entry for one token; unknown tokens come back as `null`
+#[derive(Debug, Deserialize)]
+struct PriceData {
+    price: PriceValue,
+}
+
+/// Jupiter's v2 API returns prices as strings, older versions as numbers
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PriceValue {
+    Number(f64),
+    Text(String),
+}
+
+impl HttpPriceSource {
+    /// Create a source querying `base_url`, giving up on requests that take longer than `timeout`
+    pub fn new(base_url: &str, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .connect_timeout(timeout)
+            .build()
+            .context("Failed to build HTTP price client")?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl PriceSource for HttpPriceSource {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    async fn price_in_sol(&self, mint: &Pubkey) -> Result<f64> {
+        let mint = mint.to_string();
+        let url = format!("{}/price", self.base_url);
+
+        let response: PriceResponse = self.client.get(&url)
+            .query(&[("ids", mint.as_str()), ("vsToken", WSOL_MINT)])
+            .send()
+            .await
+            .with_context(|| format!("Price request to {} failed", url))?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("Invalid price response from {}", url))?;
+
+        let price = match response.data.get(&mint) {
+            Some(Some(data)) => match &data.price {
+                PriceValue::Number(price) => *price,
+                PriceValue::Text(price) => price.parse()
+                    .with_context(|| format!("Invalid price for {}: {}", mint, price))?,
+            },
+            _ => return Err(anyhow!("{} has no price for {}", url, mint)),
+        };
+
+        if !price.is_finite() || price <= 0.0 {
+            return Err(anyhow!("{} returned an unusable price for {}: {}", url, mint, price));
+        }
+
+        Ok(price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pricing::FallbackPriceSource;
+    use std::sync::{Arc, Mutex};
+    use std::time::Instant;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve `body` as JSON to every request, recording each request line
+    async fn serve(body: String) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buffer).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => request.extend_from_slice(&buffer[..n]),
+                    }
+                }
+                let request_line = String::from_utf8_lossy(&request).lines().next().unwrap_or_default().to_string();
+                recorded.lock().unwrap().push(request_line);
+
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(), body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (url, requests)
+    }
+
+    /// Accept connections but never answer them
+    async fn serve_nothing() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let mut sockets = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                sockets.push(socket);
+            }
+        });
+
+        url
+    }
+
+    fn jupiter_body(mint: &Pubkey, price: &str) -> String {
+        format!(r#"{{"data":{{"{mint}":{{"id":"{mint}","type":"derivedPrice","price":{price}}}}},"timeTaken":0.003}}"#)
+    }
+
+    /// Stands in for the on-chain source, which needs an RPC node
+    struct FixedPriceSource(f64);
+
+    #[async_trait]
+    impl PriceSource for FixedPriceSource {
+        fn name(&self) -> &str {
+            "onchain"
+        }
+
+        async fn price_in_sol(&self, _mint: &Pubkey) -> Result<f64> {
+            Ok(self.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn parses_string_and_number_prices() {
+        let mint = Pubkey::new_unique();
+
+        for price in [r#""0.000123456""#, "0.000123456"] {
+            let (url, requests) = serve(jupiter_body(&mint, price)).await;
+            let source = HttpPriceSource::new(&url, Duration::from_secs(2)).unwrap();
+
+            assert_eq!(source.price_in_sol(&mint).await.unwrap(), 0.000123456);
+
+            let request = requests.lock().unwrap()[0].clone();
+            assert!(request.starts_with(&format!("GET /price?ids={}&vsToken={} ", mint, WSOL_MINT)), "{}", request);
+        }
+    }
+
+    #[tokio::test]
+    async fn times_out_within_the_configured_timeout() {
+        let url = serve_nothing().await;
+        let source = HttpPriceSource::new(&url, Duration::from_millis(200)).unwrap();
+
+        let started = Instant::now();
+        assert!(source.price_in_sol(&Pubkey::new_unique()).await.is_err());
+        assert!(started.elapsed() < Duration::from_millis(1_000), "took {:?}", started.elapsed());
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_mints_and_malformed_bodies() {
+        let mint = Pubkey::new_unique();
+        let bodies = [
+            jupiter_body(&Pubkey::new_unique(), r#""1.5""#),
+            format!(r#"{{"data":{{"{}":null}}}}"#, mint),
+            jupiter_body(&mint, r#""not a number""#),
+            jupiter_body(&mint, "0"),
+            "{\"data\":".to_string(),
+            "<html>rate limited</html>".to_string(),
+        ];
+
+        for body in bodies {
+            let (url, _) = serve(body.clone()).await;
+            let source = HttpPriceSource::new(&url, Duration::from_secs(2)).unwrap();
+            assert!(source.price_in_sol(&mint).await.is_err(), "accepted {}", body);
+        }
+    }
+
+    #[tokio::test]
+    async fn fallback_moves_on_to_onchain_when_http_fails() {
+        let mint = Pubkey::new_unique();
+        let (url, _) = serve(format!(r#"{{"data":{{"{}":null}}}}"#, mint)).await;
+
+        let sources: Vec<Arc<dyn PriceSource>> = vec![
+            Arc::new(HttpPriceSource::new(&url, Duration::from_secs(2)).unwrap()),
+            Arc::new(FixedPriceSource(0.042)),
+        ];
+        let fallback = FallbackPriceSource::new(sources);
+
+        assert_eq!(fallback.name(), "http > onchain");
+        assert_eq!(fallback.price_in_sol(&mint).await.unwrap(), 0.042);
+    }
+}
@@ -0,0 +1,50 @@
+mod source;
+mod onchain;
+mod http;
+mod fallback;
+mod cache;
+
+pub use source::PriceSource;
+pub use onchain::OnChainPriceSource;
+pub use onchain::pool_price;
+pub use http::HttpPriceSource;
+pub use fallback::FallbackPriceSource;
+pub use cache::CachedPriceSource;
+
+use anyhow::{Result, anyhow};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::solana::SolanaClient;
+
+/// Build the price source described by the configuration
+///
+/// Providers listed in `price_providers` are tried in order and the result is cached.
+pub fn price_source_from_config(config: &Config, client: SolanaClient) -> Result<Arc<dyn PriceSource>> {
+    let mut sources: Vec<Arc<dyn PriceSource>> = Vec::new();
+
+    for provider in &config.price_providers {
+        let source: Arc<dyn PriceSource> = match provider.as_str() {
+            "onchain" => Arc::new(OnChainPriceSource::new(client.clone())),
+            "http" => Arc::new(HttpPriceSource::new(
+                &config.price_api_url,
+                Duration::from_millis(config.price_api_timeout_ms),
+            )?),
+            other => return Err(anyhow!("Unknown price provider: {} (expected onchain or http)", other)),
+        };
+        sources.push(source);
+    }
+
+    let source: Arc<dyn PriceSource> = match sources.len() {
+        0 => return Err(anyhow!("At least one price provider must be configured")),
+        1 => sources.remove(0),
+        _ => Arc::new(FallbackPriceSource::new(sources)),
+    };
+
+    Ok(Arc::new(CachedPriceSource::new(
+        source,
+        Duration::from_secs(config.price_cache_ttl_seconds),
+        Duration::from_secs(config.price_max_staleness_seconds),
+    )))
+}
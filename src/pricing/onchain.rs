@@ -0,0 +1,255 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use log::{debug, warn};
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::meteora::{math, instructions, PoolState, USDC_MINT, USDT_MINT};
+use crate::meteora::state::{POOL_ACCOUNT_SIZE, POOL_TOKEN_A_MINT_OFFSET, POOL_TOKEN_B_MINT_OFFSET};
+use crate::pricing::PriceSource;
+use crate::solana::SolanaClient;
+use crate::solana::rpc_helpers;
+
+// How long a routing pool is used before scanning again for a deeper one
+const ROUTE_TTL: Duration = Duration::from_secs(600);
+
+/// Prices tokens from the sqrt price of DAMM v2 pools
+///
+/// A token is priced from its deepest pool against SOL. Tokens only paired with USDC or USDT
+/// are priced in that stablecoin first, which is then converted through the deepest
+/// stablecoin/SOL pool.
+pub struct OnChainPriceSource {
+    client: SolanaClient,
+    program_id: Pubkey,
+    /// Reference quotes tried, in order, when a token has no SOL pool
+    stable_quotes: Vec<Pubkey>,
+    /// Deepest pool found for each (token, quote) pair, so pairs are only rescanned when it goes stale
+    pools: Mutex<HashMap<(Pubkey, Pubkey), Route>>,
+    decimals: Mutex<HashMap<Pubkey, u8>>,
+}
+
+/// The pool a (token, quote) pair is priced from, with how deep it was when chosen
+#[derive(Debug, Clone, Copy)]
+struct Route {
+    pool: Pubkey,
+    /// Raw quote amount backing the pool's liquidity when it was found
+    depth: u64,
+    found_at: Instant,
+}
+
+impl Route {
+    /// Whether to look for a deeper pool: the route expired or lost over half its quote depth
+    fn is_stale(&self, state: &PoolState, quote: &Pubkey, now: Instant) -> bool {
+        now.duration_since(self.found_at) >= ROUTE_TTL
+            || quote_depth(state, quote).unwrap_or(0) < self.depth / 2
+    }
+}
+
+impl OnChainPriceSource {
+    pub fn new(client: SolanaClient) -> Self {
+        let stable_quotes = [USDC_MINT, USDT_MINT].iter()
+            .filter_map(|mint| Pubkey::from_str(mint).ok())
+            .collect();
+
+        Self {
+            client,
+            program_id: instructions::program_id(),
+            stable_quotes,
+            pools: Mutex::new(HashMap::new()),
+            decimals: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Price of one whole `mint` token in whole `quote` tokens, if they share a usable pool
+    async fn price_in_quote(&self, mint: &Pubkey, quote: &Pubkey) -> Result<Option<f64>> {
+        let key = (*mint, *quote);
+        let cached = self.pools.lock().unwrap_or_else(|e| e.into_inner()).get(&key).copied();
+
+        let current = cached.and_then(|route| {
+            match self.client.get_account(&route.pool).map(|a| PoolState::from_account_data(&a.data)) {
+                Ok(Ok(state)) if is_usable(&state) && !route.is_stale(&state, quote, Instant::now()) => Some(state),
+                // The pool was drained or disabled, or it is time to check for a deeper one
+                _ => {
+                    debug!("Routing pool {} for {} in {} is stale, rescanning", route.pool, mint, quote);
+                    None
+                }
+            }
+        });
+
+        let state = match (cached, current) {
+            (Some(_), Some(state)) => state,
+            _ => match self.find_deepest_pool(mint, quote)? {
+                Some((route, state)) => {
+                    self.pools.lock().unwrap_or_else(|e| e.into_inner()).insert(key, route);
+                    state
+                },
+                None => {
+                    self.pools.lock().unwrap_or_else(|e| e.into_inner()).remove(&key);
+                    return Ok(None);
+                }
+            },
+        };
+
+        let mint_decimals = self.decimals(mint).await?;
+        let quote_decimals = self.decimals(quote).await?;
+        Ok(pool_price(&state, mint, mint_decimals, quote_decimals))
+    }
+
+    /// Scan the program for pools pairing `mint` with `quote` in either order and keep the deepest
+    fn find_deepest_pool(&self, mint: &Pubkey, quote: &Pubkey) -> Result<Option<(Route, PoolState)>> {
+        let mut deepest: Option<(Pubkey, PoolState, u64)> = None;
+
+        for (token_a, token_b) in [(mint, quote), (quote, mint)] {
+            for (address, account) in self.pools_for_pair(token_a, token_b)? {
+                let state = match PoolState::from_account_data(&account.data) {
+                    Ok(state) if is_usable(&state) => state,
+                    Ok(_) => continue,
+                    Err(e) => {
+                        warn!("Failed to decode pool {}: {}", address, e);
+                        continue;
+                    }
+                };
+
+                let depth = quote_depth(&state, quote).unwrap_or(0);
+                if deepest.as_ref().is_none_or(|(_, _, best)| depth > *best) {
+                    deepest = Some((address, state, depth));
+                }
+            }
+        }
+
+        if let Some((address, _, depth)) = &deepest {
+            debug!("Using pool {} ({} raw {} deep) to price {}", address, depth, quote, mint);
+        }
+
+        Ok(deepest.map(|(pool, state, depth)| (Route { pool, depth, found_at: Instant::now() }, state)))
+    }
+
+    /// Every DAMM v2 pool with exactly this token A and token B
+    fn pools_for_pair(&self, token_a: &Pubkey, token_b: &Pubkey) -> Result<Vec<(Pubkey, solana_sdk::account::Account)>> {
+        let memcmp = |offset: usize, mint: &Pubkey| RpcFilterType::Memcmp(Memcmp::new(
+            offset,
+            MemcmpEncodedBytes::Base58(mint.to_string()),
+        ));
+
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::DataSize(POOL_ACCOUNT_SIZE as u64),
+                memcmp(POOL_TOKEN_A_MINT_OFFSET, token_a),
+                memcmp(POOL_TOKEN_B_MINT_OFFSET, token_b),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..RpcAccountInfoConfig::default()
+            },
+            with_context: None,
+        };
+
+        self.client.get_program_accounts_with_config(&self.program_id, config)
+    }
+
+    /// Decimals of a mint, fetched once
+    async fn decimals(&self, mint: &Pubkey) -> Result<u8> {
+        if let Some(decimals) = self.decimals.lock().unwrap_or_else(|e| e.into_inner()).get(mint) {
+            return Ok(*decimals);
+        }
+
+        let decimals = rpc_helpers::get_token_decimals(&self.client, mint).await?;
+        self.decimals.lock().unwrap_or_else(|e| e.into_inner()).insert(*mint, decimals);
+        Ok(decimals)
+    }
+}
+
+#[async_trait]
+impl PriceSource for OnChainPriceSource {
+    fn name(&self) -> &str {
+        "onchain"
+    }
+
+    async fn price_in_sol(&self, mint: &Pubkey) -> Result<f64> {
+        let wsol = instructions::wsol_mint();
+        if *mint == wsol {
+            return Ok(1.0);
+        }
+
+        if let Some(price) = self.price_in_quote(mint, &wsol).await? {
+            return Ok(price);
+        }
+
+        for quote in self.stable_quotes.iter().filter(|quote| *quote != mint) {
+            if let Some(price) = self.price_in_quote(mint, quote).await? {
+                let quote_price = self.price_in_quote(quote, &wsol).await?
+                    .ok_or_else(|| anyhow!("No DAMM v2 pool prices {} in SOL", quote))?;
+                return Ok(price * quote_price);
+            }
+        }
+
+        Err(anyhow!("No DAMM v2 pool prices {} against SOL, USDC or USDT", mint))
+    }
+}
+
+/// Price of one whole `mint` token in whole tokens of the pool's other side, from the sqrt price
+///
+/// Returns `None` if `mint` is not in the pool or the pool has no price.
+pub fn pool_price(state: &PoolState, mint: &Pubkey, mint_decimals: u8, other_decimals: u8) -> Option<f64> {
+    // Raw token B per raw token A
+    let raw_price = math::sqrt_price_to_price(state.sqrt_price);
+    if raw_price <= 0.0 || !raw_price.is_finite() {
+        return None;
+    }
+
+    let scale = 10f64.powi(mint_decimals as i32 - other_decimals as i32);
+
+    if *mint == state.token_a_mint {
+        Some(raw_price * scale)
+    } else if *mint == state.token_b_mint {
+        Some(scale / raw_price)
+    } else {
+        None
+    }
+}
+
+/// Whether a pool can be traded against and so has a meaningful price
+fn is_usable(state: &PoolState) -> bool {
+    state.is_enabled() && state.liquidity > 0
+}
+
+/// Raw amount of `quote` backing the pool's liquidity at its current price
+fn quote_depth(state: &PoolState, quote: &Pubkey) -> Option<u64> {
+    if *quote == state.token_a_mint {
+        math::get_delta_amount_a(state.sqrt_price, state.sqrt_max_price, state.liquidity, false)
+    } else {
+        math::get_delta_amount_b(state.sqrt_min_price, state.sqrt_price, state.liquidity, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::meteora::fixtures;
+
+    /// A SOL pool at a price of 1
+    fn sol_pool(liquidity: u128) -> PoolState {
+        fixtures::sol_pool_state(1 << 64, liquidity)
+    }
+
+    #[test]
+    fn route_goes_stale_when_drained_or_expired() {
+        let quote = instructions::wsol_mint();
+        let state = sol_pool(1_000_000_000_000 << 64);
+        let depth = quote_depth(&state, &quote).unwrap();
+        let now = Instant::now();
+        let route = Route { pool: Pubkey::new_unique(), depth, found_at: now };
+
+        assert!(!route.is_stale(&state, &quote, now));
+        assert!(!route.is_stale(&sol_pool(600_000_000_000 << 64), &quote, now));
+        assert!(route.is_stale(&sol_pool(400_000_000_000 << 64), &quote, now));
+        assert!(route.is_stale(&state, &quote, now + ROUTE_TTL));
+    }
+}
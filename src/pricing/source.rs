@@ -0,0 +1,13 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use solana_sdk::pubkey::Pubkey;
+
+/// Something that can price a token in SOL
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// Short name identifying the source in logs
+    fn name(&self) -> &str;
+
+    /// Price of one whole token (decimal adjusted) in SOL
+    async fn price_in_sol(&self, mint: &Pubkey) -> Result<f64>;
+}
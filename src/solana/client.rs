@@ -2,11 +2,14 @@ use anyhow::{Result, Context, anyhow};
 use log::{debug, info, warn, error};
 use solana_client::rpc_client::RpcClient;
 use solana_client::client_error::ClientError;
+use solana_client::rpc_config::RpcProgramAccountsConfig;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::{Response, RpcKeyedAccount};
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::transaction::Transaction;
 use solana_sdk::account::Account;
-use solana_sdk::clock::Slot;
+use solana_sdk::clock::{Epoch, Slot};
 use solana_sdk::hash::Hash;
 use std::time::{Duration, Instant};
 use std::str::FromStr;
@@ -84,6 +87,14 @@ impl SolanaClient {
         })
     }
     
+    /// Get the current epoch with retries
+    pub fn get_epoch(&self) -> Result<Epoch> {
+        self.with_retry(|| {
+            debug!("Getting current epoch");
+            self.rpc_client.get_epoch_info()
+        }).map(|info| info.epoch)
+    }
+    
     /// Get the recent blockhash with retries
     pub fn get_latest_blockhash(&self) -> Result<Hash> {
         self.with_retry(|| {
@@ -132,6 +143,51 @@ impl SolanaClient {
         })
     }
     
+    /// Get the largest token accounts of a mint (up to 20) as (account, raw amount), with retries
+    pub fn get_token_largest_accounts(&self, mint: &Pubkey) -> Result<Vec<(Pubkey, u64)>> {
+        let balances = self.with_retry(|| {
+            debug!("Getting largest token accounts for mint: {}", mint);
+            self.rpc_client.get_token_largest_accounts(mint)
+        })?;
+        
+        balances.into_iter()
+            .map(|balance| {
+                let account = Pubkey::from_str(&balance.address)
+                    .with_context(|| format!("Invalid token account address: {}", balance.address))?;
+                let amount = balance.amount.amount.parse::<u64>()
+                    .with_context(|| format!("Invalid token amount for {}", balance.address))?;
+                Ok((account, amount))
+            })
+            .collect()
+    }
+    
+    /// Get the token accounts of an owner under a token program, with retries
+    ///
+    /// Accounts are fetched base64 encoded so their raw data can be read like any other account.
+    pub fn get_token_accounts_by_owner(&self, owner: &Pubkey, token_program: &Pubkey) -> Result<Vec<(Pubkey, Account)>> {
+        let keyed_accounts = self.with_retry(|| {
+            debug!("Getting token accounts of {} under {}", owner, token_program);
+            self.rpc_client.send::<Response<Vec<RpcKeyedAccount>>>(
+                RpcRequest::GetTokenAccountsByOwner,
+                serde_json::json!([
+                    owner.to_string(),
+                    { "programId": token_program.to_string() },
+                    { "encoding": "base64", "commitment": self.rpc_client.commitment().commitment },
+                ]),
+            )
+        })?;
+        
+        keyed_accounts.value.into_iter()
+            .map(|keyed| {
+                let address = Pubkey::from_str(&keyed.pubkey)
+                    .with_context(|| format!("Invalid token account address: {}", keyed.pubkey))?;
+                let account = keyed.account.decode::<Account>()
+                    .ok_or_else(|| anyhow!("Failed to decode token account {}", keyed.pubkey))?;
+                Ok((address, account))
+            })
+            .collect()
+    }
+    
     /// Send and confirm transaction with retries
     pub fn send_and_confirm_transaction(&self, transaction: &Transaction) -> Result<String> {
         self.with_retry(|| {
@@ -149,6 +205,18 @@ impl SolanaClient {
         })
     }
     
+    /// Get Solana program accounts matching a filter config with retries
+    pub fn get_program_accounts_with_config(
+        &self,
+        program_id: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> Result<Vec<(Pubkey, Account)>> {
+        self.with_retry(|| {
+            debug!("Getting filtered program accounts for: {}", program_id);
+            self.rpc_client.get_program_accounts_with_config(program_id, config.clone())
+        })
+    }
+    
     /// Get a transaction by signature
     pub fn get_transaction(&self, signature: &str) -> Result<solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta> {
         let signature_obj = match solana_sdk::signature::Signature::from_str(signature) {
@@ -20,9 +20,29 @@ use crate::models::pool::TokenInfo;
 // SPL Token Program ID
 pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 
+// SPL Token-2022 Program ID
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+// Associated Token Account Program ID
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+
 // Token Metadata Program ID - Metaplex
 pub const TOKEN_METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
 
+// Token-2022 extension types that change how a token can be transferred or taken
+pub const EXTENSION_TRANSFER_FEE_CONFIG: u16 = 1;
+pub const EXTENSION_PERMANENT_DELEGATE: u16 = 12;
+pub const EXTENSION_TRANSFER_HOOK: u16 = 14;
+
+// Size of the base SPL mint layout, which Token-2022 mints share
+const MINT_SIZE: usize = 82;
+
+// Offset of the raw amount in a token account, after the mint and owner
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+
+// Token-2022 mints are padded to the token account size, followed by an account type byte and the extensions
+const TOKEN_2022_EXTENSIONS_OFFSET: usize = 166;
+
 /// Find program accounts with specific offset and data
 pub async fn find_program_accounts_by_data(
     client: &SolanaClient,
@@ -35,7 +55,7 @@ pub async fn find_program_accounts_by_data(
         MemcmpEncodedBytes::Base58(bs58::encode(&data).into_string()),
     );
     
-    let _config = RpcProgramAccountsConfig {
+    let config = RpcProgramAccountsConfig {
         filters: Some(vec![RpcFilterType::Memcmp(memcmp)]),
         account_config: RpcAccountInfoConfig {
             encoding: None,
@@ -46,7 +66,7 @@ pub async fn find_program_accounts_by_data(
         with_context: None,
     };
     
-    let accounts = client.get_program_accounts(program_id)?;
+    let accounts = client.get_program_accounts_with_config(program_id, config)?;
     debug!("Found {} accounts for program {}", accounts.len(), program_id);
     
     Ok(accounts)
@@ -112,6 +132,82 @@ pub struct TokenAccountInfo {
     pub decimals: u8,
 }
 
+/// SPL Token or Token-2022 mint account
+#[derive(Debug, Clone)]
+pub struct MintInfo {
+    pub mint_authority: Option<Pubkey>,
+    pub supply: u64,
+    pub decimals: u8,
+    pub freeze_authority: Option<Pubkey>,
+    pub is_token_2022: bool,
+    /// Token-2022 extensions as (extension type, data) pairs
+    pub extensions: Vec<(u16, Vec<u8>)>,
+}
+
+impl MintInfo {
+    /// Decode a mint from raw account data
+    pub fn parse(data: &[u8], is_token_2022: bool) -> Result<Self> {
+        if data.len() < MINT_SIZE {
+            return Err(anyhow!("Account data too short to be a mint"));
+        }
+        
+        let supply = u64::from_le_bytes(data[36..44].try_into()?);
+        
+        let mut extensions = Vec::new();
+        if is_token_2022 && data.len() > TOKEN_2022_EXTENSIONS_OFFSET {
+            // TLV entries: u16 type, u16 length, then the value
+            let mut offset = TOKEN_2022_EXTENSIONS_OFFSET;
+            while offset + 4 <= data.len() {
+                let extension_type = u16::from_le_bytes([data[offset], data[offset + 1]]);
+                let length = u16::from_le_bytes([data[offset + 2], data[offset + 3]]) as usize;
+                let start = offset + 4;
+                
+                if extension_type == 0 || start + length > data.len() {
+                    break;
+                }
+                
+                extensions.push((extension_type, data[start..start + length].to_vec()));
+                offset = start + length;
+            }
+        }
+        
+        Ok(Self {
+            mint_authority: parse_coption_pubkey(&data[0..36]),
+            supply,
+            decimals: data[44],
+            freeze_authority: parse_coption_pubkey(&data[46..82]),
+            is_token_2022,
+            extensions,
+        })
+    }
+    
+    /// Data of a Token-2022 extension, if the mint has it
+    pub fn extension(&self, extension_type: u16) -> Option<&[u8]> {
+        self.extensions.iter()
+            .find(|(t, _)| *t == extension_type)
+            .map(|(_, data)| data.as_slice())
+    }
+}
+
+/// Decode a `COption<Pubkey>` (u32 tag followed by the key)
+fn parse_coption_pubkey(data: &[u8]) -> Option<Pubkey> {
+    let tag = u32::from_le_bytes(data[0..4].try_into().ok()?);
+    if tag == 0 {
+        return None;
+    }
+    
+    let key: [u8; 32] = data[4..36].try_into().ok()?;
+    Some(Pubkey::new_from_array(key))
+}
+
+/// Raw amount held by a token account
+///
+/// SPL Token and Token-2022 accounts share the base layout, so this reads either.
+pub fn token_account_amount(data: &[u8]) -> Option<u64> {
+    let bytes = data.get(TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
 /// Simple token metadata structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenMetadata {
@@ -168,6 +264,22 @@ pub async fn get_token_decimals(client: &SolanaClient, mint: &Pubkey) -> Result<
     Ok(mint_account.data[44])
 }
 
+/// Fetch and decode a mint account, SPL Token or Token-2022
+pub async fn get_mint_info(client: &SolanaClient, mint: &Pubkey) -> Result<MintInfo> {
+    let account = client.get_account(mint)?;
+    
+    let is_token_2022 = if account.owner == parse_pubkey(TOKEN_2022_PROGRAM_ID)? {
+        true
+    } else if account.owner == parse_pubkey(TOKEN_PROGRAM_ID)? {
+        false
+    } else {
+        return Err(anyhow!("Account {} is not a token mint (owner {})", mint, account.owner));
+    };
+    
+    MintInfo::parse(&account.data, is_token_2022)
+        .with_context(|| format!("Failed to decode mint {}", mint))
+}
+
 /// Fetch token metadata from Metaplex
 pub async fn get_token_metadata(client: &SolanaClient, mint: &Pubkey) -> Result<TokenMetadata> {
     let token_metadata_program_id = parse_pubkey(TOKEN_METADATA_PROGRAM_ID)?;
@@ -248,4 +360,99 @@ pub async fn fetch_token_info(client: &SolanaClient, mint: &Pubkey) -> Result<To
         symbol: Some(metadata.symbol),
         decimals,
     })
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mint account data with the given authorities and raw Token-2022 TLV bytes after the account type
+    fn mint_data(mint_authority: Option<Pubkey>, freeze_authority: Option<Pubkey>, tlv: &[u8]) -> Vec<u8> {
+        let mut data = vec![0u8; MINT_SIZE];
+        if let Some(authority) = mint_authority {
+            data[0..4].copy_from_slice(&1u32.to_le_bytes());
+            data[4..36].copy_from_slice(authority.as_ref());
+        }
+        data[36..44].copy_from_slice(&1_000_000u64.to_le_bytes());
+        data[44] = 6;
+        data[45] = 1;
+        if let Some(authority) = freeze_authority {
+            data[46..50].copy_from_slice(&1u32.to_le_bytes());
+            data[50..82].copy_from_slice(authority.as_ref());
+        }
+        if !tlv.is_empty() {
+            data.resize(TOKEN_2022_EXTENSIONS_OFFSET, 0);
+            data[TOKEN_2022_EXTENSIONS_OFFSET - 1] = 1;
+            data.extend_from_slice(tlv);
+        }
+        data
+    }
+
+    fn tlv_entry(extension_type: u16, value: &[u8]) -> Vec<u8> {
+        let mut entry = extension_type.to_le_bytes().to_vec();
+        entry.extend_from_slice(&(value.len() as u16).to_le_bytes());
+        entry.extend_from_slice(value);
+        entry
+    }
+
+    #[test]
+    fn parses_mint_authorities() {
+        let authority = Pubkey::new_unique();
+        let mint = MintInfo::parse(&mint_data(Some(authority), None, &[]), false).unwrap();
+        assert_eq!(mint.mint_authority, Some(authority));
+        assert_eq!(mint.freeze_authority, None);
+        assert_eq!(mint.supply, 1_000_000);
+        assert_eq!(mint.decimals, 6);
+
+        let mint = MintInfo::parse(&mint_data(None, Some(authority), &[]), false).unwrap();
+        assert_eq!(mint.mint_authority, None);
+        assert_eq!(mint.freeze_authority, Some(authority));
+
+        assert!(MintInfo::parse(&[0u8; MINT_SIZE - 1], false).is_err());
+    }
+
+    #[test]
+    fn walks_token_2022_extensions() {
+        let mut tlv = tlv_entry(EXTENSION_TRANSFER_HOOK, &[7u8; 64]);
+        tlv.extend(tlv_entry(EXTENSION_PERMANENT_DELEGATE, &[9u8; 32]));
+        let data = mint_data(None, None, &tlv);
+
+        let mint = MintInfo::parse(&data, true).unwrap();
+        assert_eq!(mint.extensions.len(), 2);
+        assert_eq!(mint.extension(EXTENSION_TRANSFER_HOOK), Some(&[7u8; 64][..]));
+        assert_eq!(mint.extension(EXTENSION_PERMANENT_DELEGATE), Some(&[9u8; 32][..]));
+        assert_eq!(mint.extension(EXTENSION_TRANSFER_FEE_CONFIG), None);
+
+        // SPL Token mints never carry extensions
+        assert!(MintInfo::parse(&data, false).unwrap().extensions.is_empty());
+    }
+
+    #[test]
+    fn stops_at_zero_type_and_truncated_extensions() {
+        let mut tlv = tlv_entry(EXTENSION_TRANSFER_HOOK, &[7u8; 64]);
+        tlv.extend(tlv_entry(0, &[0u8; 4]));
+        tlv.extend(tlv_entry(EXTENSION_PERMANENT_DELEGATE, &[9u8; 32]));
+        let mint = MintInfo::parse(&mint_data(None, None, &tlv), true).unwrap();
+        assert_eq!(mint.extensions.len(), 1);
+
+        let mut tlv = tlv_entry(EXTENSION_TRANSFER_HOOK, &[7u8; 64]);
+        let mut truncated = tlv_entry(EXTENSION_PERMANENT_DELEGATE, &[9u8; 32]);
+        truncated.truncate(20);
+        tlv.extend(truncated);
+        let mint = MintInfo::parse(&mint_data(None, None, &tlv), true).unwrap();
+        assert_eq!(mint.extensions.len(), 1);
+        assert_eq!(mint.extension(EXTENSION_PERMANENT_DELEGATE), None);
+
+        // A partial header after the last entry is ignored
+        let mut tlv = tlv_entry(EXTENSION_TRANSFER_HOOK, &[7u8; 64]);
+        tlv.extend([12, 0]);
+        assert_eq!(MintInfo::parse(&mint_data(None, None, &tlv), true).unwrap().extensions.len(), 1);
+    }
+
+    #[test]
+    fn reads_token_account_amount() {
+        let mut data = vec![0u8; 165];
+        data[64..72].copy_from_slice(&5_000_000_000u64.to_le_bytes());
+        assert_eq!(token_account_amount(&data), Some(5_000_000_000));
+        assert_eq!(token_account_amount(&data[..71]), None);
+    }
+}
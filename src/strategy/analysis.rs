@@ -1,22 +1,62 @@
-use anyhow::Result;
-use log::{info, debug};
+use anyhow::{Result, anyhow};
+use chrono::{Duration, Utc};
+use log::{info, debug, warn};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::str::FromStr;
+use crate::config::PoolFilters;
 use crate::models::Pool;
+use crate::models::pool::QUOTE_MINTS_BY_PRIORITY;
 use crate::solana::SolanaClient;
 use crate::meteora::MeteoraClient;
-use crate::meteora::PoolInfo;
+use crate::meteora::state::BASIS_POINT_MAX;
+use crate::utils::{sol_to_lamports, lamports_to_sol};
 use crate::solana::rpc_helpers;
+use crate::strategy::safety;
+use crate::strategy::holders;
+use crate::strategy::scoring::{ScoringContext, ScoringPipeline};
+use crate::strategy::reputation::CreatorReputation;
+use crate::pricing::PriceSource;
+use std::sync::Arc;
 
 /// Analyzes and scores pools for potential profitability
 pub struct PoolAnalyzer {
     client: SolanaClient,
     meteora_client: MeteoraClient,
+    scoring: ScoringPipeline,
+    reputation: Option<CreatorReputation>,
+    max_transfer_fee_bps: u16,
 }
 
 impl PoolAnalyzer {
     /// Create a new pool analyzer
     pub fn new(client: SolanaClient) -> Self {
         let meteora_client = MeteoraClient::new(client.clone());
-        Self { client, meteora_client }
+        Self { client, meteora_client, scoring: ScoringPipeline::default(), reputation: None, max_transfer_fee_bps: 0 }
+    }
+    
+    /// Let pool tokens charge a Token-2022 transfer fee of up to `bps` without failing the safety checks
+    pub fn with_max_transfer_fee_bps(mut self, bps: u16) -> Self {
+        self.max_transfer_fee_bps = bps;
+        self
+    }
+    
+    /// Look up each pool creator's track record when scoring and filtering pools
+    pub fn with_reputation(mut self, reputation: CreatorReputation) -> Self {
+        self.reputation = Some(reputation);
+        self
+    }
+    
+    /// Score pools with the given pipeline instead of the default weights
+    pub fn with_scoring(mut self, scoring: ScoringPipeline) -> Self {
+        self.scoring = scoring;
+        self
+    }
+    
+    /// Value pools with the given price source instead of the default on-chain one
+    pub fn with_price_source(mut self, price_source: Arc<dyn PriceSource>) -> Self {
+        self.meteora_client = self.meteora_client.with_price_source(price_source);
+        self
     }
     
     /// Analyze a pool and calculate a score
@@ -29,27 +69,52 @@ impl PoolAnalyzer {
         // Get pool info from Meteora
         let pool_info = self.meteora_client.get_pool_info(&pool.address).await?;
         
-        // Calculate liquidity factors
-        let liquidity_score = self.analyze_liquidity(&pool_info).await?;
+        // Check the tokens for rug vectors; failures are stored with the score and enforced by `meets_criteria`
+        pool.safety_checks = safety::check_pool_tokens(&self.client, &pool_info, self.max_transfer_fee_bps).await?;
+        for check in pool.safety_checks.iter().filter(|c| !c.passed) {
+            warn!("Pool {} failed safety check {} for {}: {}", pool.address, check.name, check.mint, check.detail);
+        }
+        
+        // Record how the tokens are spread across holders
+        pool.holders = holders::analyze_pool_holders(&self.client, &pool_info).await?;
         
-        // Calculate potential yield
-        let yield_score = self.calculate_yield_potential(&pool_info).await?;
+        // Get total value locked in SOL
+        let tvl = self.meteora_client.get_pool_tvl(&pool_info).await?;
+        pool.tvl_lamports = Some(sol_to_lamports(tvl));
         
-        // Calculate final score (0-1 range)
-        // We weight the factors based on importance:
-        // - Liquidity is important for execution (50%)
-        // - Yield potential directly impacts profit (50%)
-        let score = 0.5 * liquidity_score + 0.5 * yield_score;
+        // Record what the filter rules look at
+        pool.creator = Some(pool_info.creator);
+        pool.fee_bps = Some(pool_info.fee_rate);
+        pool.activated_at = Some(Utc::now() - Duration::milliseconds((pool_info.age_seconds() * 1000.0) as i64));
         
-        // Ensure score is between 0 and 1
-        let clamped_score = score.max(0.0).min(1.0);
+        // Look up how the creator's earlier pools turned out
+        pool.creator_stats = match &self.reputation {
+            Some(reputation) => Some(reputation.stats(&pool_info.creator, &pool.address).await?),
+            None => None,
+        };
+        
+        let context = ScoringContext {
+            pool_info: &pool_info,
+            tvl,
+            pool: Some(pool),
+            creator_stats: pool.creator_stats.as_ref(),
+        };
+        let (score, breakdown) = self.scoring.score(&context);
+        
+        for factor in &breakdown {
+            debug!("Pool {} {} score {:.2} (weight {:.2})", pool.address, factor.name, factor.score, factor.weight);
+        }
         
         // Store the score in the pool
-        pool.score = Some(clamped_score);
+        pool.score = Some(score);
+        pool.score_breakdown = breakdown;
         pool.analyzed = true;
         
-        info!("Pool {} analyzed with score: {:.2}", pool.address, clamped_score);
-        Ok(clamped_score)
+        match pool.pair() {
+            Some(pair) => info!("Pool {} ({}) analyzed with score: {:.2}", pool.address, pair, score),
+            None => info!("Pool {} analyzed with score: {:.2}", pool.address, score),
+        }
+        Ok(score)
     }
     
     /// Populate token metadata for the pool
@@ -74,97 +139,306 @@ impl PoolAnalyzer {
         Ok(())
     }
     
-    /// Analyze liquidity of the pool
-    async fn analyze_liquidity(&self, pool_info: &PoolInfo) -> Result<f64> {
-        // Get total value locked in SOL
-        let tvl = self.meteora_client.get_pool_tvl(pool_info).await?;
-        
-        // Score based on TVL (0-1)
-        // We want at least 100 SOL for maximum score, with diminishing returns after that
-        // Less than 10 SOL is considered low liquidity
-        let liquidity_score = if tvl <= 10.0 {
-            tvl / 10.0 * 0.5  // Scale up to 0.5 for 0-10 SOL
-        } else if tvl <= 100.0 {
-            0.5 + (tvl - 10.0) / 90.0 * 0.5  // Scale from 0.5 to 1.0 for 10-100 SOL
-        } else {
-            1.0  // Maximum score for >100 SOL
-        };
-        
-        // Check balance between token A and token B
-        // Balanced pools are preferred for providing liquidity
-        let a_fraction = pool_info.token_a_amount as f64 / 
-            (pool_info.token_a_amount as f64 + pool_info.token_b_amount as f64);
-        
-        // Balance score (1.0 = perfectly balanced, 0.0 = all in one token)
-        let balance_score = if a_fraction <= 0.5 {
-            a_fraction * 2.0
-        } else {
-            (1.0 - a_fraction) * 2.0
-        };
-        
-        // Combine liquidity and balance scores
-        // We value liquidity more than perfect balance
-        let combined_score = liquidity_score * 0.7 + balance_score * 0.3;
-        
-        debug!("Liquidity score for pool {}: {}", pool_info.address, combined_score);
-        Ok(combined_score)
-    }
-    
-    /// Calculate potential yield from providing liquidity
-    async fn calculate_yield_potential(&self, pool_info: &PoolInfo) -> Result<f64> {
-        // Calculate fee APY
-        let fee_apy = self.meteora_client.calculate_fee_yield(pool_info).await?;
-        
-        // Score based on APY (0-1)
-        // 100% APY or higher is maximum score
-        // 0-10% is low score
-        let yield_score = if fee_apy <= 0.1 {
-            fee_apy * 10.0 * 0.3  // 0-10% APY maps to 0-0.3 score
-        } else if fee_apy <= 0.5 {
-            0.3 + (fee_apy - 0.1) * (0.6 / 0.4)  // 10-50% APY maps to 0.3-0.9 score
-        } else if fee_apy <= 1.0 {
-            0.9 + (fee_apy - 0.5) * 0.2  // 50-100% APY maps to 0.9-1.0 score
-        } else {
-            1.0  // >100% APY is maximum score
-        };
-        
-        debug!("Yield score for pool {}: {}", pool_info.address, yield_score);
-        Ok(yield_score)
-    }
-    
-    /// Determine if a pool meets the given criteria
+    /// Determine if a pool meets the given criteria, logging the rule that rejected it if not
     pub fn meets_criteria(&self, pool: &Pool, criteria: &PoolCriteria) -> bool {
-        // Check if the pool has been analyzed
-        if !pool.analyzed || pool.score.is_none() {
-            return false;
-        }
-        
-        // Check minimum score
-        let score = pool.score.unwrap();
-        if score < criteria.min_score {
-            return false;
+        match criteria.rejection(pool) {
+            Some(rejection) => {
+                info!("Pool {} rejected by rule {}: {}", pool.address, rejection.rule, rejection.detail);
+                false
+            },
+            None => true,
         }
-        
-        // Additional checks would go here
-        // such as checking min_liquidity and max_token_holders if we had that data
-        
-        true
     }
 }
 
+// Names of the criteria rules, matching the `pool_filters` config keys
+pub const RULE_ANALYZED: &str = "analyzed";
+pub const RULE_MIN_SCORE: &str = "min_score";
+pub const RULE_MIN_TVL: &str = "min_tvl_sol";
+pub const RULE_SAFETY: &str = "safety_checks";
+pub const RULE_MAX_TOKEN_HOLDERS: &str = "max_token_holders";
+pub const RULE_QUOTE_MINTS: &str = "quote_mints";
+pub const RULE_MAX_POOL_AGE: &str = "max_pool_age_seconds";
+pub const RULE_BLACKLISTED_CREATORS: &str = "blacklisted_creators";
+pub const RULE_BLACKLISTED_MINTS: &str = "blacklisted_mints";
+pub const RULE_FEE_TIERS: &str = "fee_tiers_bps";
+pub const RULE_MAX_CREATOR_RUGS: &str = "max_creator_rugs";
+
 /// Criteria for pool selection
+#[derive(Debug, Clone)]
 pub struct PoolCriteria {
     pub min_score: f64,
+    /// Minimum TVL in lamports
     pub min_liquidity: u64,
+    /// Most holders a non-quote token may have, to catch pools early
     pub max_token_holders: Option<u64>,
+    /// Quote tokens a pool may be priced in
+    pub quote_mints: Vec<Pubkey>,
+    pub max_pool_age_seconds: Option<u64>,
+    pub blacklisted_creators: HashSet<Pubkey>,
+    pub blacklisted_mints: HashSet<Pubkey>,
+    /// Base fees a pool must charge, in basis points; empty allows any
+    pub fee_tiers_bps: Vec<u16>,
+    /// Most earlier pools a creator may have rugged
+    pub max_creator_rugs: Option<u64>,
+}
+
+/// The rule a pool failed and why
+#[derive(Debug, Clone)]
+pub struct Rejection {
+    pub rule: &'static str,
+    pub detail: String,
+}
+
+impl PoolCriteria {
+    /// Parse and validate the filter rules from the config
+    pub fn from_filters(filters: &PoolFilters) -> Result<Self> {
+        if !(0.0..=1.0).contains(&filters.min_score) {
+            return Err(anyhow!("pool_filters.min_score must be between 0 and 1, got {}", filters.min_score));
+        }
+        if !filters.min_tvl_sol.is_finite() || filters.min_tvl_sol < 0.0 {
+            return Err(anyhow!("pool_filters.min_tvl_sol must be a non-negative number, got {}", filters.min_tvl_sol));
+        }
+        if let Some(tier) = filters.fee_tiers_bps.iter().find(|bps| **bps > BASIS_POINT_MAX as u16) {
+            return Err(anyhow!("pool_filters.fee_tiers_bps entry {} is above {} bps", tier, BASIS_POINT_MAX));
+        }
+        if filters.max_transfer_fee_bps > BASIS_POINT_MAX as u16 {
+            return Err(anyhow!("pool_filters.max_transfer_fee_bps must be at most {}, got {}",
+                BASIS_POINT_MAX, filters.max_transfer_fee_bps));
+        }
+
+        if filters.quote_mints.is_empty() {
+            return Err(anyhow!("pool_filters.quote_mints must allow at least one quote token"));
+        }
+        if let Some(mint) = filters.quote_mints.iter().find(|mint| !QUOTE_MINTS_BY_PRIORITY.contains(&mint.as_str())) {
+            return Err(anyhow!("pool_filters.quote_mints entry {} is not a supported quote token (expected one of {})",
+                mint, QUOTE_MINTS_BY_PRIORITY.join(", ")));
+        }
+
+        let parse_pubkeys = |field: &str, values: &[String]| -> Result<Vec<Pubkey>> {
+            values.iter()
+                .map(|value| Pubkey::from_str(value)
+                    .map_err(|e| anyhow!("pool_filters.{} has an invalid address {}: {}", field, value, e)))
+                .collect()
+        };
+
+        Ok(Self {
+            min_score: filters.min_score,
+            min_liquidity: sol_to_lamports(filters.min_tvl_sol),
+            max_token_holders: filters.max_token_holders,
+            quote_mints: parse_pubkeys(RULE_QUOTE_MINTS, &filters.quote_mints)?,
+            max_pool_age_seconds: filters.max_pool_age_seconds,
+            blacklisted_creators: parse_pubkeys(RULE_BLACKLISTED_CREATORS, &filters.blacklisted_creators)?.into_iter().collect(),
+            blacklisted_mints: parse_pubkeys(RULE_BLACKLISTED_MINTS, &filters.blacklisted_mints)?.into_iter().collect(),
+            fee_tiers_bps: filters.fee_tiers_bps.clone(),
+            max_creator_rugs: filters.max_creator_rugs,
+        })
+    }
+
+    /// The first rule the pool fails, if any
+    ///
+    /// Data the analysis could not determine does not pass a rule that needs it.
+    pub fn rejection(&self, pool: &Pool) -> Option<Rejection> {
+        let reject = |rule: &'static str, detail: String| Some(Rejection { rule, detail });
+        let mints = [pool.token_a.mint, pool.token_b.mint];
+
+        if let Some(mint) = mints.iter().find(|mint| self.blacklisted_mints.contains(mint)) {
+            return reject(RULE_BLACKLISTED_MINTS, format!("token {} is blacklisted", mint));
+        }
+
+        match pool.pair() {
+            Some(pair) if self.quote_mints.contains(&pair.quote.mint) => {},
+            Some(pair) => return reject(RULE_QUOTE_MINTS, format!("{} is quoted in {}, which is not allowed", pair, pair.quote.mint)),
+            None => return reject(RULE_QUOTE_MINTS, "pool is not paired with a quote token".to_string()),
+        }
+
+        let score = match pool.score {
+            Some(score) if pool.analyzed => score,
+            _ => return reject(RULE_ANALYZED, "pool has not been analyzed".to_string()),
+        };
+
+        match pool.creator {
+            Some(creator) if self.blacklisted_creators.contains(&creator) =>
+                return reject(RULE_BLACKLISTED_CREATORS, format!("creator {} is blacklisted", creator)),
+            None if !self.blacklisted_creators.is_empty() =>
+                return reject(RULE_BLACKLISTED_CREATORS, "creator is unknown".to_string()),
+            _ => {},
+        }
+
+        // Serial ruggers are blacklisted outright; a creator without a record passes
+        if let (Some(max_rugs), Some(stats)) = (self.max_creator_rugs, &pool.creator_stats) {
+            if stats.pools_rugged > max_rugs {
+                return reject(RULE_MAX_CREATOR_RUGS, format!("creator {} rugged {} of {} earlier pools, limit is {}",
+                    stats.creator, stats.pools_rugged, stats.pools_created, max_rugs));
+            }
+        }
+
+        if score < self.min_score {
+            return reject(RULE_MIN_SCORE, format!("score {:.2} is below {:.2}", score, self.min_score));
+        }
+
+        match pool.tvl_lamports {
+            Some(tvl) if tvl >= self.min_liquidity => {},
+            tvl => return reject(RULE_MIN_TVL, format!("TVL {} SOL is below {} SOL",
+                tvl.map_or("unknown".to_string(), |t| lamports_to_sol(t).to_string()), lamports_to_sol(self.min_liquidity))),
+        }
+
+        if let Some(check) = pool.failed_safety_check() {
+            return reject(RULE_SAFETY, format!("{} failed for {}: {}", check.name, check.mint, check.detail));
+        }
+
+        if let Some(max_holders) = self.max_token_holders {
+            for distribution in &pool.holders {
+                match distribution.holder_count {
+                    Some(count) if count <= max_holders => {},
+                    Some(count) => return reject(RULE_MAX_TOKEN_HOLDERS,
+                        format!("token {} has {} holders, limit is {}", distribution.mint, count, max_holders)),
+                    None => return reject(RULE_MAX_TOKEN_HOLDERS,
+                        format!("holder count of token {} is unknown", distribution.mint)),
+                }
+            }
+        }
+
+        if let Some(max_age) = self.max_pool_age_seconds {
+            match pool.activated_at {
+                Some(activated_at) => {
+                    let age = (Utc::now() - activated_at).num_seconds().max(0) as u64;
+                    if age > max_age {
+                        return reject(RULE_MAX_POOL_AGE, format!("pool is {}s old, limit is {}s", age, max_age));
+                    }
+                },
+                None => return reject(RULE_MAX_POOL_AGE, "activation time is unknown".to_string()),
+            }
+        }
+
+        if !self.fee_tiers_bps.is_empty() {
+            match pool.fee_bps {
+                Some(fee) if self.fee_tiers_bps.contains(&fee) => {},
+                fee => return reject(RULE_FEE_TIERS, format!("base fee {} bps is not one of {:?}",
+                    fee.map_or("unknown".to_string(), |f| f.to_string()), self.fee_tiers_bps)),
+            }
+        }
+
+        None
+    }
 }
 
 impl Default for PoolCriteria {
     fn default() -> Self {
-        Self {
+        Self::from_filters(&PoolFilters::default()).expect("default pool filters are valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meteora::{USDC_MINT, WSOL_MINT};
+    use crate::models::{CreatorStats, HolderDistribution, SafetyCheck};
+    use crate::models::pool::TokenInfo;
+
+    const BLACKLISTED_CREATOR: &str = "8qbHbw2BbbTHBW1sbeqakYXVKRQM8Ne7pLK7m6CVfeR";
+    const BLACKLISTED_MINT: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    /// Criteria with every rule switched on
+    fn criteria() -> PoolCriteria {
+        PoolCriteria::from_filters(&PoolFilters {
             min_score: 0.7,
-            min_liquidity: 10_000_000, // 10 SOL in lamports
+            min_tvl_sol: 10.0,
             max_token_holders: Some(100),
+            quote_mints: vec![WSOL_MINT.to_string()],
+            max_pool_age_seconds: Some(3600),
+            blacklisted_creators: vec![BLACKLISTED_CREATOR.to_string()],
+            blacklisted_mints: vec![BLACKLISTED_MINT.to_string()],
+            fee_tiers_bps: vec![25, 100],
+            max_creator_rugs: Some(1),
+            max_transfer_fee_bps: 0,
+        }).unwrap()
+    }
+
+    /// An analyzed SOL pool that passes every rule of `criteria`
+    fn passing_pool() -> Pool {
+        let token = |mint: Pubkey| TokenInfo { mint, name: None, symbol: None, decimals: None };
+        let base = Pubkey::new_unique();
+        let creator = Pubkey::new_unique();
+        Pool {
+            address: Pubkey::new_unique(),
+            token_a: token(base),
+            token_b: token(WSOL_MINT.parse().unwrap()),
+            discovered_at: Utc::now(),
+            analyzed: true,
+            score: Some(0.8),
+            score_breakdown: Vec::new(),
+            safety_checks: vec![SafetyCheck {
+                name: safety::CHECK_MINT_AUTHORITY.to_string(),
+                mint: base,
+                passed: true,
+                detail: String::new(),
+            }],
+            tvl_lamports: Some(sol_to_lamports(20.0)),
+            holders: vec![HolderDistribution { mint: base, holder_count: Some(50), top_holders: 10, top_holders_share: 0.2 }],
+            creator: Some(creator),
+            fee_bps: Some(25),
+            activated_at: Some(Utc::now() - Duration::seconds(60)),
+            creator_stats: Some(CreatorStats { creator, pools_created: 3, pools_checked: 3, pools_rugged: 1, profit_loss_sol: 0.0 }),
+            rugged: None,
+        }
+    }
+
+    fn rejected_by(pool: &Pool) -> Option<&'static str> {
+        criteria().rejection(pool).map(|rejection| rejection.rule)
+    }
+
+    #[test]
+    fn rejects_invalid_filters() {
+        let invalid = [
+            PoolFilters { min_score: 1.5, ..PoolFilters::default() },
+            PoolFilters { min_score: -0.1, ..PoolFilters::default() },
+            PoolFilters { min_tvl_sol: f64::NAN, ..PoolFilters::default() },
+            PoolFilters { quote_mints: Vec::new(), ..PoolFilters::default() },
+            PoolFilters { quote_mints: vec![BLACKLISTED_MINT.to_string()], ..PoolFilters::default() },
+            PoolFilters { blacklisted_creators: vec!["not-a-pubkey".to_string()], ..PoolFilters::default() },
+            PoolFilters { blacklisted_mints: vec!["not-a-pubkey".to_string()], ..PoolFilters::default() },
+            PoolFilters { fee_tiers_bps: vec![10_001], ..PoolFilters::default() },
+            PoolFilters { max_transfer_fee_bps: 10_001, ..PoolFilters::default() },
+        ];
+        for filters in invalid {
+            assert!(PoolCriteria::from_filters(&filters).is_err(), "{:?}", filters);
         }
+
+        let filters = PoolFilters { quote_mints: vec![WSOL_MINT.to_string(), USDC_MINT.to_string()], ..PoolFilters::default() };
+        assert_eq!(PoolCriteria::from_filters(&filters).unwrap().quote_mints.len(), 2);
+    }
+
+    #[test]
+    fn passing_pool_meets_every_rule() {
+        assert_eq!(rejected_by(&passing_pool()), None);
+    }
+
+    #[test]
+    fn reports_the_rule_a_pool_fails() {
+        let fails = |rule: &str, change: &dyn Fn(&mut Pool)| {
+            let mut pool = passing_pool();
+            change(&mut pool);
+            assert_eq!(rejected_by(&pool), Some(rule));
+        };
+
+        fails(RULE_BLACKLISTED_MINTS, &|pool| pool.token_a.mint = BLACKLISTED_MINT.parse().unwrap());
+        fails(RULE_QUOTE_MINTS, &|pool| pool.token_b.mint = USDC_MINT.parse().unwrap());
+        fails(RULE_QUOTE_MINTS, &|pool| pool.token_b.mint = Pubkey::new_unique());
+        fails(RULE_ANALYZED, &|pool| pool.analyzed = false);
+        fails(RULE_ANALYZED, &|pool| pool.score = None);
+        fails(RULE_BLACKLISTED_CREATORS, &|pool| pool.creator = Some(BLACKLISTED_CREATOR.parse().unwrap()));
+        fails(RULE_BLACKLISTED_CREATORS, &|pool| pool.creator = None);
+        fails(RULE_MAX_CREATOR_RUGS, &|pool| pool.creator_stats.as_mut().unwrap().pools_rugged = 2);
+        fails(RULE_MIN_SCORE, &|pool| pool.score = Some(0.69));
+        fails(RULE_MIN_TVL, &|pool| pool.tvl_lamports = Some(sol_to_lamports(9.0)));
+        fails(RULE_MIN_TVL, &|pool| pool.tvl_lamports = None);
+        fails(RULE_SAFETY, &|pool| pool.safety_checks[0].passed = false);
+        fails(RULE_MAX_TOKEN_HOLDERS, &|pool| pool.holders[0].holder_count = Some(101));
+        fails(RULE_MAX_TOKEN_HOLDERS, &|pool| pool.holders[0].holder_count = None);
+        fails(RULE_MAX_POOL_AGE, &|pool| pool.activated_at = Some(Utc::now() - Duration::seconds(3601)));
+        fails(RULE_MAX_POOL_AGE, &|pool| pool.activated_at = None);
+        fails(RULE_FEE_TIERS, &|pool| pool.fee_bps = Some(30));
+        fails(RULE_FEE_TIERS, &|pool| pool.fee_bps = None);
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,210 @@
+use anyhow::{Result, anyhow};
+use tokio::time::{Duration, Instant};
+
+use crate::config::ExitRules;
+use crate::meteora::PoolInfo;
+use crate::models::{ExitReason, Position};
+use crate::strategy::valuation::PositionValuation;
+
+/// Exit rules of a position, checked every time it is revalued
+///
+/// Rules compose: any that is set can end the position, whichever fires first.
+#[derive(Debug, Clone, Default)]
+pub struct ExitPolicy {
+    /// Share of the SOL invested (0-1) the fees must reach to take profit
+    take_profit_fees: Option<f64>,
+    /// Share of the SOL invested (0-1) fees minus impermanent loss may fall below zero
+    stop_loss: Option<f64>,
+    volume_timeout: Option<Duration>,
+    pub max_duration: Option<Duration>,
+}
+
+impl ExitPolicy {
+    /// Build the policy from the config's exit rules, checking each is usable
+    pub fn from_rules(rules: &ExitRules) -> Result<Self> {
+        if let Some(percent) = rules.take_profit_fee_percent {
+            if !percent.is_finite() || percent <= 0.0 {
+                return Err(anyhow!("exit_rules.take_profit_fee_percent must be a positive number, got {}", percent));
+            }
+        }
+        if let Some(percent) = rules.stop_loss_percent {
+            if !(percent > 0.0 && percent <= 100.0) {
+                return Err(anyhow!("exit_rules.stop_loss_percent must be above 0 and at most 100, got {}", percent));
+            }
+        }
+        if rules.volume_timeout_seconds == Some(0) {
+            return Err(anyhow!("exit_rules.volume_timeout_seconds must be positive"));
+        }
+        if rules.max_duration_seconds == Some(0) {
+            return Err(anyhow!("exit_rules.max_duration_seconds must be positive"));
+        }
+
+        Ok(Self {
+            take_profit_fees: rules.take_profit_fee_percent.map(|percent| percent / 100.0),
+            stop_loss: rules.stop_loss_percent.map(|percent| percent / 100.0),
+            volume_timeout: rules.volume_timeout_seconds.map(Duration::from_secs),
+            max_duration: rules.max_duration_seconds.map(Duration::from_secs),
+        })
+    }
+
+    /// The first rule that says the position should exit, with what made it fire
+    ///
+    /// `valuation` is the position's latest valuation, if it could be computed, and `idle` how
+    /// long the pool has gone without a swap.
+    pub fn check(&self, position: &Position, valuation: Option<&PositionValuation>, idle: Duration) -> Option<(ExitReason, String)> {
+        if let Some(timeout) = self.volume_timeout {
+            if idle >= timeout {
+                return Some((ExitReason::VolumeDriedUp, format!("no swaps for {}s", idle.as_secs())));
+            }
+        }
+
+        let valuation = valuation?;
+        let capital = position.sol_invested;
+
+        if let Some(take_profit) = self.take_profit_fees {
+            let fees = valuation.fees_sol();
+            if fees >= capital * take_profit {
+                return Some((ExitReason::TakeProfit, format!("fees of {:.6} SOL reached {:.1}% of {} SOL invested",
+                    fees, take_profit * 100.0, capital)));
+            }
+        }
+
+        if let Some(stop_loss) = self.stop_loss {
+            let pnl = valuation.fees_sol() + valuation.impermanent_loss_sol;
+            if pnl <= -capital * stop_loss {
+                return Some((ExitReason::StopLoss, format!("fees minus IL of {:.6} SOL hit the {:.1}% stop-loss",
+                    pnl, stop_loss * 100.0)));
+            }
+        }
+
+        None
+    }
+}
+
+/// Tracks when a pool last traded
+///
+/// Every swap pays LP fees, so a change in the pool's cumulative LP fees means it traded.
+pub struct TradeActivity {
+    lp_fees: Option<(u128, u128)>,
+    last_trade: Instant,
+}
+
+impl TradeActivity {
+    /// Start tracking, counting from `now` as if the pool had just traded
+    pub fn new(now: Instant) -> Self {
+        Self { lp_fees: None, last_trade: now }
+    }
+
+    /// Record a new state of the pool and return how long it has gone without a swap
+    pub fn observe(&mut self, now: Instant, pool_info: &PoolInfo) -> Duration {
+        let metrics = &pool_info.state.metrics;
+        let lp_fees = (metrics.total_lp_a_fee, metrics.total_lp_b_fee);
+
+        if self.lp_fees.is_some_and(|previous| previous != lp_fees) {
+            self.last_trade = now;
+        }
+        self.lp_fees = Some(lp_fees);
+
+        now.duration_since(self.last_trade)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::pubkey::Pubkey;
+    use crate::meteora::PoolState;
+
+    fn policy(rules: ExitRules) -> ExitPolicy {
+        ExitPolicy::from_rules(&rules).unwrap()
+    }
+
+    /// Valuation of a position with the given fees earned and impermanent loss, in SOL
+    fn valuation(fees_sol: f64, impermanent_loss_sol: f64) -> PositionValuation {
+        PositionValuation {
+            token_a_amount: 0,
+            token_b_amount: 0,
+            value_sol: 1.0 + impermanent_loss_sol,
+            held_value_sol: 1.0,
+            impermanent_loss_sol,
+            fees_claimed_sol: fees_sol / 2.0,
+            fees_unclaimed_sol: fees_sol / 2.0,
+            exit_value_sol: 1.0 + impermanent_loss_sol + fees_sol / 2.0,
+            profit_loss_sol: impermanent_loss_sol + fees_sol,
+        }
+    }
+
+    fn reason(policy: &ExitPolicy, valuation: Option<&PositionValuation>, idle: Duration) -> Option<ExitReason> {
+        let position = Position::new(Pubkey::new_unique(), 1.0);
+        policy.check(&position, valuation, idle).map(|(reason, _)| reason)
+    }
+
+    #[test]
+    fn takes_profit_once_fees_reach_the_target() {
+        let policy = policy(ExitRules { take_profit_fee_percent: Some(5.0), ..ExitRules::default() });
+
+        assert_eq!(reason(&policy, Some(&valuation(0.049, 0.0)), Duration::ZERO), None);
+        assert_eq!(reason(&policy, Some(&valuation(0.05, 0.0)), Duration::ZERO), Some(ExitReason::TakeProfit));
+        assert_eq!(reason(&policy, None, Duration::ZERO), None);
+    }
+
+    #[test]
+    fn stops_out_once_fees_minus_impermanent_loss_fall_below_the_limit() {
+        let policy = policy(ExitRules { stop_loss_percent: Some(10.0), ..ExitRules::default() });
+
+        // Fees make up for part of the impermanent loss
+        assert_eq!(reason(&policy, Some(&valuation(0.02, -0.11)), Duration::ZERO), None);
+        assert_eq!(reason(&policy, Some(&valuation(0.02, -0.13)), Duration::ZERO), Some(ExitReason::StopLoss));
+    }
+
+    #[test]
+    fn exits_when_volume_dries_up_even_without_a_valuation() {
+        let policy = policy(ExitRules { volume_timeout_seconds: Some(60), ..ExitRules::default() });
+
+        assert_eq!(reason(&policy, None, Duration::from_secs(59)), None);
+        assert_eq!(reason(&policy, None, Duration::from_secs(60)), Some(ExitReason::VolumeDriedUp));
+    }
+
+    #[test]
+    fn no_rules_never_exit() {
+        let policy = policy(ExitRules::default());
+
+        assert_eq!(reason(&policy, Some(&valuation(10.0, -10.0)), Duration::from_secs(3_600)), None);
+    }
+
+    #[test]
+    fn rejects_unusable_rules() {
+        let rejected = [
+            ExitRules { take_profit_fee_percent: Some(0.0), ..ExitRules::default() },
+            ExitRules { take_profit_fee_percent: Some(f64::NAN), ..ExitRules::default() },
+            ExitRules { stop_loss_percent: Some(0.0), ..ExitRules::default() },
+            ExitRules { stop_loss_percent: Some(100.5), ..ExitRules::default() },
+            ExitRules { volume_timeout_seconds: Some(0), ..ExitRules::default() },
+            ExitRules { max_duration_seconds: Some(0), ..ExitRules::default() },
+        ];
+        for rules in rejected {
+            assert!(ExitPolicy::from_rules(&rules).is_err(), "{:?}", rules);
+        }
+
+        let policy = policy(ExitRules { max_duration_seconds: Some(90), ..ExitRules::default() });
+        assert_eq!(policy.max_duration, Some(Duration::from_secs(90)));
+    }
+
+    fn pool_with_lp_fees(total_lp_a_fee: u128, total_lp_b_fee: u128) -> PoolInfo {
+        let mut state = PoolState::default();
+        state.metrics.total_lp_a_fee = total_lp_a_fee;
+        state.metrics.total_lp_b_fee = total_lp_b_fee;
+        PoolInfo::from_state(Pubkey::new_unique(), state, 0, 0, 0)
+    }
+
+    #[test]
+    fn trade_activity_resets_when_the_pool_earns_fees() {
+        let start = Instant::now();
+        let mut activity = TradeActivity::new(start);
+
+        assert_eq!(activity.observe(start + Duration::from_secs(10), &pool_with_lp_fees(5, 5)), Duration::from_secs(10));
+        assert_eq!(activity.observe(start + Duration::from_secs(20), &pool_with_lp_fees(5, 5)), Duration::from_secs(20));
+        assert_eq!(activity.observe(start + Duration::from_secs(30), &pool_with_lp_fees(5, 6)), Duration::ZERO);
+        assert_eq!(activity.observe(start + Duration::from_secs(45), &pool_with_lp_fees(5, 6)), Duration::from_secs(15));
+    }
+}
@@ -0,0 +1,153 @@
+use anyhow::Result;
+use log::{debug, warn};
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+use crate::meteora::PoolInfo;
+use crate::models::HolderDistribution;
+use crate::solana::SolanaClient;
+use crate::solana::rpc_helpers::{self, TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID};
+use crate::strategy::safety;
+
+// Number of largest holders whose combined share is reported
+pub const TOP_HOLDERS: usize = 10;
+
+// Size of an SPL token account without extensions
+const TOKEN_ACCOUNT_SIZE: u64 = 165;
+
+/// Holder distribution of the pool's non-quote tokens
+pub async fn analyze_pool_holders(client: &SolanaClient, pool_info: &PoolInfo) -> Result<Vec<HolderDistribution>> {
+    // The pool's own vaults are liquidity, not holders
+    let pool_vaults = [pool_info.token_a_vault, pool_info.token_b_vault];
+    let mut distributions = Vec::new();
+
+    for mint in [pool_info.token_a_mint, pool_info.token_b_mint] {
+        if safety::is_trusted_mint(&mint) {
+            continue;
+        }
+
+        distributions.push(analyze_holders(client, &mint, &pool_vaults).await?);
+    }
+
+    Ok(distributions)
+}
+
+/// Holder count and top holder share of one mint, ignoring the `excluded` token accounts
+pub async fn analyze_holders(client: &SolanaClient, mint: &Pubkey, excluded: &[Pubkey]) -> Result<HolderDistribution> {
+    let mint_info = rpc_helpers::get_mint_info(client, mint).await?;
+    let largest_accounts = client.get_token_largest_accounts(mint)?;
+
+    // Scanning every token account is not allowed by all RPC providers, so a failure leaves the count unknown
+    let holder_count = match count_holders(client, mint, mint_info.is_token_2022, excluded) {
+        Ok(count) => Some(count),
+        Err(e) => {
+            warn!("Failed to count holders of {}: {}", mint, e);
+            None
+        }
+    };
+
+    let distribution = HolderDistribution {
+        mint: *mint,
+        holder_count,
+        top_holders: TOP_HOLDERS,
+        top_holders_share: top_holders_share(&largest_accounts, mint_info.supply, excluded, TOP_HOLDERS),
+    };
+
+    debug!("Token {} has {:?} holders, top {} own {:.1}% of supply",
+        mint, distribution.holder_count, TOP_HOLDERS, distribution.top_holders_share * 100.0);
+    Ok(distribution)
+}
+
+/// Number of token accounts of `mint` with a non-zero balance, ignoring the `excluded` accounts
+pub fn count_holders(client: &SolanaClient, mint: &Pubkey, is_token_2022: bool, excluded: &[Pubkey]) -> Result<u64> {
+    let mut filters = vec![
+        RpcFilterType::Memcmp(Memcmp::new(0, MemcmpEncodedBytes::Base58(mint.to_string()))),
+    ];
+
+    // Token-2022 accounts grow with their extensions, so only classic accounts have a fixed size
+    let program_id = if is_token_2022 {
+        Pubkey::from_str(TOKEN_2022_PROGRAM_ID)?
+    } else {
+        filters.push(RpcFilterType::DataSize(TOKEN_ACCOUNT_SIZE));
+        Pubkey::from_str(TOKEN_PROGRAM_ID)?
+    };
+
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters),
+        account_config: RpcAccountInfoConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..RpcAccountInfoConfig::default()
+        },
+        with_context: None,
+    };
+
+    let accounts = client.get_program_accounts_with_config(&program_id, config)?;
+
+    Ok(count_funded_accounts(&accounts, excluded))
+}
+
+/// Number of token accounts with a non-zero balance, ignoring the `excluded` accounts
+pub fn count_funded_accounts(accounts: &[(Pubkey, Account)], excluded: &[Pubkey]) -> u64 {
+    accounts.iter()
+        .filter(|(address, _)| !excluded.contains(address))
+        .filter(|(_, account)| rpc_helpers::token_account_amount(&account.data).is_some_and(|amount| amount > 0))
+        .count() as u64
+}
+
+/// Share of the supply held by the `n` largest token accounts outside `excluded`
+pub fn top_holders_share(largest_accounts: &[(Pubkey, u64)], supply: u64, excluded: &[Pubkey], n: usize) -> f64 {
+    if supply == 0 {
+        return 0.0;
+    }
+
+    let mut amounts: Vec<u64> = largest_accounts.iter()
+        .filter(|(account, _)| !excluded.contains(account))
+        .map(|(_, amount)| *amount)
+        .collect();
+    amounts.sort_unstable_by(|a, b| b.cmp(a));
+
+    let held: u128 = amounts.iter().take(n).map(|amount| *amount as u128).sum();
+    held as f64 / supply as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token_account(amount: u64) -> (Pubkey, Account) {
+        let mut data = vec![0u8; TOKEN_ACCOUNT_SIZE as usize];
+        data[64..72].copy_from_slice(&amount.to_le_bytes());
+        (Pubkey::new_unique(), Account { data, ..Account::default() })
+    }
+
+    #[test]
+    fn counts_funded_accounts_outside_the_pool() {
+        let vault = token_account(900);
+        let accounts = vec![token_account(10), token_account(0), token_account(1), vault.clone()];
+        assert_eq!(count_funded_accounts(&accounts, &[]), 3);
+        assert_eq!(count_funded_accounts(&accounts, &[vault.0]), 2);
+
+        let truncated = (Pubkey::new_unique(), Account { data: vec![1u8; 70], ..Account::default() });
+        assert_eq!(count_funded_accounts(&[truncated], &[]), 0);
+    }
+
+    #[test]
+    fn top_holders_share_leaves_out_the_pool_and_keeps_the_top_n() {
+        let vault = Pubkey::new_unique();
+        let largest = [
+            (vault, 500),
+            (Pubkey::new_unique(), 100),
+            (Pubkey::new_unique(), 300),
+            (Pubkey::new_unique(), 50),
+        ];
+
+        assert_eq!(top_holders_share(&largest, 1_000, &[vault], 10), 0.45);
+        assert_eq!(top_holders_share(&largest, 1_000, &[vault], 2), 0.4);
+        assert_eq!(top_holders_share(&largest, 1_000, &[], 1), 0.5);
+        assert_eq!(top_holders_share(&largest, 0, &[vault], 10), 0.0);
+    }
+}
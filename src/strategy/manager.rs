@@ -0,0 +1,240 @@
+use anyhow::Result;
+use log::{info, warn, error};
+use solana_sdk::pubkey::Pubkey;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tokio::sync::Mutex;
+use tokio::time::{self, Duration};
+use crate::models::Pool;
+use crate::strategy::sprint::{SprintStrategy, PositionHandle};
+
+/// An open sprint and the capital committed to it
+struct OpenPosition {
+    handle: PositionHandle,
+    amount_sol: f64,
+}
+
+/// Portfolio limits a new position has to fit in
+#[derive(Debug, Clone, Copy)]
+struct PositionLimits {
+    min_sol_per_position: f64,
+    max_sol_per_position: f64,
+    max_concurrent_positions: usize,
+    max_total_sol_at_risk: f64,
+}
+
+/// Why a pool was not given a position
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Skip {
+    ShuttingDown,
+    AlreadyOpen,
+    MaxConcurrent,
+    /// Too little is left under the total at risk, which is the amount given
+    AtRisk(f64),
+}
+
+impl PositionLimits {
+    /// SOL to commit to a new position in `pool`, given the pool and amount of each open position
+    fn size(&self, pool: &Pubkey, open: &[(Pubkey, f64)], closed: bool) -> Result<f64, Skip> {
+        if closed {
+            return Err(Skip::ShuttingDown);
+        }
+        if open.iter().any(|(open_pool, _)| open_pool == pool) {
+            return Err(Skip::AlreadyOpen);
+        }
+        if open.len() >= self.max_concurrent_positions {
+            return Err(Skip::MaxConcurrent);
+        }
+
+        let at_risk: f64 = open.iter().map(|(_, amount_sol)| amount_sol).sum();
+        let amount_sol = self.max_sol_per_position.min(self.max_total_sol_at_risk - at_risk);
+        // What is left under the limit may be too little to be worth a position
+        if amount_sol <= 0.0 || amount_sol < self.min_sol_per_position {
+            return Err(Skip::AtRisk(at_risk));
+        }
+
+        Ok(amount_sol)
+    }
+}
+
+/// Hands qualifying pools to the sprint strategy while enforcing portfolio limits
+pub struct PositionManager {
+    strategy: SprintStrategy,
+    limits: PositionLimits,
+    open_positions: Mutex<Vec<OpenPosition>>,
+    /// Set once shutdown starts, after which no new sprints are opened
+    closed: AtomicBool,
+}
+
+impl PositionManager {
+    /// Create a new position manager
+    pub fn new(
+        strategy: SprintStrategy,
+        min_sol_per_position: f64,
+        max_sol_per_position: f64,
+        max_concurrent_positions: usize,
+        max_total_sol_at_risk: f64,
+    ) -> Self {
+        Self {
+            strategy,
+            limits: PositionLimits {
+                min_sol_per_position,
+                max_sol_per_position,
+                max_concurrent_positions,
+                max_total_sol_at_risk,
+            },
+            open_positions: Mutex::new(Vec::new()),
+            closed: AtomicBool::new(false),
+        }
+    }
+
+    /// Start a sprint in the pool if the portfolio limits allow it
+    ///
+    /// Returns the amount of SOL committed, or `None` if the pool was skipped.
+    pub async fn open_position(&self, pool: Pool) -> Result<Option<f64>> {
+        let mut open_positions = self.open_positions.lock().await;
+        let closed = self.closed.load(Ordering::SeqCst);
+        if !closed {
+            Self::prune_finished(&mut open_positions).await;
+        }
+
+        let open: Vec<(Pubkey, f64)> = open_positions.iter()
+            .map(|p| (p.handle.pool(), p.amount_sol))
+            .collect();
+        let amount_sol = match self.limits.size(&pool.address, &open, closed) {
+            Ok(amount_sol) => amount_sol,
+            Err(skip) => {
+                match skip {
+                    Skip::ShuttingDown => info!("Shutting down, not opening a position in pool {}", pool.address),
+                    Skip::AlreadyOpen => info!("Already sprinting in pool {}, skipping", pool.address),
+                    Skip::MaxConcurrent => info!("Maximum of {} concurrent positions reached, skipping pool {}",
+                        self.limits.max_concurrent_positions, pool.address),
+                    Skip::AtRisk(at_risk) => info!("{:.4} SOL already at risk (limit {:.4}, positions of at least {:.4}), skipping pool {}",
+                        at_risk, self.limits.max_total_sol_at_risk, self.limits.min_sol_per_position, pool.address),
+                }
+                return Ok(None);
+            }
+        };
+
+        let handle = self.strategy.execute(pool, amount_sol).await?;
+        open_positions.push(OpenPosition { handle, amount_sol });
+
+        Ok(Some(amount_sol))
+    }
+
+    /// Resume or exit positions left open by a previous run, returning how many are being tracked
+    pub async fn recover_open_positions(&self) -> Result<usize> {
+        let recovered = self.strategy.recover_open_positions().await?;
+        let count = recovered.len();
+
+        // Recovered capital counts towards the limits even if it already exceeds them
+        let mut open_positions = self.open_positions.lock().await;
+        for (handle, amount_sol) in recovered {
+            open_positions.push(OpenPosition { handle, amount_sol });
+        }
+
+        Ok(count)
+    }
+
+    /// Number of sprints currently running
+    pub async fn open_position_count(&self) -> usize {
+        let mut open_positions = self.open_positions.lock().await;
+        Self::prune_finished(&mut open_positions).await;
+        open_positions.len()
+    }
+
+    /// Force every open sprint to exit and wait for them to unwind
+    ///
+    /// No new positions are opened once this has been called.
+    pub async fn shutdown(&self, timeout: Duration) {
+        let mut open_positions = self.open_positions.lock().await;
+        // Set under the lock, so a concurrent `open_position` either finishes first or sees it
+        self.closed.store(true, Ordering::SeqCst);
+        if open_positions.is_empty() {
+            return;
+        }
+
+        info!("Exiting {} open positions...", open_positions.len());
+        for position in open_positions.iter() {
+            position.handle.force_exit();
+        }
+
+        let unwind = async {
+            for position in open_positions.drain(..) {
+                let pool = position.handle.pool();
+                match position.handle.wait().await {
+                    Ok(p) => info!("Position in pool {} exited with status {:?}", pool, p.status),
+                    Err(e) => error!("Position in pool {} failed to exit cleanly: {}", pool, e),
+                }
+            }
+        };
+
+        if time::timeout(timeout, unwind).await.is_err() {
+            warn!("Timed out waiting for positions to exit; check the positions table for open positions");
+        }
+    }
+
+    /// Drop finished sprints from the open list, logging their outcome
+    async fn prune_finished(open_positions: &mut Vec<OpenPosition>) {
+        let mut still_open = Vec::with_capacity(open_positions.len());
+
+        for position in open_positions.drain(..) {
+            if !position.handle.is_finished() {
+                still_open.push(position);
+                continue;
+            }
+
+            let pool = position.handle.pool();
+            match position.handle.wait().await {
+                Ok(p) => info!("Sprint in pool {} finished with status {:?}", pool, p.status),
+                Err(e) => warn!("Sprint in pool {} ended with error: {}", pool, e),
+            }
+        }
+
+        *open_positions = still_open;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMITS: PositionLimits = PositionLimits {
+        min_sol_per_position: 0.05,
+        max_sol_per_position: 0.1,
+        max_concurrent_positions: 3,
+        max_total_sol_at_risk: 0.25,
+    };
+
+    #[test]
+    fn sizes_positions_at_the_per_position_maximum() {
+        assert_eq!(LIMITS.size(&Pubkey::new_unique(), &[], false), Ok(0.1));
+        assert_eq!(LIMITS.size(&Pubkey::new_unique(), &[(Pubkey::new_unique(), 0.1)], false), Ok(0.1));
+    }
+
+    #[test]
+    fn sizes_positions_to_what_is_left_under_the_total_at_risk() {
+        let open = [(Pubkey::new_unique(), 0.1), (Pubkey::new_unique(), 0.08)];
+        let amount_sol = LIMITS.size(&Pubkey::new_unique(), &open, false).unwrap();
+        assert!((amount_sol - 0.07).abs() < 1e-12, "{}", amount_sol);
+
+        // Below the per-position minimum the remainder is not worth a position
+        let open = [(Pubkey::new_unique(), 0.1), (Pubkey::new_unique(), 0.11)];
+        assert!(matches!(LIMITS.size(&Pubkey::new_unique(), &open, false), Err(Skip::AtRisk(_))));
+
+        let open = [(Pubkey::new_unique(), 0.3)];
+        assert!(matches!(LIMITS.size(&Pubkey::new_unique(), &open, false), Err(Skip::AtRisk(_))));
+    }
+
+    #[test]
+    fn refuses_past_the_concurrency_cap() {
+        let open = [(Pubkey::new_unique(), 0.01), (Pubkey::new_unique(), 0.01), (Pubkey::new_unique(), 0.01)];
+        assert_eq!(LIMITS.size(&Pubkey::new_unique(), &open, false), Err(Skip::MaxConcurrent));
+    }
+
+    #[test]
+    fn refuses_the_same_pool_twice_and_any_pool_once_closed() {
+        let pool = Pubkey::new_unique();
+        assert_eq!(LIMITS.size(&pool, &[(pool, 0.1)], false), Err(Skip::AlreadyOpen));
+        assert_eq!(LIMITS.size(&pool, &[], true), Err(Skip::ShuttingDown));
+    }
+}
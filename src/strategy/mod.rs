@@ -1,6 +1,17 @@
 mod sprint;
+mod manager;
 pub mod analysis;
+pub mod valuation;
+pub mod safety;
+pub mod holders;
+pub mod scoring;
+pub mod reputation;
+pub mod rug_guard;
+pub mod exit_policy;
 
 pub use sprint::SprintStrategy;
+pub use sprint::PositionHandle;
+pub use manager::PositionManager;
 pub use analysis::PoolAnalyzer;
-pub use analysis::PoolCriteria; 
\ No newline at end of file
+pub use analysis::PoolCriteria;
+pub use exit_policy::ExitPolicy; 
\ No newline at end of file
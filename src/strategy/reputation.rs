@@ -0,0 +1,85 @@
+use anyhow::Result;
+use log::{info, debug, warn};
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use tokio::time::{self, Duration};
+
+use crate::db::Database;
+use crate::meteora::MeteoraClient;
+use crate::models::{CreatorStats, Pool};
+use crate::utils::sol_to_lamports;
+
+/// Tracks how pool creators' launches turn out
+///
+/// Every analyzed pool is checked again once `rug_window` has passed. It counts as rugged if it
+/// was disabled or emptied, or if its TVL fell by more than `rug_tvl_drop` (0-1) since analysis,
+/// which catches both pulled liquidity and a token dumped to zero.
+#[derive(Clone)]
+pub struct CreatorReputation {
+    meteora_client: Arc<MeteoraClient>,
+    db: Database,
+    rug_window: Duration,
+    rug_tvl_drop: f64,
+}
+
+impl CreatorReputation {
+    pub fn new(meteora_client: MeteoraClient, db: Database, rug_window_minutes: u64, rug_tvl_drop_percent: f64) -> Self {
+        Self {
+            meteora_client: Arc::new(meteora_client),
+            db,
+            rug_window: Duration::from_secs(rug_window_minutes * 60),
+            rug_tvl_drop: rug_tvl_drop_percent / 100.0,
+        }
+    }
+
+    /// Track record of a creator from the pools recorded before `pool`
+    pub async fn stats(&self, creator: &Pubkey, pool: &Pubkey) -> Result<CreatorStats> {
+        let stats = self.db.get_creator_stats(creator, pool).await?;
+        debug!("Creator {} has {} pools, {} of {} checked rugged, PnL {:.4} SOL",
+            creator, stats.pools_created, stats.pools_rugged, stats.pools_checked, stats.profit_loss_sol);
+        Ok(stats)
+    }
+
+    /// Check the analyzed pool for a rug in the background once the window has passed
+    pub fn watch(&self, pool: &Pool) {
+        // Without a TVL from analysis there is nothing to compare against
+        let tvl_lamports = match pool.tvl_lamports {
+            Some(tvl) => tvl,
+            None => return,
+        };
+
+        let reputation = self.clone();
+        let address = pool.address;
+
+        tokio::spawn(async move {
+            time::sleep(reputation.rug_window).await;
+
+            match reputation.check_for_rug(&address, tvl_lamports).await {
+                Ok(rugged) => {
+                    if let Err(e) = reputation.db.set_pool_rugged(&address, rugged).await {
+                        warn!("Failed to record rug check for pool {}: {}", address, e);
+                    }
+                },
+                Err(e) => warn!("Rug check for pool {} failed: {}", address, e),
+            }
+        });
+    }
+
+    /// Whether the pool has been disabled, emptied or lost most of its TVL since analysis
+    async fn check_for_rug(&self, pool: &Pubkey, analyzed_tvl_lamports: u64) -> Result<bool> {
+        let pool_info = self.meteora_client.get_pool_info(pool).await?;
+
+        let rugged = if !pool_info.state.is_enabled() || pool_info.liquidity == 0 {
+            true
+        } else {
+            let tvl_lamports = sol_to_lamports(self.meteora_client.get_pool_tvl(&pool_info).await?);
+            (tvl_lamports as f64) < analyzed_tvl_lamports as f64 * (1.0 - self.rug_tvl_drop)
+        };
+
+        if rugged {
+            info!("Pool {} from creator {} was rugged within {} minutes",
+                pool, pool_info.creator, self.rug_window.as_secs() / 60);
+        }
+        Ok(rugged)
+    }
+}
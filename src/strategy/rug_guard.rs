@@ -0,0 +1,291 @@
+use anyhow::{Result, anyhow, Context};
+use futures::stream::{self, StreamExt};
+use log::{info, debug, warn};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use tokio::time::{Duration, Instant};
+
+use crate::config::RugGuardConfig;
+use crate::meteora::{math, MeteoraClient, PoolInfo};
+use crate::solana::SolanaClient;
+use crate::solana::rpc_helpers;
+
+// Most holder accounts watched per pool, largest first
+const MAX_WATCHED_HOLDERS: usize = 5;
+
+/// Watches a pool with an open position for signs of a rug
+///
+/// The pool account and the token accounts of the largest holders are subscribed to; every
+/// notification refetches the changed account and feeds it to a `RugDetector`.
+pub struct RugGuard {
+    ws_url: String,
+    client: SolanaClient,
+    meteora_client: Arc<MeteoraClient>,
+    settings: RugGuardConfig,
+}
+
+impl RugGuard {
+    pub fn new(rpc_url: &str, solana_client: SolanaClient, settings: RugGuardConfig) -> Self {
+        // Convert HTTP URL to WebSocket URL if needed
+        let ws_url = if rpc_url.starts_with("http") {
+            rpc_url.replace("http", "ws")
+        } else {
+            rpc_url.to_string()
+        };
+
+        Self {
+            ws_url,
+            meteora_client: Arc::new(MeteoraClient::new(solana_client.clone())),
+            client: solana_client,
+            settings,
+        }
+    }
+
+    /// Watch a SOL pool until a rug is detected, returning what was seen
+    ///
+    /// Only returns early with an error if the pool cannot be watched; otherwise it runs until
+    /// a rug is detected or the future is dropped.
+    pub async fn watch(&self, pool: Pubkey) -> Result<String> {
+        let pubsub_client = PubsubClient::new(&self.ws_url).await
+            .with_context(|| format!("Failed to connect to {}", self.ws_url))?;
+        let subscription_config = || Some(RpcAccountInfoConfig {
+            commitment: Some(CommitmentConfig::confirmed()),
+            ..RpcAccountInfoConfig::default()
+        });
+
+        let (mut pool_updates, pool_unsubscribe) = pubsub_client.account_subscribe(&pool, subscription_config()).await?;
+
+        let pool_info = self.meteora_client.get_pool_info(&pool).await?;
+        let quote_is_a = pool_info.sol_is_token_a()
+            .ok_or_else(|| anyhow!("Pool {} is not paired with SOL", pool))?;
+        let base_mint = if quote_is_a { pool_info.token_b_mint } else { pool_info.token_a_mint };
+
+        // Holder dumps are only one of the signals, so the pool is still watched if they cannot be found
+        let holders = match self.large_holders(&base_mint, &[pool_info.token_a_vault, pool_info.token_b_vault]).await {
+            Ok(holders) => holders,
+            Err(e) => {
+                warn!("Failed to find large holders of {}: {}", base_mint, e);
+                Vec::new()
+            }
+        };
+        let mut detector = RugDetector::new(self.settings.clone(), &holders);
+        if let Some(alert) = detector.on_pool(Instant::now(), &pool_info) {
+            return Ok(alert);
+        }
+
+        let mut holder_streams = Vec::new();
+        let mut holder_unsubscribes = Vec::new();
+        for (account, _) in &holders {
+            let (updates, unsubscribe) = pubsub_client.account_subscribe(account, subscription_config()).await?;
+            let account = *account;
+            holder_streams.push(updates.map(move |_| account));
+            holder_unsubscribes.push(unsubscribe);
+        }
+        let mut holder_updates = stream::select_all(holder_streams);
+
+        info!("Watching pool {} for rugs ({} large holders)", pool, holders.len());
+
+        let alert = loop {
+            tokio::select! {
+                update = pool_updates.next() => {
+                    if update.is_none() {
+                        break Err(anyhow!("Account subscription for pool {} closed", pool));
+                    }
+                    match self.meteora_client.get_pool_info(&pool).await {
+                        Ok(info) => if let Some(alert) = detector.on_pool(Instant::now(), &info) {
+                            break Ok(alert);
+                        },
+                        Err(e) => warn!("Failed to refresh pool {} for rug check: {}", pool, e),
+                    }
+                },
+                Some(account) = holder_updates.next() => {
+                    match self.client.get_account(&account).map(|a| rpc_helpers::token_account_amount(&a.data)) {
+                        Ok(Some(balance)) => if let Some(alert) = detector.on_holder(&account, balance) {
+                            break Ok(alert);
+                        },
+                        Ok(None) => debug!("Holder account {} is no longer a token account", account),
+                        Err(e) => warn!("Failed to refresh holder {} for rug check: {}", account, e),
+                    }
+                },
+            }
+        };
+
+        drop(holder_updates);
+        for unsubscribe in holder_unsubscribes {
+            unsubscribe().await;
+        }
+        pool_unsubscribe().await;
+
+        alert
+    }
+
+    /// Token accounts outside the pool holding at least the configured share of the supply
+    async fn large_holders(&self, mint: &Pubkey, excluded: &[Pubkey]) -> Result<Vec<(Pubkey, u64)>> {
+        let supply = rpc_helpers::get_mint_info(&self.client, mint).await?.supply;
+        let min_amount = supply as f64 * self.settings.holder_min_share_percent / 100.0;
+
+        let mut holders: Vec<(Pubkey, u64)> = self.client.get_token_largest_accounts(mint)?
+            .into_iter()
+            .filter(|(account, amount)| !excluded.contains(account) && *amount > 0 && *amount as f64 >= min_amount)
+            .collect();
+        holders.sort_unstable_by_key(|(_, amount)| std::cmp::Reverse(*amount));
+        holders.truncate(MAX_WATCHED_HOLDERS);
+
+        Ok(holders)
+    }
+}
+
+/// Decides from successive pool and holder states whether a pool is being rugged
+pub struct RugDetector {
+    settings: RugGuardConfig,
+    /// SOL reserve over the drop window, oldest first
+    quote_reserves: VecDeque<(Instant, u64)>,
+    /// Highest price of the token in SOL seen so far
+    peak_price: f64,
+    /// Balance of each watched holder when watching started
+    holder_balances: HashMap<Pubkey, u64>,
+}
+
+impl RugDetector {
+    pub fn new(settings: RugGuardConfig, holders: &[(Pubkey, u64)]) -> Self {
+        Self {
+            settings,
+            quote_reserves: VecDeque::new(),
+            peak_price: 0.0,
+            holder_balances: holders.iter().copied().collect(),
+        }
+    }
+
+    /// Check a new state of the pool, returning an alert if its SOL reserve or token price collapsed
+    pub fn on_pool(&mut self, now: Instant, pool_info: &PoolInfo) -> Option<String> {
+        if !pool_info.state.is_enabled() {
+            return Some("pool was disabled".to_string());
+        }
+
+        let quote_is_a = pool_info.sol_is_token_a()?;
+        let quote_reserve = if quote_is_a { pool_info.token_a_amount } else { pool_info.token_b_amount };
+
+        // Raw token B per raw token A; only the relative change matters
+        let raw_price = math::sqrt_price_to_price(pool_info.sqrt_price);
+        let price = if quote_is_a && raw_price > 0.0 { 1.0 / raw_price } else { raw_price };
+
+        self.check_quote_reserve(now, quote_reserve)
+            .or_else(|| self.check_price(price))
+    }
+
+    /// Check a watched holder's new balance, returning an alert if they sold off most of it
+    pub fn on_holder(&mut self, account: &Pubkey, balance: u64) -> Option<String> {
+        let initial = *self.holder_balances.get(account)?;
+        let sold = initial.saturating_sub(balance) as f64 / initial as f64;
+
+        if sold * 100.0 > self.settings.holder_dump_percent {
+            return Some(format!("holder {} moved {:.0}% of their {} tokens", account, sold * 100.0, initial));
+        }
+        None
+    }
+
+    fn check_quote_reserve(&mut self, now: Instant, reserve: u64) -> Option<String> {
+        let window = Duration::from_secs(self.settings.quote_drop_window_seconds);
+        while self.quote_reserves.front().is_some_and(|(at, _)| now.duration_since(*at) > window) {
+            self.quote_reserves.pop_front();
+        }
+        self.quote_reserves.push_back((now, reserve));
+
+        let highest = self.quote_reserves.iter().map(|(_, reserve)| *reserve).max().unwrap_or(reserve);
+        if highest == 0 {
+            return None;
+        }
+
+        let drop = 1.0 - reserve as f64 / highest as f64;
+        if drop * 100.0 > self.settings.quote_drop_percent {
+            return Some(format!("SOL reserve fell {:.0}% within {}s ({} -> {} lamports)",
+                drop * 100.0, window.as_secs(), highest, reserve));
+        }
+        None
+    }
+
+    fn check_price(&mut self, price: f64) -> Option<String> {
+        if !price.is_finite() || price <= 0.0 {
+            return Some("token price collapsed to zero".to_string());
+        }
+
+        self.peak_price = self.peak_price.max(price);
+        let drop = 1.0 - price / self.peak_price;
+        if drop * 100.0 > self.settings.price_drop_percent {
+            return Some(format!("token price fell {:.0}% from its peak", drop * 100.0));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meteora::fixtures::{self, SQRT_PRICE};
+
+    /// A token / SOL pool holding `sol_reserve` lamports at the given sqrt price
+    fn pool(sol_reserve: u64, sqrt_price: u128) -> PoolInfo {
+        let state = fixtures::sol_pool_state(sqrt_price, 0);
+        PoolInfo::from_state(Pubkey::new_unique(), state, 1_000_000_000_000, sol_reserve, 0)
+    }
+
+    /// Detector with the default thresholds: 30% of the SOL reserve within 60s, 50% of the price
+    /// from its peak and 50% of a holder's balance
+    fn detector(holders: &[(Pubkey, u64)]) -> RugDetector {
+        RugDetector::new(RugGuardConfig::default(), holders)
+    }
+
+    #[test]
+    fn fires_when_the_sol_reserve_drops_within_the_window() {
+        let start = Instant::now();
+        let mut detector = detector(&[]);
+
+        assert_eq!(detector.on_pool(start, &pool(100_000_000_000, SQRT_PRICE)), None);
+        assert_eq!(detector.on_pool(start + Duration::from_secs(20), &pool(80_000_000_000, SQRT_PRICE)), None);
+        let alert = detector.on_pool(start + Duration::from_secs(40), &pool(60_000_000_000, SQRT_PRICE));
+        assert!(alert.unwrap().contains("SOL reserve fell 40%"));
+    }
+
+    #[test]
+    fn ignores_reserve_drops_spread_beyond_the_window() {
+        let start = Instant::now();
+        let mut detector = detector(&[]);
+
+        assert_eq!(detector.on_pool(start, &pool(100_000_000_000, SQRT_PRICE)), None);
+        // The 100 SOL sample has aged out by the time the reserve is at 60
+        assert_eq!(detector.on_pool(start + Duration::from_secs(61), &pool(60_000_000_000, SQRT_PRICE)), None);
+        // 60 -> 50 SOL within the window is only a 17% drop
+        assert_eq!(detector.on_pool(start + Duration::from_secs(90), &pool(50_000_000_000, SQRT_PRICE)), None);
+    }
+
+    #[test]
+    fn fires_when_the_price_falls_from_its_peak() {
+        let start = Instant::now();
+        let mut detector = detector(&[]);
+        let reserve = 100_000_000_000;
+
+        assert_eq!(detector.on_pool(start, &pool(reserve, SQRT_PRICE)), None);
+        // The peak moves up with the price
+        assert_eq!(detector.on_pool(start, &pool(reserve, SQRT_PRICE * 2)), None);
+        // Price is the square of the sqrt price: 0.72² of the peak is a 48% drop, 0.70² a 51% drop
+        assert_eq!(detector.on_pool(start, &pool(reserve, SQRT_PRICE * 2 * 72 / 100)), None);
+        let alert = detector.on_pool(start, &pool(reserve, SQRT_PRICE * 2 * 70 / 100));
+        assert!(alert.unwrap().contains("token price fell 51% from its peak"));
+    }
+
+    #[test]
+    fn fires_when_a_watched_holder_dumps() {
+        let holder = Pubkey::new_unique();
+        let mut detector = detector(&[(holder, 1_000)]);
+
+        assert_eq!(detector.on_holder(&holder, 1_000), None);
+        assert_eq!(detector.on_holder(&holder, 500), None);
+        assert!(detector.on_holder(&holder, 499).unwrap().contains("moved 50%"));
+        // Accounts that were never watched are ignored
+        assert_eq!(detector.on_holder(&Pubkey::new_unique(), 0), None);
+    }
+}
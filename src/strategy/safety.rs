@@ -0,0 +1,296 @@
+use anyhow::Result;
+use log::debug;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::meteora::{PoolInfo, WSOL_MINT, USDC_MINT, USDT_MINT};
+use crate::models::SafetyCheck;
+use crate::solana::SolanaClient;
+use crate::solana::rpc_helpers::{self, MintInfo, EXTENSION_TRANSFER_FEE_CONFIG, EXTENSION_PERMANENT_DELEGATE, EXTENSION_TRANSFER_HOOK};
+
+// Names of the individual checks, as stored with the pool
+pub const CHECK_MINT_AUTHORITY: &str = "mint_authority";
+pub const CHECK_FREEZE_AUTHORITY: &str = "freeze_authority";
+pub const CHECK_SUPPLY_CONCENTRATION: &str = "supply_concentration";
+pub const CHECK_TRANSFER_FEE: &str = "transfer_fee";
+pub const CHECK_TRANSFER_HOOK: &str = "transfer_hook";
+pub const CHECK_PERMANENT_DELEGATE: &str = "permanent_delegate";
+
+// Largest share of the supply a single token account outside the pool may hold
+const MAX_HOLDER_SHARE: f64 = 0.3;
+
+// Quote tokens are trusted as-is: USDC and USDT keep their freeze authorities by design
+const TRUSTED_MINTS: [&str; 3] = [WSOL_MINT, USDC_MINT, USDT_MINT];
+
+/// Run every safety check on the pool's non-quote tokens
+///
+/// Tokens may charge a transfer fee of up to `max_transfer_fee_bps`.
+pub async fn check_pool_tokens(client: &SolanaClient, pool_info: &PoolInfo, max_transfer_fee_bps: u16) -> Result<Vec<SafetyCheck>> {
+    // The pool's own vaults are expected to hold a large share of a new token
+    let pool_vaults = [pool_info.token_a_vault, pool_info.token_b_vault];
+    let mut checks = Vec::new();
+    let mut epoch = None;
+
+    for mint in [pool_info.token_a_mint, pool_info.token_b_mint] {
+        if is_trusted_mint(&mint) {
+            continue;
+        }
+
+        let mint_info = rpc_helpers::get_mint_info(client, &mint).await?;
+
+        // Only Token-2022 fees depend on the epoch, so plain tokens skip the lookup
+        let current_epoch = match epoch {
+            Some(epoch) => epoch,
+            None if mint_info.is_token_2022 => *epoch.insert(client.get_epoch()?),
+            None => 0,
+        };
+        checks.extend(check_mint(&mint, &mint_info, current_epoch, max_transfer_fee_bps));
+
+        let largest_accounts = client.get_token_largest_accounts(&mint)?;
+        checks.push(check_supply_concentration(&mint, &mint_info, &largest_accounts, &pool_vaults));
+    }
+
+    debug!("Pool {} safety checks: {} run, {} failed",
+        pool_info.address, checks.len(), checks.iter().filter(|c| !c.passed).count());
+    Ok(checks)
+}
+
+/// Whether a mint is a well-known quote token exempt from token checks
+pub fn is_trusted_mint(mint: &Pubkey) -> bool {
+    TRUSTED_MINTS.contains(&mint.to_string().as_str())
+}
+
+/// Checks that only need the mint account, with the transfer fee taken at `epoch`
+pub fn check_mint(mint: &Pubkey, mint_info: &MintInfo, epoch: u64, max_transfer_fee_bps: u16) -> Vec<SafetyCheck> {
+    let mut checks = vec![
+        match mint_info.mint_authority {
+            Some(authority) => failed(CHECK_MINT_AUTHORITY, mint, format!("{} can mint more supply", authority)),
+            None => passed(CHECK_MINT_AUTHORITY, mint, "mint authority revoked"),
+        },
+        match mint_info.freeze_authority {
+            Some(authority) => failed(CHECK_FREEZE_AUTHORITY, mint, format!("{} can freeze token accounts", authority)),
+            None => passed(CHECK_FREEZE_AUTHORITY, mint, "freeze authority revoked"),
+        },
+    ];
+
+    if !mint_info.is_token_2022 {
+        return checks;
+    }
+
+    // The extension is often present with a zero fee, which costs nothing
+    checks.push(match mint_info.extension(EXTENSION_TRANSFER_FEE_CONFIG).map(|data| transfer_fee_bps(data, epoch)) {
+        None | Some(Some(0)) => passed(CHECK_TRANSFER_FEE, mint, "no transfer fee"),
+        Some(Some(bps)) if bps <= max_transfer_fee_bps => SafetyCheck {
+            name: CHECK_TRANSFER_FEE.to_string(),
+            mint: *mint,
+            passed: true,
+            detail: format!("transfer fee of {} bps is within the {} bps limit", bps, max_transfer_fee_bps),
+        },
+        Some(Some(bps)) => failed(CHECK_TRANSFER_FEE, mint,
+            format!("transfer fee of {} bps is above the {} bps limit", bps, max_transfer_fee_bps)),
+        Some(None) => failed(CHECK_TRANSFER_FEE, mint, "transfer fee config is unreadable".to_string()),
+    });
+
+    // Both extensions may be present but unset, which leaves the token unaffected
+    checks.push(match mint_info.extension(EXTENSION_TRANSFER_HOOK).and_then(|data| pubkey_at(data, 32)) {
+        Some(program) => failed(CHECK_TRANSFER_HOOK, mint, format!("transfers invoke program {}", program)),
+        None => passed(CHECK_TRANSFER_HOOK, mint, "no transfer hook"),
+    });
+
+    checks.push(match mint_info.extension(EXTENSION_PERMANENT_DELEGATE).and_then(|data| pubkey_at(data, 0)) {
+        Some(delegate) => failed(CHECK_PERMANENT_DELEGATE, mint, format!("{} can move any holder's tokens", delegate)),
+        None => passed(CHECK_PERMANENT_DELEGATE, mint, "no permanent delegate"),
+    });
+
+    checks
+}
+
+/// Check that no single token account outside the pool holds too much of the supply
+pub fn check_supply_concentration(
+    mint: &Pubkey,
+    mint_info: &MintInfo,
+    largest_accounts: &[(Pubkey, u64)],
+    excluded: &[Pubkey],
+) -> SafetyCheck {
+    if mint_info.supply == 0 {
+        return failed(CHECK_SUPPLY_CONCENTRATION, mint, "token has no supply".to_string());
+    }
+
+    let largest = largest_accounts.iter()
+        .filter(|(account, _)| !excluded.contains(account))
+        .max_by_key(|(_, amount)| *amount);
+
+    match largest {
+        Some((account, amount)) => {
+            let share = *amount as f64 / mint_info.supply as f64;
+            let detail = format!("largest holder {} owns {:.1}% of supply", account, share * 100.0);
+            if share > MAX_HOLDER_SHARE {
+                failed(CHECK_SUPPLY_CONCENTRATION, mint, detail)
+            } else {
+                SafetyCheck { name: CHECK_SUPPLY_CONCENTRATION.to_string(), mint: *mint, passed: true, detail }
+            }
+        },
+        None => passed(CHECK_SUPPLY_CONCENTRATION, mint, "all supply is in the pool"),
+    }
+}
+
+fn passed(name: &str, mint: &Pubkey, detail: &str) -> SafetyCheck {
+    SafetyCheck { name: name.to_string(), mint: *mint, passed: true, detail: detail.to_string() }
+}
+
+fn failed(name: &str, mint: &Pubkey, detail: String) -> SafetyCheck {
+    SafetyCheck { name: name.to_string(), mint: *mint, passed: false, detail }
+}
+
+/// Non-default pubkey stored at `offset` in extension data
+fn pubkey_at(data: &[u8], offset: usize) -> Option<Pubkey> {
+    let key: [u8; 32] = data.get(offset..offset + 32)?.try_into().ok()?;
+    let key = Pubkey::new_from_array(key);
+    (key != Pubkey::default()).then_some(key)
+}
+
+/// Transfer fee a `TransferFeeConfig` extension charges at `epoch`, in basis points
+///
+/// Until the newer fee takes effect the older one applies, but a scheduled raise already counts.
+fn transfer_fee_bps(data: &[u8], epoch: u64) -> Option<u16> {
+    // Two authorities and the withheld amount, then two (epoch, maximum fee, basis points) entries
+    let older = u16::from_le_bytes(data.get(88..90)?.try_into().ok()?);
+    let newer_epoch = u64::from_le_bytes(data.get(90..98)?.try_into().ok()?);
+    let newer = u16::from_le_bytes(data.get(106..108)?.try_into().ok()?);
+
+    if epoch >= newer_epoch {
+        Some(newer)
+    } else {
+        Some(older.max(newer))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `TransferFeeConfig` extension data with the older fee and a newer one starting at `newer_epoch`
+    fn transfer_fee_config(older_bps: u16, newer_epoch: u64, newer_bps: u16) -> Vec<u8> {
+        let mut data = vec![0u8; 108];
+        data[88..90].copy_from_slice(&older_bps.to_le_bytes());
+        data[90..98].copy_from_slice(&newer_epoch.to_le_bytes());
+        data[106..108].copy_from_slice(&newer_bps.to_le_bytes());
+        data
+    }
+
+    fn token_2022_mint(extensions: Vec<(u16, Vec<u8>)>) -> MintInfo {
+        MintInfo {
+            mint_authority: None,
+            supply: 1_000_000,
+            decimals: 6,
+            freeze_authority: None,
+            is_token_2022: true,
+            extensions,
+        }
+    }
+
+    fn transfer_fee_check(mint_info: &MintInfo, epoch: u64, max_bps: u16) -> SafetyCheck {
+        check_mint(&Pubkey::new_unique(), mint_info, epoch, max_bps).into_iter()
+            .find(|check| check.name == CHECK_TRANSFER_FEE)
+            .unwrap()
+    }
+
+    #[test]
+    fn transfer_fee_follows_the_epoch() {
+        let lowered = transfer_fee_config(500, 600, 0);
+        assert_eq!(transfer_fee_bps(&lowered, 599), Some(500));
+        assert_eq!(transfer_fee_bps(&lowered, 600), Some(0));
+
+        // A raise counts as soon as it is scheduled
+        let raised = transfer_fee_config(0, 600, 300);
+        assert_eq!(transfer_fee_bps(&raised, 599), Some(300));
+
+        assert_eq!(transfer_fee_bps(&[0u8; 100], 0), None);
+    }
+
+    #[test]
+    fn transfer_fee_fails_only_above_the_limit() {
+        let unset = token_2022_mint(vec![(EXTENSION_TRANSFER_FEE_CONFIG, transfer_fee_config(0, 0, 0))]);
+        assert!(transfer_fee_check(&unset, 700, 0).passed);
+
+        let charging = token_2022_mint(vec![(EXTENSION_TRANSFER_FEE_CONFIG, transfer_fee_config(0, 0, 100))]);
+        assert!(!transfer_fee_check(&charging, 700, 0).passed);
+        assert!(transfer_fee_check(&charging, 700, 100).passed);
+        assert!(!transfer_fee_check(&charging, 700, 99).passed);
+
+        let truncated = token_2022_mint(vec![(EXTENSION_TRANSFER_FEE_CONFIG, vec![0u8; 40])]);
+        assert!(!transfer_fee_check(&truncated, 700, 10_000).passed);
+
+        assert!(transfer_fee_check(&token_2022_mint(Vec::new()), 700, 0).passed);
+    }
+
+    #[test]
+    fn authorities_fail_until_revoked() {
+        let authority = Pubkey::new_unique();
+        let mint_info = MintInfo {
+            mint_authority: Some(authority),
+            supply: 1_000_000,
+            decimals: 6,
+            freeze_authority: None,
+            is_token_2022: false,
+            extensions: Vec::new(),
+        };
+        let checks = check_mint(&Pubkey::new_unique(), &mint_info, 0, 0);
+        assert_eq!(checks.len(), 2);
+        assert!(!checks.iter().find(|c| c.name == CHECK_MINT_AUTHORITY).unwrap().passed);
+        assert!(checks.iter().find(|c| c.name == CHECK_FREEZE_AUTHORITY).unwrap().passed);
+
+        let mint_info = MintInfo { mint_authority: None, freeze_authority: Some(authority), ..mint_info };
+        let checks = check_mint(&Pubkey::new_unique(), &mint_info, 0, 0);
+        assert!(checks.iter().find(|c| c.name == CHECK_MINT_AUTHORITY).unwrap().passed);
+        assert!(!checks.iter().find(|c| c.name == CHECK_FREEZE_AUTHORITY).unwrap().passed);
+    }
+
+    #[test]
+    fn hook_and_delegate_fail_only_when_set() {
+        let check = |mint_info: &MintInfo, name: &str| {
+            check_mint(&Pubkey::new_unique(), mint_info, 0, 0).into_iter()
+                .find(|check| check.name == name)
+                .unwrap()
+                .passed
+        };
+
+        // Hook data is the hook authority then the program; delegate data is the delegate
+        let unset = token_2022_mint(vec![
+            (EXTENSION_TRANSFER_HOOK, vec![1u8; 32].into_iter().chain([0u8; 32]).collect()),
+            (EXTENSION_PERMANENT_DELEGATE, vec![0u8; 32]),
+        ]);
+        assert!(check(&unset, CHECK_TRANSFER_HOOK));
+        assert!(check(&unset, CHECK_PERMANENT_DELEGATE));
+
+        let set = token_2022_mint(vec![
+            (EXTENSION_TRANSFER_HOOK, vec![0u8; 32].into_iter().chain([1u8; 32]).collect()),
+            (EXTENSION_PERMANENT_DELEGATE, vec![1u8; 32]),
+        ]);
+        assert!(!check(&set, CHECK_TRANSFER_HOOK));
+        assert!(!check(&set, CHECK_PERMANENT_DELEGATE));
+
+        let absent = token_2022_mint(Vec::new());
+        assert!(check(&absent, CHECK_TRANSFER_HOOK));
+        assert!(check(&absent, CHECK_PERMANENT_DELEGATE));
+    }
+
+    #[test]
+    fn supply_concentration_leaves_out_the_pool_vaults() {
+        let mint = Pubkey::new_unique();
+        let mint_info = token_2022_mint(Vec::new());
+        let vault = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+
+        let accounts = [(vault, 900_000), (holder, 50_000)];
+        assert!(check_supply_concentration(&mint, &mint_info, &accounts, &[vault]).passed);
+        assert!(!check_supply_concentration(&mint, &mint_info, &accounts, &[]).passed);
+
+        let whale = [(vault, 600_000), (holder, 300_001)];
+        assert!(!check_supply_concentration(&mint, &mint_info, &whale, &[vault]).passed);
+
+        assert!(check_supply_concentration(&mint, &mint_info, &[(vault, 1_000_000)], &[vault]).passed);
+
+        let empty = MintInfo { supply: 0, ..mint_info };
+        assert!(!check_supply_concentration(&mint, &empty, &[], &[vault]).passed);
+    }
+}
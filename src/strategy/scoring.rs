@@ -0,0 +1,470 @@
+use anyhow::{Result, anyhow};
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::meteora::{PoolInfo, estimate_fee_apy};
+use crate::models::{CreatorStats, Pool, ScoreFactor};
+
+// Names of the built-in scorers, as used for their weights in the config
+pub const SCORER_LIQUIDITY: &str = "liquidity";
+pub const SCORER_BALANCE: &str = "balance";
+pub const SCORER_YIELD: &str = "yield";
+pub const SCORER_SAFETY: &str = "safety";
+pub const SCORER_AGE: &str = "age";
+pub const SCORER_CREATOR: &str = "creator";
+
+pub const SCORER_NAMES: [&str; 6] = [
+    SCORER_LIQUIDITY, SCORER_BALANCE, SCORER_YIELD, SCORER_SAFETY, SCORER_AGE, SCORER_CREATOR,
+];
+
+/// Everything a scorer may look at
+pub struct ScoringContext<'a> {
+    pub pool_info: &'a PoolInfo,
+    /// Total value locked in SOL
+    pub tvl: f64,
+    /// The analyzed pool with its safety checks and holders; absent when replaying recorded states
+    pub pool: Option<&'a Pool>,
+    /// Track record of the pool's creator, if they created pools before
+    pub creator_stats: Option<&'a CreatorStats>,
+}
+
+impl<'a> ScoringContext<'a> {
+    /// Context with only the on-chain pool state, as available to backtests
+    pub fn from_pool_info(pool_info: &'a PoolInfo, tvl: f64) -> Self {
+        Self { pool_info, tvl, pool: None, creator_stats: None }
+    }
+}
+
+/// One factor of a pool's score
+pub trait Scorer: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Score between 0 and 1, or `None` if the context lacks the data this factor needs
+    fn score(&self, context: &ScoringContext) -> Option<f64>;
+}
+
+/// Scores by TVL: up to 0.5 for 0-10 SOL, rising to 1.0 at 100 SOL
+pub struct LiquidityScorer;
+
+impl Scorer for LiquidityScorer {
+    fn name(&self) -> &str {
+        SCORER_LIQUIDITY
+    }
+
+    fn score(&self, context: &ScoringContext) -> Option<f64> {
+        let tvl = context.tvl;
+        let score = if tvl <= 10.0 {
+            tvl / 10.0 * 0.5
+        } else if tvl <= 100.0 {
+            0.5 + (tvl - 10.0) / 90.0 * 0.5
+        } else {
+            1.0
+        };
+        Some(score)
+    }
+}
+
+/// Scores how evenly the raw reserves are split between the two tokens
+pub struct BalanceScorer;
+
+impl Scorer for BalanceScorer {
+    fn name(&self) -> &str {
+        SCORER_BALANCE
+    }
+
+    fn score(&self, context: &ScoringContext) -> Option<f64> {
+        let a = context.pool_info.token_a_amount as f64;
+        let b = context.pool_info.token_b_amount as f64;
+        if a + b == 0.0 {
+            return Some(0.0);
+        }
+
+        // 1.0 = perfectly balanced, 0.0 = all in one token
+        let a_fraction = a / (a + b);
+        Some(if a_fraction <= 0.5 { a_fraction * 2.0 } else { (1.0 - a_fraction) * 2.0 })
+    }
+}
+
+/// Scores the estimated fee APY: 0-10% maps to 0-0.3, 10-50% to 0.3-0.9 and 50-100% to 0.9-1.0
+pub struct YieldScorer;
+
+impl Scorer for YieldScorer {
+    fn name(&self) -> &str {
+        SCORER_YIELD
+    }
+
+    fn score(&self, context: &ScoringContext) -> Option<f64> {
+        let fee_apy = estimate_fee_apy(context.pool_info, context.tvl);
+        let score = if fee_apy <= 0.1 {
+            fee_apy * 10.0 * 0.3
+        } else if fee_apy <= 0.5 {
+            0.3 + (fee_apy - 0.1) * (0.6 / 0.4)
+        } else if fee_apy <= 1.0 {
+            0.9 + (fee_apy - 0.5) * 0.2
+        } else {
+            1.0
+        };
+        Some(score)
+    }
+}
+
+/// Scores 0 for a failed safety check, otherwise the share of supply outside the top holders
+pub struct SafetyScorer;
+
+impl Scorer for SafetyScorer {
+    fn name(&self) -> &str {
+        SCORER_SAFETY
+    }
+
+    fn score(&self, context: &ScoringContext) -> Option<f64> {
+        let pool = context.pool?;
+        if pool.failed_safety_check().is_some() {
+            return Some(0.0);
+        }
+
+        let top_share = pool.holders.iter()
+            .map(|holders| holders.top_holders_share)
+            .fold(0.0, f64::max);
+        Some(1.0 - top_share)
+    }
+}
+
+/// Scores 1.0 for pools up to five minutes old, falling to 0 at one hour
+///
+/// Launch-hour volume is what a sprint earns fees from.
+pub struct AgeScorer;
+
+impl Scorer for AgeScorer {
+    fn name(&self) -> &str {
+        SCORER_AGE
+    }
+
+    fn score(&self, context: &ScoringContext) -> Option<f64> {
+        let age_seconds = context.pool_info.age_seconds();
+        let score = if age_seconds <= 300.0 {
+            1.0
+        } else {
+            1.0 - (age_seconds - 300.0) / 3300.0
+        };
+        Some(score)
+    }
+}
+
+/// Scores the creator's share of checked pools that did not rug, nudged by our PnL on their pools
+pub struct CreatorScorer;
+
+impl Scorer for CreatorScorer {
+    fn name(&self) -> &str {
+        SCORER_CREATOR
+    }
+
+    fn score(&self, context: &ScoringContext) -> Option<f64> {
+        let stats = context.creator_stats?;
+        if stats.pools_checked == 0 {
+            return None;
+        }
+
+        let clean_rate = 1.0 - stats.pools_rugged as f64 / stats.pools_checked as f64;
+        let pnl_adjustment = if stats.profit_loss_sol > 0.0 {
+            0.1
+        } else if stats.profit_loss_sol < 0.0 {
+            -0.1
+        } else {
+            0.0
+        };
+        Some(clean_rate + pnl_adjustment)
+    }
+}
+
+/// Look up a built-in scorer by its config name
+pub fn scorer_by_name(name: &str) -> Option<Box<dyn Scorer>> {
+    let scorer: Box<dyn Scorer> = match name {
+        SCORER_LIQUIDITY => Box::new(LiquidityScorer),
+        SCORER_BALANCE => Box::new(BalanceScorer),
+        SCORER_YIELD => Box::new(YieldScorer),
+        SCORER_SAFETY => Box::new(SafetyScorer),
+        SCORER_AGE => Box::new(AgeScorer),
+        SCORER_CREATOR => Box::new(CreatorScorer),
+        _ => return None,
+    };
+    Some(scorer)
+}
+
+// How far the configured weights may add up from 1 to allow for decimal rounding
+const WEIGHT_SUM_TOLERANCE: f64 = 1e-6;
+
+/// Default weight of each built-in scorer, adding up to 1
+pub fn default_weights() -> BTreeMap<String, f64> {
+    [
+        (SCORER_LIQUIDITY, 0.3),
+        (SCORER_BALANCE, 0.1),
+        (SCORER_YIELD, 0.3),
+        (SCORER_SAFETY, 0.1),
+        (SCORER_AGE, 0.1),
+        (SCORER_CREATOR, 0.1),
+    ]
+    .into_iter()
+    .map(|(name, weight)| (name.to_string(), weight))
+    .collect()
+}
+
+/// Weighted set of scorers that produces a pool's score
+///
+/// The score is the weighted average of the factors that could be computed, so a factor
+/// without data (e.g. an unknown creator) neither helps nor hurts the pool.
+pub struct ScoringPipeline {
+    scorers: Vec<(Box<dyn Scorer>, f64)>,
+}
+
+impl ScoringPipeline {
+    /// An empty pipeline, to be filled with `with_scorer`
+    pub fn new() -> Self {
+        Self { scorers: Vec::new() }
+    }
+
+    /// Build the pipeline from scorer weights keyed by name, as in the config
+    ///
+    /// The weights must add up to 1 so each reads as the factor's share of the score.
+    pub fn from_weights(weights: &BTreeMap<String, f64>) -> Result<Self> {
+        let mut pipeline = Self::new();
+
+        for (name, weight) in weights {
+            let scorer = scorer_by_name(name).ok_or_else(|| anyhow!(
+                "Unknown scorer in score_weights: {} (expected one of {})", name, SCORER_NAMES.join(", ")
+            ))?;
+            if !weight.is_finite() || *weight < 0.0 {
+                return Err(anyhow!("Weight of scorer {} must be a non-negative number, got {}", name, weight));
+            }
+            pipeline = pipeline.with_boxed_scorer(scorer, *weight);
+        }
+
+        let total: f64 = pipeline.scorers.iter().map(|(_, weight)| weight).sum();
+        if (total - 1.0).abs() > WEIGHT_SUM_TOLERANCE {
+            return Err(anyhow!("Weights in score_weights must add up to 1, got {}", total));
+        }
+
+        Ok(pipeline)
+    }
+
+    /// Add a scorer with the given weight
+    pub fn with_scorer(self, scorer: impl Scorer + 'static, weight: f64) -> Self {
+        self.with_boxed_scorer(Box::new(scorer), weight)
+    }
+
+    fn with_boxed_scorer(mut self, scorer: Box<dyn Scorer>, weight: f64) -> Self {
+        self.scorers.push((scorer, weight));
+        self
+    }
+
+    /// Score a pool (0-1) and return the factors it is made of
+    ///
+    /// Each factor's weight is its share of the total, so the score is the sum of weight × score.
+    pub fn score(&self, context: &ScoringContext) -> (f64, Vec<ScoreFactor>) {
+        let mut factors: Vec<ScoreFactor> = self.scorers.iter()
+            .filter(|(_, weight)| *weight > 0.0)
+            .filter_map(|(scorer, weight)| scorer.score(context).map(|score| ScoreFactor {
+                name: scorer.name().to_string(),
+                score: score.clamp(0.0, 1.0),
+                weight: *weight,
+            }))
+            .collect();
+
+        let total_weight: f64 = factors.iter().map(|factor| factor.weight).sum();
+        if total_weight == 0.0 {
+            return (0.0, factors);
+        }
+
+        for factor in &mut factors {
+            factor.weight /= total_weight;
+        }
+
+        let score = factors.iter().map(|factor| factor.weight * factor.score).sum::<f64>();
+        (score.clamp(0.0, 1.0), factors)
+    }
+}
+
+impl fmt::Debug for ScoringPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.scorers.iter().map(|(scorer, weight)| (scorer.name(), weight)))
+            .finish()
+    }
+}
+
+impl Default for ScoringPipeline {
+    fn default() -> Self {
+        Self::from_weights(&default_weights()).expect("default score weights are valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::Utc;
+    use solana_sdk::pubkey::Pubkey;
+    use crate::meteora::fixtures;
+    use crate::models::{HolderDistribution, SafetyCheck};
+    use crate::models::pool::TokenInfo;
+
+    fn weights(pairs: &[(&str, f64)]) -> BTreeMap<String, f64> {
+        pairs.iter().map(|(name, weight)| (name.to_string(), *weight)).collect()
+    }
+
+    fn pool_info() -> PoolInfo {
+        fixtures::sol_pool(1_000_000_000_000 << 64, 20_000_000_000_000, 1_000_000_000_000)
+    }
+
+    fn score(scorer: &dyn Scorer, pool_info: &PoolInfo, tvl: f64) -> f64 {
+        scorer.score(&ScoringContext::from_pool_info(pool_info, tvl)).unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "{} != {}", actual, expected);
+    }
+
+    fn analyzed_pool(safety_passed: bool, top_holders_shares: &[f64]) -> Pool {
+        let mint = Pubkey::new_unique();
+        Pool {
+            address: Pubkey::new_unique(),
+            token_a: TokenInfo { mint, name: None, symbol: None, decimals: None },
+            token_b: TokenInfo { mint: Pubkey::new_unique(), name: None, symbol: None, decimals: None },
+            discovered_at: Utc::now(),
+            analyzed: true,
+            score: None,
+            score_breakdown: Vec::new(),
+            safety_checks: vec![SafetyCheck { name: "mint_authority".to_string(), mint, passed: safety_passed, detail: String::new() }],
+            tvl_lamports: None,
+            holders: top_holders_shares.iter()
+                .map(|share| HolderDistribution { mint, holder_count: None, top_holders: 10, top_holders_share: *share })
+                .collect(),
+            creator: None,
+            fee_bps: None,
+            activated_at: None,
+            creator_stats: None,
+            rugged: None,
+        }
+    }
+
+    fn creator_stats(pools_checked: u64, pools_rugged: u64, profit_loss_sol: f64) -> CreatorStats {
+        CreatorStats { creator: Pubkey::new_unique(), pools_created: pools_checked, pools_checked, pools_rugged, profit_loss_sol }
+    }
+
+    #[test]
+    fn liquidity_rises_to_one_at_100_sol() {
+        let pool_info = pool_info();
+        assert_close(score(&LiquidityScorer, &pool_info, 0.0), 0.0);
+        assert_close(score(&LiquidityScorer, &pool_info, 5.0), 0.25);
+        assert_close(score(&LiquidityScorer, &pool_info, 10.0), 0.5);
+        assert_close(score(&LiquidityScorer, &pool_info, 55.0), 0.75);
+        assert_close(score(&LiquidityScorer, &pool_info, 100.0), 1.0);
+        assert_close(score(&LiquidityScorer, &pool_info, 1_000.0), 1.0);
+    }
+
+    #[test]
+    fn balance_peaks_at_an_even_split() {
+        let mut pool_info = pool_info();
+        for (a, b, expected) in [(50, 50, 1.0), (25, 75, 0.5), (75, 25, 0.5), (100, 0, 0.0), (0, 0, 0.0)] {
+            pool_info.token_a_amount = a;
+            pool_info.token_b_amount = b;
+            assert_close(score(&BalanceScorer, &pool_info, 1.0), expected);
+        }
+    }
+
+    #[test]
+    fn yield_follows_the_fee_apy_bands() {
+        // At a 1% fee and 3.65 SOL of TVL, the APY equals the daily volume in SOL
+        let mut pool_info = pool_info();
+        pool_info.fee_rate = 100;
+        for (fee_apy, expected) in [(0.0, 0.0), (0.05, 0.15), (0.1, 0.3), (0.3, 0.6), (0.5, 0.9), (0.75, 0.95), (1.0, 1.0), (3.0, 1.0)] {
+            pool_info.volume_24h = Some((fee_apy * 1_000_000_000.0) as u64);
+            assert_close(score(&YieldScorer, &pool_info, 3.65), expected);
+        }
+    }
+
+    #[test]
+    fn age_falls_from_five_minutes_to_an_hour() {
+        let mut pool_info = pool_info();
+        pool_info.state.activation_type = 1;
+        pool_info.state.activation_point = 1_000;
+        for (age, expected) in [(0, 1.0), (300, 1.0), (1_950, 0.5), (3_600, 0.0)] {
+            pool_info.current_point = 1_000 + age;
+            assert_close(score(&AgeScorer, &pool_info, 1.0), expected);
+        }
+
+        // Past the hour the raw score goes negative and the pipeline clamps it
+        pool_info.current_point = 1_000 + 6_900;
+        assert_close(score(&AgeScorer, &pool_info, 1.0), -1.0);
+        let (total, factors) = ScoringPipeline::new().with_scorer(AgeScorer, 1.0)
+            .score(&ScoringContext::from_pool_info(&pool_info, 1.0));
+        assert_eq!((total, factors[0].score), (0.0, 0.0));
+    }
+
+    #[test]
+    fn safety_scores_supply_outside_the_top_holders() {
+        let pool_info = pool_info();
+        let safety = |pool: &Pool| SafetyScorer.score(&ScoringContext { pool: Some(pool), ..ScoringContext::from_pool_info(&pool_info, 1.0) });
+
+        assert_close(safety(&analyzed_pool(true, &[0.2, 0.4])).unwrap(), 0.6);
+        assert_close(safety(&analyzed_pool(true, &[])).unwrap(), 1.0);
+        assert_close(safety(&analyzed_pool(false, &[0.1])).unwrap(), 0.0);
+        assert_eq!(SafetyScorer.score(&ScoringContext::from_pool_info(&pool_info, 1.0)), None);
+    }
+
+    #[test]
+    fn creator_scores_clean_pools_nudged_by_pnl() {
+        let pool_info = pool_info();
+        let creator = |stats: &CreatorStats| CreatorScorer.score(&ScoringContext { creator_stats: Some(stats), ..ScoringContext::from_pool_info(&pool_info, 1.0) });
+
+        assert_close(creator(&creator_stats(4, 1, 0.0)).unwrap(), 0.75);
+        assert_close(creator(&creator_stats(4, 1, -0.5)).unwrap(), 0.65);
+        assert_eq!(creator(&creator_stats(0, 0, 1.0)), None);
+        assert_eq!(CreatorScorer.score(&ScoringContext::from_pool_info(&pool_info, 1.0)), None);
+
+        // A clean record with a profit goes past 1 until the pipeline clamps it
+        let stats = creator_stats(2, 0, 0.3);
+        assert_close(creator(&stats).unwrap(), 1.1);
+        let context = ScoringContext { creator_stats: Some(&stats), ..ScoringContext::from_pool_info(&pool_info, 1.0) };
+        let (total, factors) = ScoringPipeline::new().with_scorer(CreatorScorer, 1.0).score(&context);
+        assert_eq!((total, factors[0].score), (1.0, 1.0));
+    }
+
+    #[test]
+    fn missing_factors_are_weighted_out() {
+        let pool_info = pool_info();
+        let pipeline = ScoringPipeline::from_weights(&weights(&[
+            (SCORER_LIQUIDITY, 0.3),
+            (SCORER_BALANCE, 0.1),
+            (SCORER_SAFETY, 0.3),
+            (SCORER_CREATOR, 0.3),
+        ])).unwrap();
+
+        // Safety and creator need an analyzed pool and a creator record, which a backtest lacks
+        let (total, factors) = pipeline.score(&ScoringContext::from_pool_info(&pool_info, 55.0));
+        let names: Vec<_> = factors.iter().map(|factor| factor.name.as_str()).collect();
+        assert_eq!(names, vec![SCORER_BALANCE, SCORER_LIQUIDITY]);
+        assert_close(factors[0].weight, 0.25);
+        assert_close(factors[1].weight, 0.75);
+        let balance = score(&BalanceScorer, &pool_info, 55.0);
+        assert_close(total, 0.75 * 0.75 + 0.25 * balance);
+
+        let (total, factors) = ScoringPipeline::new().with_scorer(SafetyScorer, 1.0)
+            .score(&ScoringContext::from_pool_info(&pool_info, 55.0));
+        assert_eq!(total, 0.0);
+        assert!(factors.is_empty());
+    }
+
+    #[test]
+    fn default_weights_add_up_to_one() {
+        let total: f64 = default_weights().values().sum();
+        assert!((total - 1.0).abs() <= WEIGHT_SUM_TOLERANCE);
+        assert!(ScoringPipeline::from_weights(&default_weights()).is_ok());
+    }
+
+    #[test]
+    fn weights_not_adding_up_to_one_are_rejected() {
+        assert!(ScoringPipeline::from_weights(&weights(&[(SCORER_LIQUIDITY, 0.6), (SCORER_YIELD, 0.5)])).is_err());
+        assert!(ScoringPipeline::from_weights(&weights(&[(SCORER_LIQUIDITY, 0.0), (SCORER_YIELD, 0.0)])).is_err());
+        assert!(ScoringPipeline::from_weights(&weights(&[(SCORER_LIQUIDITY, 0.7), (SCORER_BALANCE, 0.1), (SCORER_YIELD, 0.2)])).is_ok());
+    }
+}
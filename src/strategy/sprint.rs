@@ -1,16 +1,93 @@
-use anyhow::Result;
-use tokio::time::Duration;
-use log::info;
-use crate::models::{Pool, Position, PositionStatus};
-use crate::solana::SolanaClient;
-use crate::meteora::MeteoraClient;
+use anyhow::{Result, anyhow};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{self, Duration, Instant};
+use log::{info, warn, error, debug};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use std::fmt;
+use std::sync::Arc;
+use crate::db::Database;
+use crate::models::{ExitReason, Pool, Position, PositionStatus};
+use crate::solana::{SolanaClient, WalletManager};
+use crate::meteora::{MeteoraClient, PoolInfo, SimulatedPosition, instructions};
+use crate::strategy::valuation::{self, PositionValuation};
+use crate::strategy::rug_guard::RugGuard;
+use crate::strategy::exit_policy::{ExitPolicy, TradeActivity};
+
+// How often an open position's pool is checked while waiting for the exit timer
+const MONITOR_INTERVAL: Duration = Duration::from_secs(10);
+
+// Number of attempts made to exit a position before backing off
+const MAX_EXIT_ATTEMPTS: u32 = 3;
+
+// How long to back off after a round of failed exit attempts before trying again
+const EXIT_RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+// How long to wait before asking again whether a failed entry created its position
+const ENTRY_CHECK_RETRY_INTERVAL: Duration = Duration::from_secs(10);
 
 /// Implements the "sprint" strategy for LP position management
+#[derive(Clone)]
 pub struct SprintStrategy {
-    solana_client: SolanaClient,
-    meteora_client: MeteoraClient,
+    solana_client: Arc<SolanaClient>,
+    meteora_client: Arc<MeteoraClient>,
+    wallet: Arc<WalletManager>,
+    db: Database,
     position_duration: Duration,
     fee_claim_interval: Duration,
+    slippage_bps: u16,
+    paper_trading: bool,
+    rug_guard: Option<Arc<RugGuard>>,
+    exit_policy: ExitPolicy,
+}
+
+/// Where a sprint's liquidity lives once it has been entered
+enum Holding {
+    /// A real DAMM v2 position account
+    OnChain(Pubkey),
+    /// A paper-trading position tracked in memory
+    Simulated(Box<SimulatedPosition>),
+}
+
+impl fmt::Display for Holding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Holding::OnChain(address) => write!(f, "{}", address),
+            Holding::Simulated(position) => write!(f, "paper position in {}", position.pool),
+        }
+    }
+}
+
+/// Handle to a sprint running as its own task
+pub struct PositionHandle {
+    pool: Pubkey,
+    exit_sender: watch::Sender<bool>,
+    task: JoinHandle<Result<Position>>,
+}
+
+impl PositionHandle {
+    /// Pool the position was opened in
+    pub fn pool(&self) -> Pubkey {
+        self.pool
+    }
+
+    /// Ask the sprint to exit immediately instead of waiting for its timer
+    pub fn force_exit(&self) {
+        // The receiver is gone once the sprint has finished, in which case there is nothing to exit
+        let _ = self.exit_sender.send(true);
+    }
+
+    /// Whether the sprint task has finished
+    pub fn is_finished(&self) -> bool {
+        self.task.is_finished()
+    }
+
+    /// Wait for the sprint to finish and return the final position
+    pub async fn wait(self) -> Result<Position> {
+        self.task.await.map_err(|e| anyhow!("Sprint task for pool {} panicked: {}", self.pool, e))?
+    }
 }
 
 impl SprintStrategy {
@@ -18,39 +95,532 @@ impl SprintStrategy {
     pub fn new(
         solana_client: SolanaClient,
         meteora_client: MeteoraClient,
+        wallet: Arc<WalletManager>,
+        db: Database,
         position_duration_seconds: u64,
         fee_claim_interval_seconds: u64,
+        slippage_bps: u16,
     ) -> Self {
         Self {
-            solana_client,
-            meteora_client,
+            solana_client: Arc::new(solana_client),
+            meteora_client: Arc::new(meteora_client),
+            wallet,
+            db,
             position_duration: Duration::from_secs(position_duration_seconds),
             fee_claim_interval: Duration::from_secs(fee_claim_interval_seconds),
+            slippage_bps,
+            paper_trading: false,
+            rug_guard: None,
+            exit_policy: ExitPolicy::default(),
         }
     }
-    
+
+    /// Simulate every entry, claim and exit against live pool state instead of sending transactions
+    pub fn with_paper_trading(mut self, enabled: bool) -> Self {
+        self.paper_trading = enabled;
+        self
+    }
+
+    /// Watch the pool of every open position and exit immediately when the guard detects a rug
+    pub fn with_rug_guard(mut self, rug_guard: RugGuard) -> Self {
+        self.rug_guard = Some(Arc::new(rug_guard));
+        self
+    }
+
+    /// Exit positions early when one of the policy's rules fires
+    ///
+    /// A `max_duration` shorter than the position duration cuts every sprint short.
+    pub fn with_exit_policy(mut self, exit_policy: ExitPolicy) -> Self {
+        if let Some(max_duration) = exit_policy.max_duration {
+            self.position_duration = self.position_duration.min(max_duration);
+        }
+        self.exit_policy = exit_policy;
+        self
+    }
+
     /// Execute the strategy on a pool
-    pub async fn execute(&self, pool: Pool, amount_sol: f64) -> Result<Position> {
-        info!("Starting sprint strategy for pool {} with {} SOL", pool.address, amount_sol);
-        
-        // TODO: Implement strategy
+    ///
+    /// The sprint runs as an independent task: enter, claim fees every `fee_claim_interval`,
+    /// monitor the pool and exit once `position_duration` has elapsed or an exit is forced.
+    pub async fn execute(&self, pool: Pool, amount_sol: f64) -> Result<PositionHandle> {
+        // Positions are sized and valued in the quote token, which has to be the SOL they are funded with
+        let pair = pool.pair()
+            .ok_or_else(|| anyhow!("Pool {} is not paired with a quote token", pool.address))?;
+        if pair.quote.mint != instructions::wsol_mint() {
+            return Err(anyhow!("Pool {} ({}) is not quoted in SOL, which positions are funded with", pool.address, pair));
+        }
+
+        info!("Starting sprint strategy for pool {} ({}) with {} SOL", pool.address, pair, amount_sol);
+
+        let (exit_sender, exit_receiver) = watch::channel(false);
+        let strategy = self.clone();
+        let pool_address = pool.address;
+
+        let task = tokio::spawn(async move {
+            strategy.run_sprint(pool, amount_sol, exit_receiver).await
+        });
+
+        Ok(PositionHandle {
+            pool: pool_address,
+            exit_sender,
+            task,
+        })
+    }
+
+    /// Run the full lifecycle of a single position
+    async fn run_sprint(&self, pool: Pool, amount_sol: f64, exit_receiver: watch::Receiver<bool>) -> Result<Position> {
+        let mut position = Position::new(pool.address, amount_sol);
+        position.simulated = self.paper_trading;
+        position.id = Some(self.db.save_position(&position).await?);
+
         // 1. Add liquidity
-        // 2. Schedule fee claiming
-        // 3. Monitor position
-        // 4. Exit after timer expires
-        
-        let position = Position {
-            pool: pool.address,
-            created_at: chrono::Utc::now(),
-            closed_at: None,
-            sol_invested: amount_sol,
-            fee_claimed: None,
-            profit_loss: None,
-            status: PositionStatus::Created,
-        };
-        
+        let holding = match self.enter(&pool, &mut position).await {
+            Ok(holding) => holding,
+            Err(e) => {
+                error!("Failed to enter pool {}: {}", pool.address, e);
+                if position.status != PositionStatus::Failed {
+                    self.transition(&mut position, PositionStatus::Failed).await;
+                }
+                return Err(e);
+            }
+        };
+        self.transition(&mut position, PositionStatus::Active).await;
+
+        let deadline = Instant::now() + self.position_duration;
+        self.hold_and_exit(position, holding, deadline, exit_receiver).await
+    }
+
+    /// Hold an entered position until its deadline, an exit rule fires or an exit is forced, then exit it
+    async fn hold_and_exit(
+        &self,
+        mut position: Position,
+        mut holding: Holding,
+        deadline: Instant,
+        mut exit_receiver: watch::Receiver<bool>,
+    ) -> Result<Position> {
+        let pool_address = position.pool;
+
+        // 2-3. Claim fees on schedule and monitor the position until the timer expires
+        let mut claim_timer = time::interval_at(Instant::now() + self.fee_claim_interval, self.fee_claim_interval);
+        let mut monitor_timer = time::interval_at(Instant::now() + MONITOR_INTERVAL, MONITOR_INTERVAL);
+        let mut exit_signal_open = true;
+        let mut activity = TradeActivity::new(Instant::now());
+
+        // The guard runs for the whole sprint; without one the branch stays disabled
+        let rug_guard = self.rug_guard.clone();
+        let mut rug_watch_open = rug_guard.is_some();
+        let rug_watch = async move {
+            match rug_guard {
+                Some(guard) => guard.watch(pool_address).await,
+                None => std::future::pending().await,
+            }
+        };
+        tokio::pin!(rug_watch);
+
+        let reason = loop {
+            tokio::select! {
+                _ = time::sleep_until(deadline) => {
+                    info!("Sprint timer expired for position {}", holding);
+                    break ExitReason::MaxDuration;
+                },
+                changed = exit_receiver.changed(), if exit_signal_open => {
+                    match changed {
+                        Ok(_) if *exit_receiver.borrow() => {
+                            info!("Forced exit requested for position {}", holding);
+                            break ExitReason::Forced;
+                        },
+                        Ok(_) => {},
+                        // The handle was dropped; keep running on the timer alone
+                        Err(_) => exit_signal_open = false,
+                    }
+                },
+                alert = &mut rug_watch, if rug_watch_open => {
+                    match alert {
+                        Ok(alert) => {
+                            warn!("Rug detected in pool {} ({}), exiting position {}", pool_address, alert, holding);
+                            break ExitReason::Rug;
+                        },
+                        // Keep running on the timer and the periodic pool check
+                        Err(e) => {
+                            warn!("Rug guard for pool {} stopped: {}", pool_address, e);
+                            rug_watch_open = false;
+                        },
+                    }
+                },
+                _ = claim_timer.tick() => {
+                    self.transition(&mut position, PositionStatus::ClaimingFees).await;
+                    if let Err(e) = self.claim(&mut holding, &mut position).await {
+                        warn!("Failed to claim fees for position {}: {}", holding, e);
+                    }
+                    self.transition(&mut position, PositionStatus::Active).await;
+                },
+                _ = monitor_timer.tick() => {
+                    match self.meteora_client.get_pool_info(&pool_address).await {
+                        Ok(info) if !info.state.is_enabled() => {
+                            warn!("Pool {} was disabled, exiting position {}", pool_address, holding);
+                            break ExitReason::PoolDisabled;
+                        },
+                        Ok(info) => {
+                            debug!("Position {} pool reserves: {} / {}",
+                                holding, info.token_a_amount, info.token_b_amount);
+                            let idle = activity.observe(Instant::now(), &info);
+                            let valuation = self.revalue(&holding, &mut position, &info).await;
+                            if let Some((reason, detail)) = self.exit_policy.check(&position, valuation.as_ref(), idle) {
+                                info!("Exit rule {} fired for position {}: {}", reason, holding, detail);
+                                break reason;
+                            }
+                        },
+                        Err(e) => warn!("Failed to monitor pool {}: {}", pool_address, e),
+                    }
+                },
+            }
+        };
+
+        // A position resumed mid-exit keeps the reason it was first exited for
+        if position.exit_reason.is_none() {
+            position.exit_reason = Some(reason);
+        }
+
+        // 4. Exit the position, valuing it one last time at the price it leaves at
+        match self.meteora_client.get_pool_info(&pool_address).await {
+            Ok(info) => {
+                self.revalue(&holding, &mut position, &info).await;
+            },
+            Err(e) => warn!("Failed to value position {} before exit: {}", holding, e),
+        }
+        self.transition(&mut position, PositionStatus::Exiting).await;
+        // The liquidity stays in the pool until the exit lands, so the sprint keeps retrying and its
+        // capital stays committed; if the process stops first the next start resumes the exit
+        loop {
+            match self.exit(&holding, &mut position).await {
+                Ok(_) => break,
+                Err(e) => {
+                    if let Holding::OnChain(address) = &holding {
+                        // An earlier attempt may have landed without being confirmed
+                        if let Ok(None) = self.meteora_client.get_position_and_owner(address).await {
+                            info!("Position {} no longer exists on-chain, its exit landed", address);
+                            break;
+                        }
+                    }
+                    error!("Failed to exit position {}, retrying in {}s: {}", holding, EXIT_RETRY_INTERVAL.as_secs(), e);
+                    time::sleep(EXIT_RETRY_INTERVAL).await;
+                }
+            }
+        }
+
+        position.closed_at = Some(chrono::Utc::now());
+        self.transition(&mut position, PositionStatus::Closed).await;
+        info!("Sprint completed for pool {}", pool_address);
         Ok(position)
     }
-    
-    // TODO: Add methods for liquidity management, fee claiming, and position monitoring
-} 
\ No newline at end of file
+
+    /// Reconcile positions left open by a previous run against the chain
+    ///
+    /// Positions whose account still holds liquidity for our wallet are resumed with the time
+    /// left on their sprint, or exited immediately if they are past `position_duration` or were
+    /// interrupted mid-exit.
+    /// Returns the handle of each recovered sprint with the SOL it was opened with.
+    pub async fn recover_open_positions(&self) -> Result<Vec<(PositionHandle, f64)>> {
+        let open_positions = self.db.get_open_positions().await?;
+        let mut handles = Vec::new();
+
+        for position in open_positions {
+            // Live positions are left alone while paper trading; they resume on the next live run
+            if self.paper_trading && !position.simulated {
+                warn!("Not recovering live position {:?} in pool {} while paper trading", position.id, position.pool);
+                continue;
+            }
+
+            let pool = position.pool;
+            match self.recover_position(position).await {
+                Ok(Some(recovered)) => handles.push(recovered),
+                Ok(None) => {},
+                Err(e) => error!("Failed to recover position in pool {}: {}", pool, e),
+            }
+        }
+
+        Ok(handles)
+    }
+
+    /// Reconcile a single persisted position, resuming it if it is still live
+    async fn recover_position(&self, mut position: Position) -> Result<Option<(PositionHandle, f64)>> {
+        if position.simulated {
+            // Paper positions only exist in memory, so there is nothing left to resume
+            warn!("Paper position {:?} in pool {} was interrupted, marking failed", position.id, position.pool);
+            self.transition(&mut position, PositionStatus::Failed).await;
+            return Ok(None);
+        }
+
+        let position_address = match position.position_address {
+            Some(address) => address,
+            None => {
+                // The process died before the entry confirmed, so nothing was recorded on-chain
+                warn!("Position {:?} in pool {} was never entered, marking failed", position.id, position.pool);
+                self.transition(&mut position, PositionStatus::Failed).await;
+                return Ok(None);
+            }
+        };
+
+        let (position_state, nft_owner) = match self.meteora_client.get_position_and_owner(&position_address).await? {
+            Some(found) => found,
+            None if position.status == PositionStatus::Opening => {
+                // The entry transaction never landed
+                warn!("Position {} was never created on-chain, marking failed", position_address);
+                self.transition(&mut position, PositionStatus::Failed).await;
+                return Ok(None);
+            },
+            None => {
+                info!("Position {} no longer exists on-chain, marking closed", position_address);
+                position.closed_at = Some(chrono::Utc::now());
+                self.transition(&mut position, PositionStatus::Closed).await;
+                return Ok(None);
+            }
+        };
+
+        if nft_owner != Some(self.wallet.pubkey()) {
+            warn!("Position {} is not held by wallet {}, no longer tracking it", position_address, self.wallet.pubkey());
+            self.transition(&mut position, PositionStatus::Failed).await;
+            return Ok(None);
+        }
+
+        // An interrupted exit is resumed straight away instead of waiting out the sprint
+        let remaining = if position.status == PositionStatus::Exiting {
+            Duration::ZERO
+        } else {
+            let elapsed = (chrono::Utc::now() - position.created_at).to_std().unwrap_or_default();
+            self.position_duration.saturating_sub(elapsed)
+        };
+
+        if remaining.is_zero() {
+            info!("Position {} was exiting or is past its sprint duration, exiting immediately", position_address);
+        } else {
+            info!("Resuming position {} with {}s left (liquidity {})",
+                position_address, remaining.as_secs(), position_state.total_liquidity());
+            self.transition(&mut position, PositionStatus::Active).await;
+        }
+
+        let (exit_sender, exit_receiver) = watch::channel(false);
+        let strategy = self.clone();
+        let pool = position.pool;
+        let amount_sol = position.sol_invested;
+        let deadline = Instant::now() + remaining;
+
+        let task = tokio::spawn(async move {
+            strategy.hold_and_exit(position, Holding::OnChain(position_address), deadline, exit_receiver).await
+        });
+
+        Ok(Some((PositionHandle { pool, exit_sender, task }, amount_sol)))
+    }
+
+    /// Move a position to a new status and persist it
+    async fn transition(&self, position: &mut Position, status: PositionStatus) {
+        debug!("Position in pool {}: {:?} -> {:?}", position.pool, position.status, status);
+        position.status = status;
+
+        if let Err(e) = self.db.update_position(position).await {
+            error!("Failed to persist position status for pool {}: {}", position.pool, e);
+        }
+    }
+
+    /// Mark the position to market against the pool's current state, persist its PnL and return the valuation
+    async fn revalue(&self, holding: &Holding, position: &mut Position, pool_info: &PoolInfo) -> Option<PositionValuation> {
+        let position_state = match holding {
+            Holding::OnChain(address) => match self.meteora_client.get_position_state(address).await {
+                Ok(state) => state,
+                Err(e) => {
+                    warn!("Failed to fetch position {} for valuation: {}", address, e);
+                    return None;
+                }
+            },
+            Holding::Simulated(simulated) => simulated.state.clone(),
+        };
+
+        let valuation = match valuation::value_position(pool_info, &position_state, position) {
+            Ok(valuation) => valuation,
+            Err(e) => {
+                warn!("Failed to value position {}: {}", holding, e);
+                return None;
+            }
+        };
+
+        debug!("Position {} worth {:.6} SOL (held {:.6}): IL {:.6}, fees {:.6}, PnL {:.6}",
+            holding, valuation.value_sol, valuation.held_value_sol, valuation.impermanent_loss_sol,
+            valuation.fees_sol(), valuation.profit_loss_sol);
+
+        position.profit_loss = Some(valuation.profit_loss_sol);
+        position.impermanent_loss = Some(valuation.impermanent_loss_sol);
+
+        if let Err(e) = self.db.update_position(position).await {
+            error!("Failed to persist valuation of position in pool {}: {}", position.pool, e);
+        }
+
+        Some(valuation)
+    }
+
+    /// Open a position in the pool, real or simulated
+    async fn enter(&self, pool: &Pool, position: &mut Position) -> Result<Holding> {
+        let amount_sol = position.sol_invested;
+
+        if self.paper_trading {
+            let simulated = self.meteora_client
+                .simulate_add_liquidity(pool.address, amount_sol, self.slippage_bps)
+                .await?;
+            info!("Paper traded {} SOL into pool {}", amount_sol, pool.address);
+
+            position.token_a_amount_in = Some(simulated.token_a_amount);
+            position.token_b_amount_in = Some(simulated.token_b_amount);
+            position.token_a_refund = Some(simulated.token_a_refund);
+            position.token_b_refund = Some(simulated.token_b_refund);
+            return Ok(Holding::Simulated(Box::new(simulated)));
+        }
+
+        if !self.wallet.has_sufficient_balance(amount_sol, true).await? {
+            return Err(anyhow!("Insufficient balance to add {} SOL of liquidity", amount_sol));
+        }
+
+        let entry = self.meteora_client
+            .add_liquidity(&self.wallet.pubkey(), pool.address, amount_sol, self.slippage_bps)
+            .await?;
+
+        // Record where the position will live before sending, so a crash mid-entry can't orphan it
+        position.position_address = Some(entry.position);
+        position.position_nft_mint = Some(entry.position_nft_mint.pubkey());
+        position.token_a_amount_in = Some(entry.token_a_deposit);
+        position.token_b_amount_in = Some(entry.token_b_deposit);
+        position.token_a_refund = Some(entry.token_a_refund);
+        position.token_b_refund = Some(entry.token_b_refund);
+        position.status = PositionStatus::Opening;
+        self.db.update_position(position).await?;
+
+        let signature = match self.sign_and_send(entry.transaction, &[&entry.position_nft_mint]).await {
+            Ok(signature) => signature,
+            // The send can fail after the entry landed, e.g. on a confirmation timeout, so ask the chain
+            // until it answers: the position may already hold liquidity
+            Err(e) => loop {
+                match self.meteora_client.get_position_and_owner(&entry.position).await {
+                    Ok(Some(_)) => {
+                        warn!("Entry into pool {} reported an error but position {} exists, holding it: {}",
+                            pool.address, entry.position, e);
+                        return Ok(Holding::OnChain(entry.position));
+                    },
+                    Ok(None) => {
+                        self.transition(position, PositionStatus::Failed).await;
+                        return Err(e);
+                    },
+                    Err(lookup) => {
+                        warn!("Failed to check whether position {} was created, retrying in {}s: {}",
+                            entry.position, ENTRY_CHECK_RETRY_INTERVAL.as_secs(), lookup);
+                        time::sleep(ENTRY_CHECK_RETRY_INTERVAL).await;
+                    },
+                }
+            },
+        };
+        info!("Added liquidity to pool {} (position {}): {}", pool.address, entry.position, signature);
+
+        position.entry_signature = Some(signature);
+
+        Ok(Holding::OnChain(entry.position))
+    }
+
+    /// Claim the position's fees and add the received value to the position
+    async fn claim(&self, holding: &mut Holding, position: &mut Position) -> Result<()> {
+        let position_address = match holding {
+            Holding::OnChain(address) => *address,
+            Holding::Simulated(simulated) => {
+                let claimed = self.meteora_client.simulate_claim_fees(simulated).await?;
+                position.record_fee_claim(claimed.value_sol);
+                debug!("Paper claimed fees in pool {}: {} / {} ({:.6} SOL)",
+                    position.pool, claimed.token_a_amount, claimed.token_b_amount, claimed.value_sol);
+                return Ok(());
+            }
+        };
+
+        let owner = self.wallet.pubkey();
+        let claim = self.meteora_client.claim_fees(&owner, position_address).await?;
+
+        if claim.expected_fee_a == 0 && claim.expected_fee_b == 0 {
+            debug!("No fees to claim for position {}", position_address);
+            return Ok(());
+        }
+
+        let signature = self.sign_and_send(claim.transaction.clone(), &[]).await?;
+        let claimed = self.meteora_client.record_claimed_fees(&owner, &claim, &signature).await?;
+        position.record_fee_claim(claimed.value_sol);
+
+        info!("Claimed fees for position {}: {} / {} ({:.6} SOL)",
+            position_address, claimed.token_a_amount, claimed.token_b_amount, claimed.value_sol);
+        Ok(())
+    }
+
+    /// Remove all liquidity and close the position, retrying with a fresh quote on failure
+    async fn exit(&self, holding: &Holding, position: &mut Position) -> Result<()> {
+        let position_address = match holding {
+            Holding::OnChain(address) => address,
+            Holding::Simulated(simulated) => {
+                let pool_info = self.meteora_client.get_pool_info(&simulated.pool).await?;
+                let exit = simulated.exit(&pool_info)?;
+                position.token_a_amount_out = Some(exit.token_a_amount.saturating_add(exit.fee_a));
+                position.token_b_amount_out = Some(exit.token_b_amount.saturating_add(exit.fee_b));
+                // Hypothetical PnL: what selling everything the position owns back would return plus fees claimed
+                let valuation = valuation::value_position(&pool_info, &simulated.state, position)?;
+                position.profit_loss = Some(valuation.profit_loss_sol);
+                position.impermanent_loss = Some(valuation.impermanent_loss_sol);
+                info!("Paper exited pool {} for {:.6} SOL", position.pool, valuation.exit_value_sol);
+                return Ok(());
+            }
+        };
+
+        let mut last_error = None;
+
+        for attempt in 1..=MAX_EXIT_ATTEMPTS {
+            let result = async {
+                let exit = self.meteora_client
+                    .remove_liquidity(&self.wallet.pubkey(), *position_address, self.slippage_bps)
+                    .await?;
+                let signature = self.sign_and_send(exit.transaction.clone(), &[]).await?;
+                Ok::<_, anyhow::Error>((signature, exit))
+            }.await;
+
+            match result {
+                Ok((signature, exit)) => {
+                    info!("Exited position {}: {}", position_address, signature);
+                    let (token_a_out, token_b_out) = match self.meteora_client.record_exit(&exit, &signature).await {
+                        Ok(withdrawn) => withdrawn,
+                        Err(e) => {
+                            // The exit went through; fall back to the amounts it guaranteed
+                            warn!("Failed to read the amounts withdrawn from position {}: {}", position_address, e);
+                            (exit.token_a_minimum.saturating_add(exit.fee_a), exit.token_b_minimum.saturating_add(exit.fee_b))
+                        }
+                    };
+                    position.token_a_amount_out = Some(token_a_out);
+                    position.token_b_amount_out = Some(token_b_out);
+                    position.exit_signature = Some(signature);
+                    return Ok(());
+                },
+                Err(e) => {
+                    warn!("Exit attempt {}/{} for position {} failed: {}",
+                        attempt, MAX_EXIT_ATTEMPTS, position_address, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| anyhow!("Failed to exit position {}", position_address)))
+    }
+
+    /// Sign a transaction with the wallet and any extra signers, then send and confirm it
+    ///
+    /// Confirmation blocks on the RPC client for as long as it takes, so it runs on the blocking pool.
+    async fn sign_and_send(&self, mut transaction: Transaction, extra_signers: &[&Keypair]) -> Result<String> {
+        let mut signers = vec![self.wallet.wallet().keypair()];
+        signers.extend_from_slice(extra_signers);
+
+        let blockhash = transaction.message.recent_blockhash;
+        transaction.try_sign(&signers, blockhash)?;
+
+        let solana_client = self.solana_client.clone();
+        tokio::task::spawn_blocking(move || solana_client.send_and_confirm_transaction(&transaction))
+            .await
+            .map_err(|e| anyhow!("Transaction send task panicked: {}", e))?
+    }
+}
@@ -0,0 +1,121 @@
+use anyhow::{Result, anyhow};
+
+use crate::meteora::{PoolInfo, PositionState};
+use crate::models::Position;
+
+/// Mark-to-market value of an open position, in SOL
+#[derive(Debug, Clone)]
+pub struct PositionValuation {
+    /// Token A the position's liquidity would withdraw at the current price
+    pub token_a_amount: u64,
+    /// Token B the position's liquidity would withdraw at the current price
+    pub token_b_amount: u64,
+    /// Withdrawable liquidity and the tokens left over from the entry, valued at the spot price
+    pub value_sol: f64,
+    /// Everything the entry bought valued at the spot price, i.e. what holding it instead would be worth
+    pub held_value_sol: f64,
+    /// `value_sol - held_value_sol`; negative when providing liquidity lost to holding
+    pub impermanent_loss_sol: f64,
+    pub fees_claimed_sol: f64,
+    /// Fees accrued but not yet claimed, valued at the spot price
+    pub fees_unclaimed_sol: f64,
+    /// SOL an exit right now would return, with everything the position owns sold through the pool
+    pub exit_value_sol: f64,
+    /// `exit_value_sol` plus fees already claimed, minus the SOL invested
+    pub profit_loss_sol: f64,
+}
+
+impl PositionValuation {
+    /// Fees earned so far, claimed or not
+    pub fn fees_sol(&self) -> f64 {
+        self.fees_claimed_sol + self.fees_unclaimed_sol
+    }
+
+    /// Whether fee income has made up for the impermanent loss
+    pub fn fees_cover_impermanent_loss(&self) -> bool {
+        self.fees_sol() + self.impermanent_loss_sol >= 0.0
+    }
+}
+
+/// Value a position from its entry amounts, on-chain state and the pool's current state
+///
+/// Impermanent loss compares the withdrawable liquidity against the entry amounts at the same
+/// spot price, so it is independent of fees and swap costs. Net PnL is what exiting now would
+/// actually return: the liquidity and unclaimed fees with the non-SOL side sold through the pool.
+///
+/// Both sides include the tokens the entry bought but left undeposited, since the slippage
+/// headroom means a position never deposits all of the SOL it was sized with.
+pub fn value_position(pool_info: &PoolInfo, position_state: &PositionState, position: &Position) -> Result<PositionValuation> {
+    let pool = pool_info.address;
+
+    let (token_a_in, token_b_in) = match (position.token_a_amount_in, position.token_b_amount_in) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return Err(anyhow!("Position in pool {} has no recorded entry amounts", pool)),
+    };
+
+    let (token_a_amount, token_b_amount) = pool_info.withdrawal_amounts(position_state.total_liquidity())
+        .ok_or_else(|| anyhow!("Failed to compute withdrawal for pool {}", pool))?;
+    let (fee_a, fee_b) = position_state.pending_fees(&pool_info.state);
+    let refund_a = position.token_a_refund.unwrap_or(0);
+    let refund_b = position.token_b_refund.unwrap_or(0);
+
+    let spot_value = |a: u64, b: u64| pool_info.spot_value_in_sol(a, b)
+        .ok_or_else(|| anyhow!("Pool {} is not paired with SOL", pool));
+
+    let value_sol = spot_value(token_a_amount.saturating_add(refund_a), token_b_amount.saturating_add(refund_b))?;
+    let held_value_sol = spot_value(token_a_in.saturating_add(refund_a), token_b_in.saturating_add(refund_b))?;
+    let fees_unclaimed_sol = spot_value(fee_a, fee_b)?;
+    let fees_claimed_sol = position.fee_claimed.unwrap_or(0.0);
+
+    let exit_value_sol = pool_info.sell_value_in_sol(
+        token_a_amount.saturating_add(fee_a).saturating_add(refund_a),
+        token_b_amount.saturating_add(fee_b).saturating_add(refund_b),
+    ).unwrap_or(0.0);
+
+    Ok(PositionValuation {
+        token_a_amount,
+        token_b_amount,
+        value_sol,
+        held_value_sol,
+        impermanent_loss_sol: value_sol - held_value_sol,
+        fees_claimed_sol,
+        fees_unclaimed_sol,
+        exit_value_sol,
+        profit_loss_sol: exit_value_sol + fees_claimed_sol - position.sol_invested,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::meteora::SimulatedPosition;
+    use crate::meteora::fixtures;
+
+    /// A deep SOL pool, so a small position barely moves the price
+    fn sol_pool() -> PoolInfo {
+        fixtures::sol_pool(2_000_000_000_000_000 << 64, 100_000_000_000_000_000, 100_000_000_000_000)
+    }
+
+    #[test]
+    fn fresh_position_only_loses_trading_fees() {
+        let pool_info = sol_pool();
+        let slippage_bps = 100;
+        let simulated = SimulatedPosition::open(&pool_info, 1.0, slippage_bps).unwrap();
+
+        let mut position = Position::new(pool_info.address, 1.0);
+        position.token_a_amount_in = Some(simulated.token_a_amount);
+        position.token_b_amount_in = Some(simulated.token_b_amount);
+        position.token_a_refund = Some(simulated.token_a_refund);
+        position.token_b_refund = Some(simulated.token_b_refund);
+
+        let valuation = value_position(&pool_info, &simulated.state, &position).unwrap();
+
+        // The headroom left about 1% of what was bought undeposited on both sides
+        assert!(simulated.token_a_refund > 0 && simulated.token_b_refund > 0);
+        assert!(valuation.held_value_sol > 0.99);
+        assert!(valuation.impermanent_loss_sol.abs() < 1e-6);
+        // Swapping half in and selling it back out costs about 0.25%, not the 1% headroom
+        assert!(valuation.profit_loss_sol < 0.0 && valuation.profit_loss_sol > -0.003,
+            "PnL {} includes more than trading fees", valuation.profit_loss_sol);
+    }
+}